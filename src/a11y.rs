@@ -0,0 +1,113 @@
+use crate::config::Config;
+use crate::repo_config::RepoConfig;
+use crate::store;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde_json::json;
+
+lazy_static! {
+    static ref BARE_URL: Regex = Regex::new(r"(^|\s)(https?://[^\s)<>\]]+)").unwrap();
+    static ref GITHUB_ISSUE: Regex = Regex::new(r"^https://github\.com/([\w.-]+)/([\w.-]+)/(issues|pull)/(\d+)").unwrap();
+    static ref EMPTY_ALT: Regex = Regex::new(r"!\[\s*\]\(").unwrap();
+    static ref TABLE_SEPARATOR: Regex = Regex::new(r"^\|?\s*:?-{3,}:?\s*(\|\s*:?-{3,}:?\s*)*\|?$").unwrap();
+}
+
+fn pref_key(login: &str) -> String {
+    format!("prefs:{}", login.to_lowercase())
+}
+
+/// Whether replies to `login` use the screen-reader friendly format, either because the
+/// repo enables it or the user opted in with `@flows_bot prefs accessible=on`.
+pub fn enabled(config: &Config, repo_config: &RepoConfig, login: &str) -> bool {
+    repo_config.accessible
+        || store::get(config, &pref_key(login))
+            .and_then(|v| v["accessible"].as_bool())
+            .unwrap_or(false)
+}
+
+/// Handles `@flows_bot prefs accessible=on|off`.
+pub fn set_pref(config: &Config, login: &str, args: &str) -> String {
+    match args.split_once('=').map(|(k, v)| (k.trim(), v.trim().to_lowercase())) {
+        Some(("accessible", v)) if v == "on" || v == "off" => {
+            store::set(config, &pref_key(login), json!({ "accessible": v == "on" }), None);
+            format!("@{} accessible output is now {} for your requests.", login, v)
+        }
+        _ => "Usage: `prefs accessible=on|off`".to_string(),
+    }
+}
+
+/// Describes a bare URL so screen readers announce more than the raw address.
+fn link_text(url: &str) -> String {
+    if let Some(c) = GITHUB_ISSUE.captures(url) {
+        let kind = if &c[3] == "pull" { "pull request" } else { "issue" };
+        return format!("{} {} in {}/{}", kind, &c[4], &c[1], &c[2]);
+    }
+    let host = url.split('/').nth(2).unwrap_or(url);
+    format!("link to {}", host)
+}
+
+/// Rewrites a Markdown table as a list with one item per row, each cell labeled by its
+/// column header.
+fn table_to_list(rows: &[&str]) -> Vec<String> {
+    let cells = |row: &str| {
+        row.trim()
+            .trim_matches('|')
+            .split('|')
+            .map(|c| c.trim().to_string())
+            .collect::<Vec<String>>()
+    };
+    let headers = cells(rows[0]);
+    rows[2..]
+        .iter()
+        .map(|row| {
+            let item = cells(row)
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| !v.is_empty())
+                .map(|(i, v)| match headers.get(i).filter(|h| !h.is_empty()) {
+                    Some(header) => format!("{}: {}", header, v),
+                    None => v.to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join("; ");
+            format!("* {}", item)
+        })
+        .collect()
+}
+
+/// Applies the accessible format when `accessible` is set: tables become labeled lists,
+/// bare URLs get descriptive link text and images without alt text get a placeholder.
+pub fn format(accessible: bool, text: &str) -> String {
+    if !accessible {
+        return text.to_string();
+    }
+
+    let lines = text.lines().collect::<Vec<&str>>();
+    let mut out = Vec::new();
+    let mut in_code = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if in_code {
+            out.push(line.to_string());
+            i += 1;
+            continue;
+        }
+        let is_table =
+            line.trim_start().starts_with('|') && lines.get(i + 1).map_or(false, |next| TABLE_SEPARATOR.is_match(next.trim()));
+        if !is_table {
+            let line = BARE_URL.replace_all(line, |c: &Captures| format!("{}[{}]({})", &c[1], link_text(&c[2]), &c[2]));
+            out.push(EMPTY_ALT.replace_all(&line, "![image attachment](").to_string());
+            i += 1;
+            continue;
+        }
+        let end = (i + 2..lines.len()).find(|&j| !lines[j].trim_start().starts_with('|')).unwrap_or(lines.len());
+        out.extend(table_to_list(&lines[i..end]));
+        i = end;
+    }
+
+    out.join("\n")
+}
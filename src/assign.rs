@@ -0,0 +1,92 @@
+use crate::config::Config;
+use crate::{mutations, permissions};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+
+/// GitHub accepts at most this many assignees per issue.
+const MAX_ASSIGNEES: usize = 10;
+
+/// Parses `@alice bob` into logins.
+fn logins(args: &str) -> Vec<&str> {
+    args.split_whitespace()
+        .map(|a| a.trim_start_matches('@').trim_end_matches(','))
+        .filter(|a| !a.is_empty())
+        .collect()
+}
+
+/// `assign @alice [@bob ...]`, for maintainers. Every target must be a collaborator.
+pub async fn assign(config: &Config, octo: &Octocrab, issue: &Issue, requester: &str, args: &str) -> anyhow::Result<String> {
+    let targets = logins(args);
+    if targets.is_empty() {
+        return Ok("Usage: `assign @user [@user ...]`, or `assign-me`.".to_string());
+    }
+    if !permissions::is_maintainer(config, octo, requester).await {
+        return Ok(format!("@{} only maintainers can assign others; use `assign-me` to take the issue yourself.", requester));
+    }
+    if issue.assignees.len() + targets.len() > MAX_ASSIGNEES {
+        return Ok(format!("An issue can have at most {} assignees.", MAX_ASSIGNEES));
+    }
+
+    let mut rejected = Vec::new();
+    for target in &targets {
+        if !permissions::is_collaborator(config, octo, target).await {
+            rejected.push(format!("`{}`", target));
+        }
+    }
+    if !rejected.is_empty() {
+        return Ok(format!("Not assigned: {} {} not a collaborator on this repository.", rejected.join(", "), if rejected.len() == 1 { "is" } else { "are" }));
+    }
+
+    if let Err(error) = mutations::add_assignees(config, octo, requester, issue.number, &targets).await {
+        permissions::check(config, octo, "assign issues", permissions::ISSUES_WRITE, &error).await;
+        return Err(error.into());
+    }
+    Ok(format!("Assigned {} to #{}.", targets.iter().map(|t| format!("`{}`", t)).collect::<Vec<String>>().join(", "), issue.number))
+}
+
+/// `unassign [@alice ...]`. Without arguments the requester unassigns themselves; only
+/// maintainers may unassign others.
+pub async fn unassign(config: &Config, octo: &Octocrab, issue: &Issue, requester: &str, args: &str) -> anyhow::Result<String> {
+    let mut targets = logins(args);
+    if targets.is_empty() {
+        targets.push(requester);
+    }
+    let only_self = targets.iter().all(|t| t.eq_ignore_ascii_case(requester));
+    if !only_self && !permissions::is_maintainer(config, octo, requester).await {
+        return Ok(format!("@{} only maintainers can unassign others.", requester));
+    }
+    let assigned = targets
+        .into_iter()
+        .filter(|t| issue.assignees.iter().any(|a| a.login.eq_ignore_ascii_case(t)))
+        .collect::<Vec<&str>>();
+    if assigned.is_empty() {
+        return Ok("Nobody named is assigned to this issue.".to_string());
+    }
+
+    if let Err(error) = mutations::remove_assignees(config, octo, requester, issue.number, &assigned).await {
+        permissions::check(config, octo, "unassign issues", permissions::ISSUES_WRITE, &error).await;
+        return Err(error.into());
+    }
+    Ok(format!("Unassigned {} from #{}.", assigned.iter().map(|t| format!("`{}`", t)).collect::<Vec<String>>().join(", "), issue.number))
+}
+
+/// `assign-me`: self-service assignment for collaborators.
+pub async fn assign_me(config: &Config, octo: &Octocrab, issue: &Issue, requester: &str) -> anyhow::Result<String> {
+    if issue.assignees.iter().any(|a| a.login.eq_ignore_ascii_case(requester)) {
+        return Ok(format!("@{} you are already assigned to #{}.", requester, issue.number));
+    }
+    if issue.assignees.len() >= MAX_ASSIGNEES {
+        return Ok(format!("An issue can have at most {} assignees.", MAX_ASSIGNEES));
+    }
+    if !permissions::is_collaborator(config, octo, requester).await {
+        return Ok(format!(
+            "@{} GitHub only lets collaborators be assigned. Comment that you're working on it and a maintainer will follow up.",
+            requester
+        ));
+    }
+
+    if let Err(error) = mutations::add_assignees(config, octo, requester, issue.number, &[requester]).await {
+        permissions::check(config, octo, "assign issues", permissions::ISSUES_WRITE, &error).await;
+        return Err(error.into());
+    }
+    Ok(format!("Assigned #{} to @{}. Thanks for picking it up!", issue.number, requester))
+}
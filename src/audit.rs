@@ -0,0 +1,43 @@
+use crate::config::Config;
+use crate::store;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const AUDIT_KEY: &str = "audit:log";
+const MAX_ENTRIES: usize = 500;
+
+/// One write the bot made (or refused to make) on GitHub.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Entry {
+    pub at: i64,
+    pub actor: String,
+    pub action: String,
+    pub issue: Option<u64>,
+    pub details: Value,
+}
+
+/// Appends an entry to the repository's audit log, keeping the most recent `MAX_ENTRIES`.
+pub fn record(config: &Config, actor: &str, action: &str, issue: Option<u64>, details: Value) {
+    let mut entries = recent(config);
+    entries.push(Entry {
+        at: chrono::Utc::now().timestamp(),
+        actor: actor.to_string(),
+        action: action.to_string(),
+        issue,
+        details,
+    });
+    if entries.len() > MAX_ENTRIES {
+        entries.drain(..entries.len() - MAX_ENTRIES);
+    }
+    match serde_json::to_value(&entries) {
+        Ok(value) => store::set(config, AUDIT_KEY, value, None),
+        Err(error) => log::error!("Error serializing audit log: {}", error),
+    }
+}
+
+/// Returns the audit log, oldest first.
+pub fn recent(config: &Config) -> Vec<Entry> {
+    store::get(config, AUDIT_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<Entry>>(v).ok())
+        .unwrap_or_default()
+}
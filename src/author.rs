@@ -0,0 +1,87 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// What kind of account wrote an issue or comment.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    User,
+    Bot,
+    /// A placeholder for a user of an imported repository who hasn't claimed it.
+    Mannequin,
+    /// A deleted account, shown by GitHub as `ghost` or a null author.
+    Deleted,
+}
+
+/// The author of an issue or comment, which may no longer be (or never was) a person.
+#[derive(Clone, Debug, Serialize)]
+pub struct Author {
+    pub login: Option<String>,
+    pub kind: Kind,
+}
+
+impl Author {
+    /// From a REST login; GitHub substitutes `ghost` for deleted accounts there.
+    pub fn from_login(login: &str) -> Self {
+        let kind = if login.is_empty() || login == "ghost" {
+            Kind::Deleted
+        } else if login.ends_with("[bot]") {
+            Kind::Bot
+        } else {
+            Kind::User
+        };
+        Author {
+            login: Some(login.to_string()).filter(|_| kind != Kind::Deleted),
+            kind,
+        }
+    }
+
+    /// From a GraphQL `author { login __typename }` selection, which is null for deleted
+    /// accounts.
+    pub fn from_graphql(author: &Value) -> Self {
+        let Some(login) = author["login"].as_str() else {
+            return Author { login: None, kind: Kind::Deleted };
+        };
+        let kind = match author["__typename"].as_str() {
+            Some("Bot") => Kind::Bot,
+            Some("Mannequin") => Kind::Mannequin,
+            _ => return Author::from_login(login),
+        };
+        Author {
+            login: Some(login.to_string()),
+            kind,
+        }
+    }
+
+    /// The login, or `ghost` for a deleted account.
+    pub fn login(&self) -> &str {
+        self.login.as_deref().unwrap_or("ghost")
+    }
+
+    pub fn is_human(&self) -> bool {
+        self.kind == Kind::User
+    }
+
+    /// How to refer to the author in a comment: an @-mention for people only, since bots
+    /// and mannequins can't respond and deleted accounts can't be notified.
+    pub fn mention(&self) -> String {
+        match self.kind {
+            Kind::User => format!("@{}", self.login()),
+            Kind::Deleted => "a deleted user".to_string(),
+            _ => format!("`{}`", self.login()),
+        }
+    }
+}
+
+/// How the author reads in LLM prompts.
+impl fmt::Display for Author {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            Kind::User => write!(f, "{}", self.login()),
+            Kind::Bot => write!(f, "{} (bot)", self.login()),
+            Kind::Mannequin => write!(f, "{} (imported user)", self.login()),
+            Kind::Deleted => write!(f, "a deleted user"),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use crate::commands;
+use crate::config::Config;
+use crate::footer;
+use crate::outbox::{self, Outbox};
+use crate::repo_config::RepoConfig;
+use crate::store;
+use crate::summary;
+use crate::{environment, milestone, severity};
+use github_flows::octocrab::{models::issues::Issue, params, Octocrab};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::env;
+use std::time::Duration;
+
+const CHECKPOINT_KEY: &str = "backfill";
+const MODES: [&str; 2] = ["triage", "summarize"];
+
+/// Progress of a backfill, saved after every issue so the next invocation (or the hourly
+/// schedule) picks up where the last one stopped.
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    mode: String,
+    state: String,
+    /// Issues handled per invocation.
+    limit: usize,
+    /// Page of the oldest-first issue listing being worked through.
+    page: u32,
+    /// Last issue handled. Issues are listed by creation, so later ones have higher numbers.
+    after: u64,
+    processed: u64,
+    done: bool,
+}
+
+fn load(config: &Config) -> Option<Checkpoint> {
+    store::get(config, CHECKPOINT_KEY).and_then(|v| serde_json::from_value::<Checkpoint>(v).ok())
+}
+
+fn save(config: &Config, checkpoint: &Checkpoint) {
+    store::set(config, CHECKPOINT_KEY, json!(checkpoint), None);
+}
+
+fn env_num(name: &str, default: u64) -> u64 {
+    env::var(name).ok().and_then(|n| n.parse::<u64>().ok()).unwrap_or(default)
+}
+
+/// Remaining REST calls and when the window resets, if the rate limit could be read.
+async fn rate_limit(octo: &Octocrab) -> Option<(u64, i64)> {
+    let limits = octo.get::<Value, _, ()>("/rate_limit", None).await.ok()?;
+    let core = &limits["resources"]["core"];
+    Some((core["remaining"].as_u64()?, core["reset"].as_i64().unwrap_or_default()))
+}
+
+fn state(name: &str) -> params::State {
+    match name {
+        "closed" => params::State::Closed,
+        "all" => params::State::All,
+        _ => params::State::Open,
+    }
+}
+
+/// Runs the labeling pipeline of newly opened issues on an existing one.
+async fn triage(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue) {
+    let outbox = Outbox::new(config);
+    if repo_config.severity.enabled {
+        severity::triage(config, repo_config, octo, issue, &outbox).await;
+    }
+    if repo_config.milestones.enabled {
+        milestone::triage(config, repo_config, octo, &outbox, issue).await;
+    }
+    environment::on_opened(config, repo_config, octo, issue).await;
+    outbox.flush(config, octo).await;
+}
+
+async fn summarize(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue) -> anyhow::Result<()> {
+    let context = summary::issue_context(octo, &config.owner, &config.repo, issue).await?;
+    let detail = repo_config.summary_detail.unwrap_or(config.summary_detail);
+    let summary = summary::summarize(&config.llm, issue, &context, detail)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let resp = format!("{}{}", summary, footer::render(config, None));
+    outbox::reply(config, octo, issue.number, &resp).await
+}
+
+/// Works through up to `limit` issues from the checkpoint, stopping early when the rate
+/// limit runs low. Returns a progress report.
+async fn advance(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, checkpoint: &mut Checkpoint) -> anyhow::Result<String> {
+    let min_remaining = env_num("backfill_min_remaining", 500);
+    let delay = Duration::from_millis(env_num("backfill_delay_ms", 2000));
+    let mut handled = 0;
+    while handled < checkpoint.limit {
+        let issues = octo
+            .issues(&config.owner, &config.repo)
+            .list()
+            .state(state(&checkpoint.state))
+            .sort(params::issues::Sort::Created)
+            .direction(params::Direction::Ascending)
+            .per_page(100)
+            .page(checkpoint.page)
+            .send()
+            .await?
+            .items;
+        if issues.is_empty() {
+            checkpoint.done = true;
+            save(config, checkpoint);
+            break;
+        }
+
+        let pending = issues
+            .iter()
+            .filter(|i| i.pull_request.is_none() && i.number > checkpoint.after)
+            .collect::<Vec<&Issue>>();
+        for issue in pending {
+            if handled >= checkpoint.limit {
+                break;
+            }
+            if let Some((remaining, reset)) = rate_limit(octo).await {
+                if remaining < min_remaining {
+                    let minutes = ((reset - chrono::Utc::now().timestamp()) / 60).max(1);
+                    return Ok(format!(
+                        "⏸️ Backfill paused after {} issue(s): only {} API calls left until the rate limit resets in about {} minutes. It resumes on the next hourly run, or run the command again later.",
+                        handled, remaining, minutes
+                    ));
+                }
+            }
+
+            match checkpoint.mode.as_str() {
+                "summarize" => {
+                    if let Err(error) = summarize(config, repo_config, octo, issue).await {
+                        log::error!("Error backfilling a summary for #{}: {}", issue.number, error);
+                    }
+                }
+                _ => triage(config, repo_config, octo, issue).await,
+            }
+            checkpoint.after = issue.number;
+            checkpoint.processed += 1;
+            handled += 1;
+            save(config, checkpoint);
+            tokio::time::sleep(delay).await;
+        }
+
+        if handled < checkpoint.limit {
+            checkpoint.page += 1;
+            save(config, checkpoint);
+        }
+    }
+
+    Ok(if checkpoint.done {
+        format!("✅ Backfill `{}` of {} issues finished: {} issue(s) processed in total.", checkpoint.mode, checkpoint.state, checkpoint.processed)
+    } else {
+        format!(
+            "🔄 Backfill `{}` of {} issues: {} issue(s) processed this run, {} in total, up to #{}. It continues on the next hourly run, or run the command again.",
+            checkpoint.mode, checkpoint.state, handled, checkpoint.processed, checkpoint.after
+        )
+    })
+}
+
+/// Handles `@flows_bot backfill <triage|summarize> [state=open|closed|all] [limit=50]`,
+/// plus `backfill status` and `backfill stop`. Repeating the same backfill continues from
+/// its checkpoint; `reset=true` starts over.
+pub async fn run(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, args: &str) -> anyhow::Result<String> {
+    let mode = args.split_whitespace().find(|a| !a.contains('=')).unwrap_or_default().to_lowercase();
+    let kv = commands::kv_args(args);
+
+    match mode.as_str() {
+        "status" => {
+            return Ok(match load(config) {
+                Some(c) => format!(
+                    "Backfill `{}` of {} issues: {} issue(s) processed, up to #{}{}.",
+                    c.mode,
+                    c.state,
+                    c.processed,
+                    c.after,
+                    if c.done { ", finished" } else { "" }
+                ),
+                None => "No backfill has been started.".to_string(),
+            })
+        }
+        "stop" => {
+            store::del(config, CHECKPOINT_KEY);
+            return Ok("Backfill stopped and its checkpoint removed.".to_string());
+        }
+        _ if !MODES.contains(&mode.as_str()) => {
+            return Ok("Usage: `backfill triage|summarize [state=open|closed|all] [limit=50]`, `backfill status` or `backfill stop`.".to_string());
+        }
+        _ => {}
+    }
+
+    let state = kv.get("state").cloned().unwrap_or("open".to_string());
+    let limit = kv.get("limit").and_then(|l| l.parse::<usize>().ok()).unwrap_or(50).clamp(1, 500);
+    let reset = kv.get("reset").map_or(false, |r| r == "true");
+
+    let mut checkpoint = match load(config) {
+        Some(c) if !reset && !c.done && c.mode == mode && c.state == state => Checkpoint { limit, ..c },
+        _ => Checkpoint {
+            mode,
+            state,
+            limit,
+            page: 1,
+            after: 0,
+            processed: 0,
+            done: false,
+        },
+    };
+    save(config, &checkpoint);
+    advance(config, repo_config, octo, &mut checkpoint).await
+}
+
+/// Continues an unfinished backfill from the scheduler.
+pub async fn resume(config: &Config, repo_config: &RepoConfig, octo: &Octocrab) {
+    let Some(mut checkpoint) = load(config).filter(|c| !c.done) else {
+        return;
+    };
+    match advance(config, repo_config, octo, &mut checkpoint).await {
+        Ok(report) => log::info!("Backfill for {}/{}: {}", config.owner, config.repo, report),
+        Err(error) => log::error!("Error continuing the backfill for {}/{}: {}", config.owner, config.repo, error),
+    }
+}
@@ -0,0 +1,76 @@
+use crate::config::Config;
+use crate::dry_run;
+use crate::footer;
+use crate::outbox;
+use crate::store;
+use github_flows::octocrab::Octocrab;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+const BATCH_KEY: &str = "batch:queue";
+
+/// A low-priority note waiting for the daily flush.
+#[derive(Serialize, Deserialize)]
+struct Note {
+    issue: u64,
+    body: String,
+    at: i64,
+}
+
+fn queued(config: &Config) -> Vec<Note> {
+    store::get(config, BATCH_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<Note>>(v).ok())
+        .unwrap_or_default()
+}
+
+/// Queues a low-priority note (stats, reminders, triage notes) for the issue's next daily
+/// comment instead of posting it now.
+pub fn enqueue(config: &Config, issue_number: u64, body: &str) {
+    let mut notes = queued(config);
+    notes.push(Note {
+        issue: issue_number,
+        body: body.to_string(),
+        at: chrono::Utc::now().timestamp(),
+    });
+    match serde_json::to_value(&notes) {
+        Ok(value) => store::set(config, BATCH_KEY, value, None),
+        Err(error) => log::error!("Error serializing comment batch: {}", error),
+    }
+}
+
+/// Posts one consolidated comment per issue with everything queued since the last flush.
+/// Notes that fail to post stay queued for the next run.
+pub async fn flush(config: &Config, octo: &Octocrab) {
+    let notes = queued(config);
+    if notes.is_empty() {
+        return;
+    }
+
+    let mut numbers = notes.iter().map(|n| n.issue).collect::<Vec<u64>>();
+    numbers.sort();
+    numbers.dedup();
+
+    let mut failed = Vec::new();
+    for number in numbers {
+        let body = notes
+            .iter()
+            .filter(|n| n.issue == number)
+            .map(|n| format!("* {}", n.body))
+            .collect::<Vec<String>>()
+            .join("\n");
+        let resp = footer::marked(&format!("🗓️ **Daily bot notes**\n\n{}", outbox::scrub(config, &body)));
+        if dry_run::intercept(config, "comment", Some(number), json!({ "body": resp })) {
+            continue;
+        }
+        if let Err(error) = octo.issues(&config.owner, &config.repo).create_comment(number, &resp).await {
+            log::error!("Error posting batched notes on #{}: {}", number, error);
+            failed.push(number);
+        }
+    }
+
+    let remaining = notes.into_iter().filter(|n| failed.contains(&n.issue)).collect::<Vec<Note>>();
+    match serde_json::to_value(&remaining) {
+        Ok(value) => store::set(config, BATCH_KEY, value, None),
+        Err(error) => log::error!("Error serializing comment batch: {}", error),
+    }
+}
@@ -0,0 +1,151 @@
+use crate::config::Config;
+use crate::output::Channel;
+use crate::permissions;
+use crate::repo_config::RepoConfig;
+use github_flows::octocrab::Octocrab;
+use serde_json::{json, Value};
+use std::env;
+
+/// A GitHub permission that an enabled feature relies on, in the repository it is used in.
+struct Need {
+    feature: &'static str,
+    permission: &'static str,
+    repo: String,
+}
+
+enum Probe {
+    Granted,
+    Missing(String),
+    /// The probe failed for some other reason, or the permission can't be probed.
+    Unknown(String),
+}
+
+/// Every permission the features enabled for this repo require.
+fn manifest(config: &Config, repo_config: &RepoConfig) -> Vec<Need> {
+    let here = format!("{}/{}", config.owner, config.repo);
+    let need = |feature, permission, repo: &str| Need { feature, permission, repo: repo.to_string() };
+
+    let mut needs = vec![
+        need("repo config", permissions::CONTENTS_READ, &here),
+        need("summaries and commands", permissions::ISSUES_READ, &here),
+        need("summaries and commands", permissions::ISSUES_WRITE, &here),
+        need("release notes", permissions::CONTENTS_WRITE, &here),
+        need("suggested fixes", permissions::CONTENTS_READ, &here),
+    ];
+    let labelers = [
+        ("severity", repo_config.severity.enabled),
+        ("spam screening", repo_config.spam.enabled),
+        ("security screening", repo_config.security.enabled),
+        ("milestones", repo_config.milestones.enabled),
+        ("good first issues", repo_config.good_first_issue.weekly),
+        ("title renames", repo_config.titles.rename),
+    ];
+    for (feature, _) in labelers.iter().filter(|(_, enabled)| *enabled) {
+        needs.push(need(*feature, permissions::ISSUES_WRITE, &here));
+    }
+    // Replaying deliveries is optional; only deployments that point it at their webhook need hook access.
+    if env::var("replay_hook_id").is_ok() || env::var("replay_hook_host").is_ok() {
+        needs.push(need("replay", permissions::HOOKS_WRITE, &here));
+    }
+    if env::var("ci_logs").unwrap_or_default() != "false" {
+        needs.push(need("CI log analysis", permissions::ACTIONS_READ, &here));
+    }
+    if repo_config.milestones.project_id.is_some() {
+        needs.push(need("project boards", permissions::PROJECTS_WRITE, &here));
+    }
+    if repo_config.outputs.values().any(|c| *c == Channel::CheckRun) {
+        needs.push(need("check run output", permissions::CHECKS_WRITE, &here));
+    }
+    if let Some(mirror) = &repo_config.mirror.repo {
+        needs.push(need("private mirror", permissions::ISSUES_WRITE, mirror));
+        if repo_config.leak_audit {
+            needs.push(need("leak audit", permissions::ISSUES_WRITE, mirror));
+        }
+    }
+    needs
+}
+
+/// Checks one permission without changing anything: reads are plain GETs, and writes send
+/// an empty payload that GitHub rejects as invalid only after authorizing the request.
+async fn probe(octo: &Octocrab, repo: &str, permission: &str) -> Probe {
+    let empty = json!({});
+    let result = match permission {
+        permissions::ISSUES_READ => octo.get::<Value, _, ()>(format!("/repos/{}/issues?per_page=1", repo), None).await,
+        permissions::CONTENTS_READ => octo.get::<Value, _, ()>(format!("/repos/{}/commits?per_page=1", repo), None).await,
+        permissions::ACTIONS_READ => octo.get::<Value, _, ()>(format!("/repos/{}/actions/runs?per_page=1", repo), None).await,
+        permissions::ISSUES_WRITE => octo.post(format!("/repos/{}/labels", repo), Some(&empty)).await,
+        permissions::CONTENTS_WRITE => octo.post(format!("/repos/{}/releases", repo), Some(&empty)).await,
+        permissions::CHECKS_WRITE => octo.post(format!("/repos/{}/check-runs", repo), Some(&empty)).await,
+        permissions::HOOKS_WRITE => octo.post(format!("/repos/{}/hooks", repo), Some(&empty)).await,
+        _ => return Probe::Unknown("not probed".to_string()),
+    };
+    let error = match result {
+        Ok(_) => return Probe::Granted,
+        Err(error) => error,
+    };
+    if permissions::status_code(&error) == Some(422) {
+        Probe::Granted
+    } else if permissions::is_permission_error(&error) {
+        Probe::Missing(error.to_string())
+    } else {
+        Probe::Unknown(error.to_string())
+    }
+}
+
+/// Probes every permission in the manifest and returns a Markdown report along with the
+/// permissions found missing.
+pub async fn audit(config: &Config, repo_config: &RepoConfig, octo: &Octocrab) -> (String, Vec<&'static str>) {
+    let needs = manifest(config, repo_config);
+    let mut checked: Vec<(String, &'static str)> = Vec::new();
+    let mut missing = Vec::new();
+    let mut report = format!("**Capability audit for {}/{}**\n\n| Permission | Repository | Needed by | Status |\n|---|---|---|---|\n", config.owner, config.repo);
+
+    for need in &needs {
+        if checked.iter().any(|(repo, permission)| *repo == need.repo && *permission == need.permission) {
+            continue;
+        }
+        checked.push((need.repo.clone(), need.permission));
+        let features = needs
+            .iter()
+            .filter(|n| n.repo == need.repo && n.permission == need.permission)
+            .map(|n| n.feature)
+            .collect::<Vec<&str>>();
+        let status = match probe(octo, &need.repo, need.permission).await {
+            Probe::Granted => {
+                if need.repo == format!("{}/{}", config.owner, config.repo) {
+                    permissions::clear_missing(config, need.permission);
+                }
+                "✅ granted".to_string()
+            }
+            Probe::Missing(error) => {
+                log::error!("Missing '{}' on {} needed by {}: {}", need.permission, need.repo, features.join(", "), error);
+                if !missing.contains(&need.permission) {
+                    missing.push(need.permission);
+                }
+                "❌ missing".to_string()
+            }
+            Probe::Unknown(reason) => {
+                log::warn!("Could not verify '{}' on {}: {}", need.permission, need.repo, reason);
+                format!("❔ unverified ({})", reason.lines().next().unwrap_or_default())
+            }
+        };
+        report.push_str(&format!("| {} | {} | {} | {} |\n", need.permission, need.repo, features.join(", "), status));
+    }
+
+    if missing.is_empty() {
+        report.push_str("\nEvery enabled feature has the permissions it needs.");
+    } else {
+        report.push_str("\nGrant the missing permissions under **Settings → GitHub Apps → flows.network → Configure**, or disable the features that need them.");
+    }
+    (report, missing)
+}
+
+/// Runs the audit when the flow is deployed, logging the report and raising the usual setup
+/// guidance for anything missing instead of waiting for the first failed write.
+pub async fn audit_on_deploy(config: &Config, repo_config: &RepoConfig, octo: &Octocrab) {
+    let (report, missing) = audit(config, repo_config, octo).await;
+    log::info!("{}", report);
+    for permission in missing {
+        permissions::record_missing(config, octo, "run the features enabled in the repo config", permission).await;
+    }
+}
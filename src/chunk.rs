@@ -0,0 +1,173 @@
+use crate::config::Config;
+use crate::gist;
+use github_flows::octocrab::Octocrab;
+use std::env;
+
+/// GitHub rejects comment bodies longer than this many characters.
+pub const MAX_COMMENT_CHARS: usize = 65536;
+
+/// Part budget, leaving room for the part header, the hidden marker and a scrubbed mention
+/// or two.
+fn max_chars() -> usize {
+    env::var("comment_chunk_chars")
+        .ok()
+        .and_then(|n| n.parse::<usize>().ok())
+        .unwrap_or(60000)
+        .clamp(1000, MAX_COMMENT_CHARS - 1000)
+}
+
+/// Above this many parts the output goes to a gist instead.
+fn max_parts() -> usize {
+    env::var("comment_max_parts").ok().and_then(|n| n.parse::<usize>().ok()).unwrap_or(5)
+}
+
+fn chars(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Markdown blocks still open at some point of a comment, which a part has to close and
+/// the next one reopen.
+#[derive(Default)]
+struct Open {
+    /// Opening line of a fenced code block, e.g. "```rust".
+    fence: Option<String>,
+    details: usize,
+}
+
+impl Open {
+    fn update(&mut self, line: &str) {
+        let trimmed = line.trim();
+        if let Some(fence) = &self.fence {
+            let marker = fence.chars().next().unwrap_or('`');
+            let run = fence.chars().take_while(|c| *c == marker).count();
+            if trimmed.chars().take_while(|c| *c == marker).count() >= run && trimmed.chars().all(|c| c == marker) {
+                self.fence = None;
+            }
+            return;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            self.fence = Some(trimmed.to_string());
+            return;
+        }
+        let lower = trimmed.to_lowercase();
+        self.details += lower.matches("<details").count();
+        self.details = self.details.saturating_sub(lower.matches("</details>").count());
+    }
+
+    fn is_closed(&self) -> bool {
+        self.fence.is_none() && self.details == 0
+    }
+
+    fn closing(&self) -> String {
+        let mut text = String::new();
+        if let Some(fence) = &self.fence {
+            let marker = fence.chars().next().unwrap_or('`');
+            let run = fence.chars().take_while(|c| *c == marker).count();
+            text.push_str(&marker.to_string().repeat(run));
+            text.push('\n');
+        }
+        text.push_str(&"</details>\n".repeat(self.details));
+        text
+    }
+
+    fn reopening(&self) -> String {
+        let mut text = "<details><summary>(continued)</summary>\n\n".repeat(self.details);
+        if let Some(fence) = &self.fence {
+            text.push_str(fence);
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Lines longer than half a part are cut so every line fits a part with room to spare.
+fn lines(body: &str, max: usize) -> Vec<String> {
+    let width = max / 2;
+    let mut lines = Vec::new();
+    for line in body.split('\n') {
+        if chars(line) <= width {
+            lines.push(line.to_string());
+            continue;
+        }
+        let line = line.chars().collect::<Vec<char>>();
+        lines.extend(line.chunks(width).map(|c| c.iter().collect::<String>()));
+    }
+    lines
+}
+
+/// Splits `body` into parts of at most `max` characters. Parts end at a blank line outside
+/// code blocks and `<details>` where possible; otherwise open blocks are closed at the end
+/// of the part and reopened at the start of the next.
+pub fn split(body: &str, max: usize) -> Vec<String> {
+    if chars(body) <= max {
+        return vec![body.to_string()];
+    }
+
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    // Byte offset into `current` just after the last blank line with nothing open.
+    let mut last_break: Option<usize> = None;
+    let mut open = Open::default();
+
+    for line in lines(body, max) {
+        let over = |current: &str, open: &Open| chars(current) + chars(&line) + 1 + chars(&open.closing()) > max;
+        if over(&current, &open) {
+            if let Some(at) = last_break.take() {
+                let rest = current.split_off(at);
+                parts.push(current.trim_end().to_string());
+                current = rest;
+            }
+        }
+        if over(&current, &open) && !current.trim().is_empty() {
+            current.push_str(&open.closing());
+            parts.push(current.trim_end().to_string());
+            current = open.reopening();
+        }
+
+        current.push_str(&line);
+        current.push('\n');
+        open.update(&line);
+        if line.trim().is_empty() && open.is_closed() {
+            last_break = Some(current.len());
+        }
+    }
+    if !current.trim().is_empty() || parts.is_empty() {
+        parts.push(current.trim_end().to_string());
+    }
+    parts
+}
+
+/// The comment bodies to post for `body`: the body itself when it fits, numbered parts when
+/// it doesn't, or a short comment linking a gist of the whole output when it would take more
+/// than `comment_max_parts` parts. If the gist can't be created the parts are posted anyway.
+pub async fn prepare(config: &Config, octo: &Octocrab, issue_number: u64, body: &str) -> Vec<String> {
+    let parts = split(body, max_chars());
+    if parts.len() == 1 {
+        return parts;
+    }
+
+    if parts.len() > max_parts() {
+        let filename = format!("{}-{}-issue-{}.md", config.owner, config.repo, issue_number);
+        let description = format!("Bot output for {}/{}#{}", config.owner, config.repo, issue_number);
+        match gist::create(config, octo, &description, &[(filename, body.to_string())]).await {
+            Ok(url) => {
+                let heading = body.lines().find(|l| !l.trim().is_empty()).unwrap_or_default();
+                return vec![format!(
+                    "{}\n\nThe full output is too long for a comment ({} characters) and was saved as a gist: {}",
+                    heading,
+                    chars(body),
+                    url
+                )];
+            }
+            Err(error) => log::warn!("Could not create a gist for the long output on #{}: {}", issue_number, error),
+        }
+    }
+
+    log::info!("Posting a {}-character output on #{} in {} parts", chars(body), issue_number, parts.len());
+    let total = parts.len();
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(i, part)| format!("*(part {}/{})*\n\n{}", i + 1, total, part))
+        .collect()
+}
@@ -0,0 +1,280 @@
+use crate::config::Config;
+use crate::llm;
+use github_flows::octocrab::Octocrab;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
+use std::env;
+
+lazy_static! {
+    static ref RUN_LINK: Regex =
+        Regex::new(r"https://github\.com/([\w.-]+)/([\w.-]+)/actions/runs/(\d+)(?:/attempts/\d+)?(?:/job/(\d+))?").unwrap();
+    static ref FENCE: Regex = Regex::new(r"(?s)```[^\n]*\n(.*?)```").unwrap();
+    static ref TIMESTAMP: Regex = Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?Z ?").unwrap();
+    static ref ANSI: Regex = Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").unwrap();
+    static ref ERROR_LINE: Regex = Regex::new(
+        r"(?i)##\[error\]|\berror(?:\[E\d+\])?:|\bFAILED\b|panicked at|\bfatal\b|segmentation fault|exit code [1-9]|assertion .*failed"
+    )
+    .unwrap();
+}
+
+/// Actions runs analyzed per thread.
+const MAX_RUNS: usize = 3;
+/// Failed jobs analyzed per run.
+const MAX_JOBS: usize = 2;
+/// Pasted logs analyzed per thread.
+const MAX_PASTED: usize = 2;
+/// Only the end of a job log is read; the failure is almost always there.
+const MAX_LOG_BYTES: usize = 256 * 1024;
+/// Lines kept around every error line, and at the very end of the log.
+const ERROR_CONTEXT: usize = 5;
+const TAIL_LINES: usize = 30;
+
+/// The probable cause of a failing CI job, linked or pasted into an issue thread.
+pub struct Failure {
+    /// The job's page, or `None` for a log pasted into the thread.
+    pub url: Option<String>,
+    /// Workflow job name, e.g. `build (ubuntu-22.04)`.
+    pub job: Option<String>,
+    pub step: String,
+    pub error: String,
+    pub cause: String,
+}
+
+fn max_lines() -> usize {
+    env::var("ci_log_lines").ok().and_then(|n| n.parse::<usize>().ok()).unwrap_or(120)
+}
+
+/// Cuts a CI log down to what explains the failure: timestamps, color codes, group markers
+/// and repeated lines are dropped, then the lines around each error and the last lines of the
+/// log are kept, newest first when over `ci_log_lines`.
+fn condense(log: &str, max_lines: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in log.lines() {
+        let line = ANSI.replace_all(&TIMESTAMP.replace(line, ""), "").trim_end().to_string();
+        if line.trim().is_empty() || line.starts_with("##[group]") || line.starts_with("##[endgroup]") {
+            continue;
+        }
+        if lines.last() != Some(&line) {
+            lines.push(line);
+        }
+    }
+
+    let mut keep = vec![false; lines.len()];
+    for (i, line) in lines.iter().enumerate() {
+        if ERROR_LINE.is_match(line) {
+            for k in keep.iter_mut().take(i + ERROR_CONTEXT + 1).skip(i.saturating_sub(ERROR_CONTEXT)) {
+                *k = true;
+            }
+        }
+    }
+    for k in keep.iter_mut().skip(lines.len().saturating_sub(TAIL_LINES)) {
+        *k = true;
+    }
+
+    let mut kept = Vec::new();
+    let mut gap = false;
+    for (line, k) in lines.iter().zip(&keep) {
+        if !*k {
+            gap = true;
+            continue;
+        }
+        if gap {
+            kept.push("…");
+            gap = false;
+        }
+        kept.push(line.as_str());
+    }
+    if kept.len() > max_lines {
+        kept.drain(..kept.len() - max_lines);
+    }
+    kept.join("\n")
+}
+
+/// Fenced blocks in `text` that look like CI output: long, and containing at least one error line.
+fn pasted_logs(text: &str) -> Vec<String> {
+    let min_lines = env::var("ci_log_min_lines").ok().and_then(|n| n.parse::<usize>().ok()).unwrap_or(30);
+    FENCE
+        .captures_iter(text)
+        .map(|c| c[1].to_string())
+        .filter(|block| block.lines().count() >= min_lines && ERROR_LINE.is_match(block))
+        .take(MAX_PASTED)
+        .collect()
+}
+
+/// Failed jobs of a run, or just `job_id` when the link pointed at one.
+async fn failed_jobs(octo: &Octocrab, owner: &str, repo: &str, run_id: u64, job_id: Option<u64>) -> anyhow::Result<Vec<Value>> {
+    let route = format!("/repos/{}/{}/actions/runs/{}/jobs?filter=latest&per_page=100", owner, repo, run_id);
+    let jobs = octo.get::<Value, _, ()>(route, None).await?;
+    Ok(jobs["jobs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|j| match job_id {
+            Some(id) => j["id"].as_u64() == Some(id),
+            None => j["conclusion"].as_str() == Some("failure"),
+        })
+        .take(MAX_JOBS)
+        .cloned()
+        .collect())
+}
+
+/// Reads the last `MAX_LOG_BYTES` of a job's log. The logs endpoint redirects to a
+/// short-lived download URL with a plain-text body, so it is streamed rather than parsed.
+async fn log_tail(octo: &Octocrab, owner: &str, repo: &str, job_id: u64) -> anyhow::Result<String> {
+    let url = octo.absolute_url(format!("/repos/{}/{}/actions/jobs/{}/logs", owner, repo, job_id))?;
+    let mut resp = octo._get(url, None::<&()>).await?.error_for_status()?;
+    let mut tail = Vec::new();
+    let mut cut = false;
+    while let Some(chunk) = resp.chunk().await? {
+        tail.extend_from_slice(&chunk);
+        if tail.len() > 2 * MAX_LOG_BYTES {
+            tail.drain(..tail.len() - MAX_LOG_BYTES);
+            cut = true;
+        }
+    }
+    if tail.len() > MAX_LOG_BYTES {
+        tail.drain(..tail.len() - MAX_LOG_BYTES);
+        cut = true;
+    }
+    let text = String::from_utf8_lossy(&tail).to_string();
+    // The first line after a cut is partial.
+    Ok(match (cut, text.split_once('\n')) {
+        (true, Some((_, rest))) => rest.to_string(),
+        _ => text,
+    })
+}
+
+/// Asks the LLM for the failing step, the key error and its probable cause.
+async fn diagnose(config: &Config, source: &str, job: Option<&str>, step: Option<&str>, log: &str) -> anyhow::Result<(String, String, String)> {
+    let sys_prompt = "You read failing CI logs. Reply only with JSON: {\"step\": \"<the step or command that failed>\", \"error\": \"<the key error message, verbatim and shortened to one line>\", \"cause\": \"<one sentence on the probable cause>\"}.";
+    let mut usr_prompt = String::new();
+    if let Some(job) = job {
+        usr_prompt.push_str(&format!("Job: {}\n", job));
+    }
+    if let Some(step) = step {
+        usr_prompt.push_str(&format!("Failed step according to GitHub: {}\n", step));
+    }
+    usr_prompt.push_str(&format!("Log excerpt:\n{}", log));
+
+    let reply = llm::chat(&config.llm.profile("ci"), &format!("ci_{}", source), sys_prompt, &usr_prompt, 192)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let value = llm::extract_json(&reply).ok_or(anyhow::anyhow!("Unexpected reply: {}", reply))?;
+    let field = |name: &str| value[name].as_str().unwrap_or_default().trim().to_string();
+    Ok((field("step"), field("error"), field("cause")))
+}
+
+/// Analyzes the failing jobs of GitHub Actions runs linked from `text` and CI logs pasted
+/// into it. Disabled with `ci_logs=false`.
+pub async fn analyze(config: &Config, octo: &Octocrab, text: &str) -> Vec<Failure> {
+    if env::var("ci_logs").unwrap_or_default() == "false" {
+        return Vec::new();
+    }
+    let max_lines = max_lines();
+    let mut failures = Vec::new();
+    let mut seen = Vec::new();
+
+    for c in RUN_LINK.captures_iter(text) {
+        if seen.len() >= MAX_RUNS {
+            break;
+        }
+        let (owner, repo) = (&c[1], &c[2]);
+        let run_id = c[3].parse::<u64>().unwrap_or_default();
+        let job_id = c.get(4).and_then(|j| j.as_str().parse::<u64>().ok());
+        if seen.contains(&(run_id, job_id)) {
+            continue;
+        }
+        seen.push((run_id, job_id));
+
+        let jobs = match failed_jobs(octo, owner, repo, run_id, job_id).await {
+            Ok(jobs) => jobs,
+            Err(error) => {
+                log::debug!("Skipping Actions run {}/{}#{}: {}", owner, repo, run_id, error);
+                continue;
+            }
+        };
+        for job in jobs {
+            let Some(id) = job["id"].as_u64() else {
+                continue;
+            };
+            let name = job["name"].as_str().unwrap_or_default().to_string();
+            let failed_step = job["steps"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|s| s["conclusion"].as_str() == Some("failure"))
+                .and_then(|s| s["name"].as_str())
+                .map(|s| s.to_string());
+            let log = match log_tail(octo, owner, repo, id).await {
+                Ok(log) => condense(&log, max_lines),
+                Err(error) => {
+                    log::debug!("Could not read the log of job {} in {}/{}: {}", id, owner, repo, error);
+                    continue;
+                }
+            };
+            match diagnose(config, &id.to_string(), Some(&name), failed_step.as_deref(), &log).await {
+                Ok((step, error, cause)) => failures.push(Failure {
+                    url: job["html_url"].as_str().map(|u| u.to_string()),
+                    job: Some(name),
+                    step: if step.is_empty() { failed_step.unwrap_or_default() } else { step },
+                    error,
+                    cause,
+                }),
+                Err(error) => log::warn!("Error analyzing the log of job {}: {}", id, error),
+            }
+        }
+    }
+
+    for (i, log) in pasted_logs(text).iter().enumerate() {
+        let source = format!("pasted_{}_{}", i, log.len());
+        match diagnose(config, &source, None, None, &condense(log, max_lines)).await {
+            Ok((step, error, cause)) => failures.push(Failure { url: None, job: None, step, error, cause }),
+            Err(error) => log::warn!("Error analyzing a pasted CI log: {}", error),
+        }
+    }
+    failures
+}
+
+fn describe(failure: &Failure) -> String {
+    match (&failure.job, &failure.url) {
+        (Some(job), Some(url)) => format!("[{}]({})", job, url),
+        (Some(job), None) => job.clone(),
+        _ => "Pasted log".to_string(),
+    }
+}
+
+/// Renders the analyses as additional LLM context.
+pub fn context_block(failures: &[Failure]) -> String {
+    failures
+        .iter()
+        .map(|f| {
+            let source = match &f.job {
+                Some(job) => format!("CI job '{}'", job),
+                None => "A CI log pasted in the thread".to_string(),
+            };
+            format!("{} failed at step '{}' with '{}'. Probable cause: {}\n", source, f.step, f.error, f.cause)
+        })
+        .collect()
+}
+
+/// Renders the "CI failures" section appended to summaries.
+pub fn section(failures: &[Failure]) -> String {
+    if failures.is_empty() {
+        return String::new();
+    }
+    let lines = failures
+        .iter()
+        .map(|f| {
+            format!(
+                "* {}: `{}` failed with `{}`. {}",
+                describe(f),
+                f.step.replace('`', "'"),
+                f.error.replace('`', "'"),
+                f.cause
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!("\n\n**CI failures**\n{}", lines)
+}
@@ -0,0 +1,89 @@
+use crate::author::Author;
+use crate::config::Config;
+use crate::llm;
+use crate::outbox::Outbox;
+use crate::persona;
+use crate::permissions;
+use crate::repo_config::RepoConfig;
+use crate::severity;
+use crate::store;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// Questions unanswered for this long are dropped; the item stays in the triage queue.
+const PENDING_TTL_SECS: i64 = 14 * 24 * 3600;
+
+/// A clarifying question waiting for the reporter's reply.
+#[derive(Serialize, Deserialize)]
+struct Pending {
+    feature: String,
+    question: String,
+}
+
+fn pending_key(number: u64) -> String {
+    format!("clarify:{}", number)
+}
+
+/// Asks the reporter one targeted question to fill in `missing`, the information the
+/// classifier said it lacked. Triage re-runs when the reporter replies.
+pub async fn ask(config: &Config, octo: &Octocrab, outbox: &Outbox, issue: &Issue, feature: &str, missing: &str) {
+    if !Author::from_login(&issue.user.login).is_human() {
+        log::debug!("Not asking a clarifying question on #{}: the reporter can't answer", issue.number);
+        return;
+    }
+    let sys_prompt = config.llm.persona.apply(
+        "You help triage GitHub issues for the WasmEdge project. Write exactly one short, specific question to the issue reporter. Reply with the question only.",
+    );
+    let usr_prompt = format!(
+        "Issue titled '{}':\n{}\n\nThe triager could not decide because this information is missing: {}",
+        issue.title,
+        issue.body.clone().unwrap_or_default(),
+        missing
+    );
+    let conversation_id = format!("clarify_{}", issue.html_url);
+    let cfg = config.llm.profile("clarify");
+    let question = match llm::chat(&cfg, &conversation_id, &sys_prompt, &usr_prompt, 96).await {
+        Ok(question) => persona::enforce(&cfg, &conversation_id, question, 96).await.trim().to_string(),
+        Err(error) => {
+            log::error!("Error generating a clarifying question for #{}: {}", issue.number, error);
+            return;
+        }
+    };
+
+    let resp = format!(
+        "{} {}\n\nYour answer helps us triage this issue; the bot will take another look once you reply.",
+        Author::from_login(&issue.user.login).mention(),
+        question
+    );
+    if let Err(error) = outbox.post(config, octo, issue.number, &resp).await {
+        permissions::check(config, octo, "ask clarifying questions", permissions::ISSUES_WRITE, &error).await;
+        return;
+    }
+    store::set(
+        config,
+        &pending_key(issue.number),
+        json!(Pending {
+            feature: feature.to_string(),
+            question,
+        }),
+        Some(PENDING_TTL_SECS),
+    );
+}
+
+/// Re-runs triage with the reporter's answer when a clarifying question is pending.
+pub async fn on_author_reply(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue, reply: &str) {
+    let Some(pending) = store::get(config, &pending_key(issue.number)).and_then(|v| serde_json::from_value::<Pending>(v).ok()) else {
+        return;
+    };
+    store::del(config, &pending_key(issue.number));
+
+    log::info!("Re-running {} triage of #{} after the reporter replied", pending.feature, issue.number);
+    let followup = format!("Follow-up question to the reporter: {}\nReporter's answer: {}", pending.question, reply);
+    let outbox = Outbox::new(config);
+    match pending.feature.as_str() {
+        "severity" => severity::retriage(config, repo_config, octo, issue, &outbox, &followup).await,
+        other => log::warn!("No re-triage for feature '{}'", other),
+    }
+    outbox.flush(config, octo).await;
+}
@@ -0,0 +1,68 @@
+use crate::config::Config;
+use crate::store;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::env;
+use std::time::Duration;
+
+/// A run that crashed before `finish` stops absorbing triggers after this long.
+const RUN_TTL_SECS: i64 = 600;
+
+/// A summarization run under way on an issue and everyone whose trigger it answers.
+#[derive(Serialize, Deserialize)]
+struct Run {
+    requesters: Vec<String>,
+}
+
+fn run_key(number: u64) -> String {
+    format!("coalesce:{}", number)
+}
+
+fn current(config: &Config, number: u64) -> Option<Run> {
+    store::get(config, &run_key(number)).and_then(|v| serde_json::from_value::<Run>(v).ok())
+}
+
+/// Adds `requester` to a run already under way on the issue. Returns false when there is
+/// none and the caller should start its own.
+pub fn join(config: &Config, number: u64, requester: &str) -> bool {
+    let Some(mut run) = current(config, number) else {
+        return false;
+    };
+    if !run.requesters.iter().any(|r| r.eq_ignore_ascii_case(requester)) {
+        run.requesters.push(requester.to_string());
+    }
+    store::set(config, &run_key(number), json!(run), Some(RUN_TTL_SECS));
+    log::info!("Coalesced trigger by {} into the run on #{}", requester, number);
+    true
+}
+
+/// Starts a run for `requester`, then waits `trigger_coalesce_secs` (default 15) so
+/// triggers posted right after this one can join it.
+pub async fn start(config: &Config, number: u64, requester: &str) {
+    let run = Run {
+        requesters: vec![requester.to_string()],
+    };
+    store::set(config, &run_key(number), json!(run), Some(RUN_TTL_SECS));
+    let wait = env::var("trigger_coalesce_secs").ok().and_then(|n| n.parse::<u64>().ok()).unwrap_or(15);
+    if wait > 0 {
+        tokio::time::sleep(Duration::from_secs(wait)).await;
+    }
+}
+
+/// Ends the run on the issue and returns everyone it answers, first requester first.
+pub fn finish(config: &Config, number: u64, requester: &str) -> Vec<String> {
+    let requesters = current(config, number)
+        .map(|run| run.requesters)
+        .unwrap_or(vec![requester.to_string()]);
+    store::del(config, &run_key(number));
+    requesters
+}
+
+/// Renders the requesters for the reply's credit line, e.g. `@alice, @bob and @carol`.
+pub fn credits(requesters: &[String]) -> String {
+    let mentions = requesters.iter().map(|r| format!("@{}", r)).collect::<Vec<String>>();
+    match mentions.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => mentions.join(""),
+    }
+}
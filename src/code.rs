@@ -0,0 +1,252 @@
+use github_flows::octocrab::Octocrab;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
+use std::env;
+
+lazy_static! {
+    static ref BLOB_LINK: Regex = Regex::new(
+        r"https://github\.com/([\w.-]+)/([\w.-]+)/blob/([\w./-]+?)/([\w./-]+\.\w+)(?:#L(\d+)(?:-L(\d+))?)?"
+    )
+    .unwrap();
+    static ref BARE_PATH: Regex = Regex::new(r"(?:^|[\s`(])((?:[\w.-]+/)+[\w.-]+\.\w+)(?::(\d+)(?:-(\d+))?|#L(\d+)(?:-L(\d+))?)?").unwrap();
+    /// Backticked calls or paths such as `Executor::instantiate()` or `wasmedge::vm::run`.
+    static ref FUNCTION: Regex = Regex::new(r"`(?:[A-Za-z_]\w*::)*([A-Za-z_]\w{2,})\([^`)]*\)`|`(?:[A-Za-z_]\w*::)+([A-Za-z_]\w{2,})`").unwrap();
+}
+
+/// Lines of context kept around a single-line link.
+const CONTEXT_LINES: usize = 20;
+/// Files and functions excerpted into a summary prompt.
+const MAX_ENRICH_REFS: usize = 5;
+const MAX_ENRICH_LINES: usize = 60;
+
+/// A source file (optionally a line range) referenced from an issue.
+#[derive(Clone, PartialEq)]
+pub struct CodeRef {
+    pub owner: String,
+    pub repo: String,
+    pub git_ref: String,
+    pub path: String,
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+}
+
+/// Extracts GitHub blob links such as `https://github.com/o/r/blob/main/src/lib.rs#L10-L20`.
+pub fn extract_links(text: &str) -> Vec<CodeRef> {
+    let mut refs: Vec<CodeRef> = Vec::new();
+    for c in BLOB_LINK.captures_iter(text) {
+        let code_ref = CodeRef {
+            owner: c[1].to_string(),
+            repo: c[2].to_string(),
+            git_ref: c[3].to_string(),
+            path: c[4].to_string(),
+            start: c.get(5).and_then(|m| m.as_str().parse().ok()),
+            end: c.get(6).and_then(|m| m.as_str().parse().ok()),
+        };
+        if !refs.contains(&code_ref) {
+            refs.push(code_ref);
+        }
+    }
+    refs
+}
+
+/// Extracts repository-relative paths such as `lib/api/wasmedge.cpp:120` or
+/// `src/lib.rs#L10-L20`, resolved against `owner/repo` at `git_ref`.
+pub fn extract_paths(text: &str, owner: &str, repo: &str, git_ref: &str) -> Vec<CodeRef> {
+    let stripped = BLOB_LINK.replace_all(text, "");
+    let mut refs: Vec<CodeRef> = Vec::new();
+    for c in BARE_PATH.captures_iter(&stripped) {
+        let line = |a: usize, b: usize| c.get(a).or_else(|| c.get(b)).and_then(|m| m.as_str().parse().ok());
+        let code_ref = CodeRef {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            git_ref: git_ref.to_string(),
+            path: c[1].trim_start_matches("./").to_string(),
+            start: line(2, 4),
+            end: line(3, 5),
+        };
+        if !refs.contains(&code_ref) {
+            refs.push(code_ref);
+        }
+    }
+    refs
+}
+
+async fn fetch_file(octo: &Octocrab, code_ref: &CodeRef) -> Option<String> {
+    match octo
+        .repos(&code_ref.owner, &code_ref.repo)
+        .get_content()
+        .path(&code_ref.path)
+        .r#ref(&code_ref.git_ref)
+        .send()
+        .await
+    {
+        Ok(items) => items.items.into_iter().next()?.decoded_content(),
+        Err(error) => {
+            log::debug!("Could not fetch {}: {}", code_ref.path, error);
+            None
+        }
+    }
+}
+
+/// Fetches the referenced lines (or the head of the file when no range is given), capped
+/// at `max_lines`, with line numbers.
+pub async fn fetch_excerpt(octo: &Octocrab, code_ref: &CodeRef, max_lines: usize) -> Option<String> {
+    let content = fetch_file(octo, code_ref).await?;
+    excerpt_lines(code_ref, &content, max_lines)
+}
+
+fn excerpt_lines(code_ref: &CodeRef, content: &str, max_lines: usize) -> Option<String> {
+    let lines = content.lines().collect::<Vec<&str>>();
+    let (from, to) = match (code_ref.start, code_ref.end) {
+        (Some(start), Some(end)) => (start.saturating_sub(1), end),
+        (Some(start), None) => (start.saturating_sub(CONTEXT_LINES + 1), start + CONTEXT_LINES),
+        _ => (0, max_lines),
+    };
+    let to = to.min(lines.len()).min(from + max_lines);
+    if from >= to {
+        return None;
+    }
+
+    let excerpt = lines[from..to]
+        .iter()
+        .enumerate()
+        .map(|(i, l)| format!("{:>5} {}", from + i + 1, l))
+        .collect::<Vec<String>>()
+        .join("\n");
+    Some(format!("{} (lines {}-{}):\n{}", code_ref.path, from + 1, to, excerpt))
+}
+
+/// Lists every file path in the repository at `git_ref`. Very large trees come back
+/// truncated by the API; the returned list is then partial.
+pub async fn tree(octo: &Octocrab, owner: &str, repo: &str, git_ref: &str) -> anyhow::Result<Vec<String>> {
+    let route = format!("/repos/{}/{}/git/trees/{}?recursive=1", owner, repo, git_ref);
+    let value = octo.get::<Value, _, ()>(route, None).await?;
+    Ok(value["tree"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|e| e["type"] == "blob")
+        .filter_map(|e| e["path"].as_str().map(|p| p.to_string()))
+        .collect())
+}
+
+/// Picks up to `limit` paths from `paths` whose components share a word with `text`,
+/// best matches first. Cheap enough to narrow a whole repository tree for the LLM.
+pub fn candidate_paths(paths: &[String], text: &str, limit: usize) -> Vec<String> {
+    let words = text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| w.len() >= 4)
+        .map(|w| w.to_string())
+        .collect::<Vec<String>>();
+    let mut scored = paths
+        .iter()
+        .map(|p| {
+            let lower = p.to_lowercase();
+            (words.iter().filter(|w| lower.contains(w.as_str())).count(), p)
+        })
+        .filter(|(score, _)| *score > 0)
+        .collect::<Vec<(usize, &String)>>();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.len().cmp(&b.1.len())));
+    scored.into_iter().take(limit).map(|(_, p)| p.clone()).collect()
+}
+
+/// Function names mentioned in backticks, e.g. `Executor::instantiate()` yields `instantiate`.
+pub fn extract_functions(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for c in FUNCTION.captures_iter(text) {
+        let Some(name) = c.get(1).or_else(|| c.get(2)) else {
+            continue;
+        };
+        if !names.iter().any(|n| n == name.as_str()) {
+            names.push(name.as_str().to_string());
+        }
+    }
+    names
+}
+
+/// Finds where `name` is defined with code search, returning a reference to the
+/// definition line so the excerpt is centred on it.
+async fn locate_function(octo: &Octocrab, owner: &str, repo: &str, git_ref: &str, name: &str) -> Option<(CodeRef, String)> {
+    let route = format!("/search/code?q={}+repo:{}/{}&per_page=5", name, owner, repo);
+    let found = match octo.get::<Value, _, ()>(route, None).await {
+        Ok(found) => found,
+        Err(error) => {
+            log::debug!("Code search for {} failed: {}", name, error);
+            return None;
+        }
+    };
+    let definition = Regex::new(&format!(
+        r"\b(?:fn|def|func|function|class|struct|void|auto|bool|int|Expect<[^>]*>)\s+(?:\w+::)*{}\b",
+        regex::escape(name)
+    ))
+    .ok()?;
+    let call = Regex::new(&format!(r"\b{}\s*\(", regex::escape(name))).ok()?;
+
+    for path in found["items"].as_array().into_iter().flatten().filter_map(|i| i["path"].as_str()) {
+        let mut code_ref = CodeRef {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            git_ref: git_ref.to_string(),
+            path: path.to_string(),
+            start: None,
+            end: None,
+        };
+        let Some(content) = fetch_file(octo, &code_ref).await else {
+            continue;
+        };
+        let line = content
+            .lines()
+            .position(|l| definition.is_match(l))
+            .or_else(|| content.lines().position(|l| call.is_match(l)));
+        if let Some(line) = line {
+            code_ref.start = Some(line + 1);
+            return Some((code_ref, content));
+        }
+    }
+    None
+}
+
+/// Bounded excerpts, from `git_ref`, of the files, line ranges and functions `text` refers
+/// to, for grounding a summary in the actual code. Stops at `code_context_chars` (default
+/// 6000); set `code_context=false` to disable.
+pub async fn context_block(octo: &Octocrab, owner: &str, repo: &str, git_ref: &str, text: &str) -> String {
+    if env::var("code_context").unwrap_or_default() == "false" {
+        return String::new();
+    }
+    let budget = env::var("code_context_chars").ok().and_then(|n| n.parse::<usize>().ok()).unwrap_or(6000);
+
+    let mut refs = extract_links(text)
+        .into_iter()
+        .filter(|r| r.owner.eq_ignore_ascii_case(owner) && r.repo.eq_ignore_ascii_case(repo))
+        .collect::<Vec<CodeRef>>();
+    refs.extend(extract_paths(text, owner, repo, git_ref));
+
+    let mut excerpts = Vec::new();
+    for code_ref in refs.iter().take(MAX_ENRICH_REFS) {
+        if let Some(excerpt) = fetch_excerpt(octo, code_ref, MAX_ENRICH_LINES).await {
+            excerpts.push(excerpt);
+        }
+    }
+    for name in extract_functions(text).iter().take(MAX_ENRICH_REFS.saturating_sub(excerpts.len())) {
+        if let Some((code_ref, content)) = locate_function(octo, owner, repo, git_ref, name).await {
+            if let Some(excerpt) = excerpt_lines(&code_ref, &content, MAX_ENRICH_LINES) {
+                excerpts.push(excerpt);
+            }
+        }
+    }
+
+    let mut block = String::new();
+    for excerpt in excerpts {
+        if block.len() + excerpt.len() > budget {
+            log::debug!("Code context for {}/{} reached its {} character budget", owner, repo, budget);
+            break;
+        }
+        block.push_str(&format!("{}\n\n", excerpt));
+    }
+    if block.is_empty() {
+        return block;
+    }
+    format!("\nSource code referenced in the issue, from {} (line numbers on the left):\n{}", git_ref, block)
+}
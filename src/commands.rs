@@ -0,0 +1,235 @@
+use crate::config::Config;
+use crate::repo_config::RepoConfig;
+use crate::{a11y, assign, backfill, capabilities, confidence, deep_dive, digest, dry_run, duplicate, evaluate, export, features, feedback, good_first, healthcheck, metrics, milestone, moderation, permissions, pin, quota, replay, state, titles, triage_import};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use std::collections::HashMap;
+
+/// Returns the subcommand and its remaining arguments following `phrase`,
+/// e.g. `@flows_bot digest` yields `("digest", "")`.
+pub fn bot_subcommand<'a>(body: &'a str, phrase: &str) -> Option<(String, &'a str)> {
+    let rest = &body[body.find(phrase)? + phrase.len()..];
+    let rest = rest.lines().next().unwrap_or_default().trim_start();
+    let name = rest.split_whitespace().next()?;
+    let args = rest[name.len()..].trim();
+    Some((name.to_lowercase(), args))
+}
+
+/// Parses `key=value` arguments, e.g. `models=a,b limit=5`.
+pub fn kv_args(args: &str) -> HashMap<String, String> {
+    args.split_whitespace()
+        .filter_map(|a| a.split_once('='))
+        .map(|(k, v)| (k.to_lowercase(), v.to_string()))
+        .collect()
+}
+
+/// Placeholder shown while a long-running command works, replaced by its reply.
+pub fn progress_text(command: &str) -> Option<&'static str> {
+    match command {
+        "backfill" => Some("⏳ Backfilling existing issues…"),
+        "deep-dive" => Some("⏳ Running deep dive…"),
+        "evaluate" => Some("⏳ Running evaluation…"),
+        "export" => Some("⏳ Exporting issue context…"),
+        "good-first-issues" => Some("⏳ Looking for good first issues…"),
+        "healthcheck" => Some("⏳ Running health check…"),
+        "pin-summary" => Some("⏳ Pinning a summary…"),
+        _ => None,
+    }
+}
+
+/// Commands that spend LLM calls or many API calls, and so count against the quotas.
+const METERED: [&str; 8] = ["backfill", "deep-dive", "digest", "evaluate", "export", "good-first-issues", "milestone-status", "pin-summary"];
+
+/// A `@flows_bot <command>` found in a comment.
+pub struct Invocation<'a> {
+    pub issue: &'a Issue,
+    pub requester: &'a str,
+    pub command: &'a str,
+    pub args: &'a str,
+    /// The full comment body, for commands that take block input.
+    pub body: &'a str,
+}
+
+/// Runs a bot command and returns the reply to post.
+pub async fn dispatch(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, inv: &Invocation<'_>) -> anyhow::Result<String> {
+    let (issue, requester, command, args, body) = (inv.issue, inv.requester, inv.command, inv.args, inv.body);
+    if METERED.contains(&command) {
+        if let Some(refusal) = quota::check(config, octo, issue.number, requester).await {
+            return Ok(refusal);
+        }
+    }
+    match command {
+        "assign" => assign::assign(config, octo, issue, requester, args).await,
+        "assign-me" => assign::assign_me(config, octo, issue, requester).await,
+        "backfill" => {
+            if !permissions::is_admin(config, octo, requester).await {
+                return Ok(format!("@{} `backfill` is restricted to repository admins.", requester));
+            }
+            backfill::run(config, repo_config, octo, args).await
+        }
+        "capabilities" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `capabilities` is restricted to maintainers.", requester));
+            }
+            Ok(capabilities::audit(config, repo_config, octo).await.0)
+        }
+        "confirm" | "dismiss" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `{}` is restricted to maintainers.", requester, command));
+            }
+            moderation::decide(config, octo, issue, requester, command == "confirm").await
+        }
+        "digest" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `digest` is restricted to maintainers.", requester));
+            }
+            digest::build(config, repo_config, octo).await
+        }
+        "evaluate" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `evaluate` is restricted to maintainers.", requester));
+            }
+            evaluate::run(config, &kv_args(args)).await
+        }
+        "deep-dive" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `deep-dive` is restricted to maintainers.", requester));
+            }
+            deep_dive::run(config, octo, issue).await
+        }
+        "duplicate" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `duplicate` is restricted to maintainers.", requester));
+            }
+            duplicate::run(config, octo, issue, requester, args).await
+        }
+        "export" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `export` is restricted to maintainers.", requester));
+            }
+            export::run(config, octo, issue).await
+        }
+        "feedback" => Ok(feedback::report(config, octo).await),
+        "good-first-issues" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `good-first-issues` is restricted to maintainers.", requester));
+            }
+            good_first::run(config, repo_config, octo).await
+        }
+        "healthcheck" => {
+            if !permissions::is_admin(config, octo, requester).await {
+                return Ok(format!("@{} `healthcheck` is restricted to repository admins.", requester));
+            }
+            Ok(healthcheck::run(config, repo_config, octo).await)
+        }
+        "import" => {
+            if !triage_import::is_trusted(config, octo, requester).await {
+                return Ok(format!("@{} `import` is restricted to maintainers and trusted tools.", requester));
+            }
+            let decision = match triage_import::parse_comment(body) {
+                Ok(decision) => decision,
+                Err(error) => return Ok(format!("Could not import: {}", error)),
+            };
+            let number = decision.issue.unwrap_or(issue.number);
+            match triage_import::apply(config, repo_config, octo, requester, number, &decision).await {
+                Ok(report) => Ok(report),
+                Err(error) => Ok(format!("Triage decision rejected: {}", error)),
+            }
+        }
+        "last-dry-run" => {
+            if !permissions::is_admin(config, octo, requester).await {
+                return Ok(format!("@{} `last-dry-run` is restricted to repository admins.", requester));
+            }
+            Ok(dry_run::last(config, args))
+        }
+        "lock" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `lock` is restricted to maintainers.", requester));
+            }
+            moderation::run_lock(config, octo, issue, requester, args).await
+        }
+        "milestone-status" => milestone::status_report(config, octo, args).await,
+        "pin-summary" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `pin-summary` is restricted to maintainers.", requester));
+            }
+            pin::run(config, repo_config, octo, issue, requester, args).await
+        }
+        "prefs" => Ok(a11y::set_pref(config, requester, args)),
+        "replay" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `replay` is restricted to maintainers.", requester));
+            }
+            replay::run(config, octo, args).await
+        }
+        "retitle" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `retitle` is restricted to maintainers.", requester));
+            }
+            titles::retitle(config, octo, issue, requester, args).await
+        }
+        "state" => {
+            if !permissions::is_admin(config, octo, requester).await {
+                return Ok(format!("@{} `state` is restricted to repository admins.", requester));
+            }
+            state::run(config, octo, requester, args).await
+        }
+        "stats" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `stats` is restricted to maintainers.", requester));
+            }
+            Ok(metrics::report(config, args))
+        }
+        "triage-queue" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `triage-queue` is restricted to maintainers.", requester));
+            }
+            Ok(confidence::render_queue(config))
+        }
+        "unassign" => assign::unassign(config, octo, issue, requester, args).await,
+        "unlock" => {
+            if !permissions::is_maintainer(config, octo, requester).await {
+                return Ok(format!("@{} `unlock` is restricted to maintainers.", requester));
+            }
+            moderation::run_unlock(config, octo, issue, requester).await
+        }
+        "whatsnew" => features::whatsnew(config, repo_config),
+        _ => Ok(format!(
+            "Unknown command `{}`. Available commands: `assign`, `assign-me`, `backfill`, `capabilities`, `confirm`, `deep-dive`, `digest`, `dismiss`, `duplicate`, `evaluate`, `export`, `feedback`, `good-first-issues`, `healthcheck`, `import`, `last-dry-run`, `lock`, `milestone-status`, `pin-summary`, `prefs`, `replay`, `retitle`, `state`, `stats`, `triage-queue`, `unassign`, `unlock`, `whatsnew`.",
+            command
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str = "@flows_bot";
+
+    #[test]
+    fn bot_subcommand_reads_the_command_and_its_arguments() {
+        assert_eq!(bot_subcommand("@flows_bot digest", PHRASE), Some(("digest".to_string(), "")));
+        assert_eq!(bot_subcommand("Thanks! @flows_bot Assign @alice \nmore text", PHRASE), Some(("assign".to_string(), "@alice")));
+    }
+
+    #[test]
+    fn bot_subcommand_needs_a_command_on_the_same_line() {
+        assert_eq!(bot_subcommand("@flows_bot", PHRASE), None);
+        assert_eq!(bot_subcommand("@flows_bot\nlock spam", PHRASE), None);
+        assert_eq!(bot_subcommand("no command here", PHRASE), None);
+    }
+
+    #[test]
+    fn commands_quoted_without_the_mention_do_not_trigger() {
+        assert_eq!(bot_subcommand("a maintainer can apply it with `flows_bot retitle`.", PHRASE), None);
+        assert_eq!(bot_subcommand("reply with the bot's `confirm` command to lock it", PHRASE), None);
+    }
+
+    #[test]
+    fn kv_args_parses_pairs_and_skips_the_rest() {
+        let args = kv_args("models=a,b Limit=5 verbose");
+        assert_eq!(args.len(), 2);
+        assert_eq!(args["models"], "a,b");
+        assert_eq!(args["limit"], "5");
+    }
+}
@@ -0,0 +1,63 @@
+use crate::config::Config;
+use crate::summary::{self, Detail};
+use crate::{footer, llm, persona, search};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+
+/// Reads `#456` or an issue URL in this repository from after the compare phrase.
+fn target(config: &Config, body: &str) -> Option<u64> {
+    let arg = search::query(body, &config.compare_phrase).split_whitespace().next()?;
+    let arg = arg.trim_end_matches(|c: char| !c.is_ascii_digit());
+    let arg = arg.rsplit('/').next().unwrap_or(arg);
+    arg.trim_start_matches('#').parse::<u64>().ok()
+}
+
+async fn summarize(config: &Config, octo: &Octocrab, issue: &Issue) -> anyhow::Result<String> {
+    let context = summary::issue_context(octo, &config.owner, &config.repo, issue).await?;
+    summary::summarize(&config.llm, issue, &context, Detail::Full)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+}
+
+/// Summarizes this issue and the one named after the compare phrase, then compares them:
+/// overlaps, differences in symptoms and environment, and whether they likely share a
+/// root cause.
+pub async fn run(config: &Config, octo: &Octocrab, issue: &Issue, body: &str) -> anyhow::Result<String> {
+    let Some(number) = target(config, body) else {
+        return Ok("Usage: follow the compare phrase with the `<issue>` to compare against.".to_string());
+    };
+    if number == issue.number {
+        return Ok("An issue can't be compared with itself.".to_string());
+    }
+    let other = match octo.issues(&config.owner, &config.repo).get(number).await {
+        Ok(other) => other,
+        Err(error) => {
+            log::debug!("Could not get comparison target #{}: {}", number, error);
+            return Ok(format!("Issue #{} was not found in this repository.", number));
+        }
+    };
+
+    let ours = summarize(config, octo, issue).await?;
+    let theirs = summarize(config, octo, &other).await?;
+
+    let cfg = config.llm.profile("compare");
+    let sys_prompt = cfg.persona.apply(
+        "You help triagers of the WasmEdge project decide whether two GitHub issues should be merged into one discussion.",
+    );
+    let usr_prompt = format!(
+        "Issue #{} '{}':\n{}\n\nIssue #{} '{}':\n{}\n\nCompare them in Markdown with these sections: **Overlap**, **Differences** (symptoms, environment and versions), and **Same root cause?** starting with Likely, Possibly or Unlikely followed by the reasoning and, if they should be merged, which issue to keep.",
+        issue.number, issue.title, ours, other.number, other.title, theirs
+    );
+    let conv_id = format!("compare_{}_{}", issue.html_url, other.number);
+    let comparison = llm::chat(&cfg, &conv_id, &sys_prompt, &usr_prompt, 768)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let comparison = persona::enforce(&cfg, &conv_id, comparison, 768).await;
+
+    Ok(format!(
+        "**Comparison of #{} and #{}**\n\n{}{}",
+        issue.number,
+        other.number,
+        comparison,
+        footer::render(config, None)
+    ))
+}
@@ -0,0 +1,129 @@
+use crate::audit;
+use crate::batch;
+use crate::config::Config;
+use crate::mutations;
+use crate::permissions;
+use crate::repo_config::RepoConfig;
+use crate::store;
+use github_flows::octocrab::Octocrab;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const QUEUE_KEY: &str = "triage:queue";
+const MAX_QUEUED: usize = 200;
+
+/// Prompt suffix asking classifiers for a calibrated confidence alongside their answer.
+pub const INSTRUCTION: &str = "Also include \"confidence\": a number from 0 to 1 giving the probability that your answer is correct; be calibrated, and use low values when the information is ambiguous or missing.";
+
+/// A classification the bot declined to apply, waiting for a human.
+#[derive(Serialize, Deserialize)]
+pub struct Queued {
+    pub issue: u64,
+    pub feature: String,
+    pub proposal: String,
+    pub confidence: f64,
+    pub at: i64,
+}
+
+/// Reads a model's `confidence` field, treating a missing or malformed one as no confidence.
+pub fn parse(value: &Value) -> f64 {
+    value["confidence"].as_f64().unwrap_or(0.0).clamp(0.0, 1.0)
+}
+
+/// The minimum confidence for `feature` to act on its own.
+pub fn threshold(repo_config: &RepoConfig, feature: &str) -> f64 {
+    repo_config
+        .confidence
+        .thresholds
+        .get(feature)
+        .copied()
+        .unwrap_or(repo_config.confidence.default_threshold)
+}
+
+pub fn is_confident(repo_config: &RepoConfig, feature: &str, confidence: f64) -> bool {
+    confidence >= threshold(repo_config, feature)
+}
+
+/// Declines to act on a low-confidence classification: labels the issue for human triage,
+/// queues the proposal so a maintainer can review it, and leaves a note in the issue's
+/// daily batch.
+pub async fn abstain(
+    config: &Config,
+    repo_config: &RepoConfig,
+    octo: &Octocrab,
+    issue_number: u64,
+    feature: &str,
+    proposal: &str,
+    confidence: f64,
+) {
+    log::info!(
+        "Abstaining from {} on #{} (confidence {:.2} < {:.2}): {}",
+        feature,
+        issue_number,
+        confidence,
+        threshold(repo_config, feature),
+        proposal
+    );
+
+    let label = repo_config.confidence.queue_label.clone();
+    if let Err(error) = mutations::add_labels(config, octo, issue_number, &[label]).await {
+        permissions::check(config, octo, "label issues for human triage", permissions::ISSUES_WRITE, &error).await;
+    }
+
+    let mut queue = queued(config);
+    queue.retain(|q| !(q.issue == issue_number && q.feature == feature));
+    queue.push(Queued {
+        issue: issue_number,
+        feature: feature.to_string(),
+        proposal: proposal.to_string(),
+        confidence,
+        at: chrono::Utc::now().timestamp(),
+    });
+    if queue.len() > MAX_QUEUED {
+        queue.drain(..queue.len() - MAX_QUEUED);
+    }
+    match serde_json::to_value(&queue) {
+        Ok(value) => store::set(config, QUEUE_KEY, value, None),
+        Err(error) => log::error!("Error serializing triage queue: {}", error),
+    }
+
+    batch::enqueue(
+        config,
+        issue_number,
+        &format!("Triage ({}): not confident enough to act on \"{}\" ({:.0}%); left for a maintainer.", feature, proposal, confidence * 100.0),
+    );
+    audit::record(
+        config,
+        feature,
+        "abstained",
+        Some(issue_number),
+        json!({ "proposal": proposal, "confidence": confidence }),
+    );
+}
+
+/// Returns the human triage queue, oldest first.
+pub fn queued(config: &Config) -> Vec<Queued> {
+    store::get(config, QUEUE_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<Queued>>(v).ok())
+        .unwrap_or_default()
+}
+
+/// Renders the `triage-queue` reply.
+pub fn render_queue(config: &Config) -> String {
+    let queue = queued(config);
+    if queue.is_empty() {
+        return "The human triage queue is empty.".to_string();
+    }
+    let rows = queue
+        .iter()
+        .rev()
+        .take(30)
+        .map(|q| format!("| #{} | {} | {} | {:.2} |", q.issue, q.feature, q.proposal, q.confidence))
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!(
+        "## Awaiting human triage ({} item(s))\n\n| Issue | Feature | Proposal | Confidence |\n|---|---|---|---|\n{}",
+        queue.len(),
+        rows
+    )
+}
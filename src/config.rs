@@ -0,0 +1,120 @@
+use crate::footer;
+use crate::llm::LlmConfig;
+use crate::summary::Detail;
+use std::env;
+
+/// Deployment settings read from the flow's environment variables, bound to the
+/// repository the current event came from.
+pub struct Config {
+    pub owner: String,
+    pub repo: String,
+    pub trigger_phrase: String,
+    pub release_notes_phrase: String,
+    pub bot_phrase: String,
+    pub todos_phrase: String,
+    pub suggest_fix_phrase: String,
+    pub extract_phrase: String,
+    pub search_phrase: String,
+    pub compare_phrase: String,
+    pub priority_reporters: Vec<String>,
+    pub priority_label: String,
+    pub summary_detail: Detail,
+    /// Never @-mention anyone in bot comments.
+    pub no_mentions: bool,
+    /// Post one combined comment per issue for everything produced by a single event.
+    pub consolidate_comments: bool,
+    /// Delete acknowledgement and progress comments once their result is posted.
+    pub delete_superseded: bool,
+    /// `bot_mode=dry-run`: run the full pipeline but record writes instead of making them.
+    pub dry_run: bool,
+    /// Trailer on bot outputs; see `footer::render`.
+    pub footer_template: String,
+    /// Quiet-hours windows in minutes of the UTC day, from the repo config's `filters`.
+    pub quiet_hours: Vec<(u32, u32)>,
+    /// `owner/repo` of the mirror and tracking repositories, whose issues are never quoted.
+    pub private_repos: Vec<String>,
+    pub llm: LlmConfig,
+}
+
+impl Config {
+    pub fn from_env(owner: &str, repo: &str) -> Self {
+        Config {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            trigger_phrase: env::var("trigger_phrase").unwrap_or("@flows_summarize".to_string()),
+            release_notes_phrase: env::var("release_notes_phrase").unwrap_or("@flows_release_notes".to_string()),
+            bot_phrase: env::var("bot_phrase").unwrap_or("@flows_bot".to_string()),
+            todos_phrase: env::var("todos_phrase").unwrap_or("@flows_todos".to_string()),
+            suggest_fix_phrase: env::var("suggest_fix_phrase").unwrap_or("@flows_suggest_fix".to_string()),
+            extract_phrase: env::var("extract_phrase").unwrap_or("@flows_extract".to_string()),
+            search_phrase: env::var("search_phrase").unwrap_or("@flows_search".to_string()),
+            compare_phrase: env::var("compare_phrase").unwrap_or("@flows_compare".to_string()),
+            priority_reporters: list_var("priority_reporters"),
+            priority_label: env::var("priority_label").unwrap_or("priority-reporter".to_string()),
+            summary_detail: env::var("summary_detail")
+                .ok()
+                .and_then(|d| Detail::parse(&d))
+                .unwrap_or(Detail::Brief),
+            no_mentions: env::var("no_mentions").unwrap_or_default() == "true",
+            consolidate_comments: env::var("consolidate_comments").unwrap_or_default() == "true",
+            delete_superseded: env::var("delete_superseded").unwrap_or_default() != "false",
+            dry_run: env::var("bot_mode").unwrap_or_default() == "dry-run",
+            footer_template: env::var("footer_template").unwrap_or(footer::DEFAULT_TEMPLATE.to_string()),
+            quiet_hours: Vec::new(),
+            private_repos: Vec::new(),
+            llm: LlmConfig::from_env(),
+        }
+    }
+
+    /// Every phrase that makes the bot act on a comment.
+    pub fn phrases(&self) -> [&str; 8] {
+        [
+            &self.trigger_phrase,
+            &self.release_notes_phrase,
+            &self.bot_phrase,
+            &self.todos_phrase,
+            &self.suggest_fix_phrase,
+            &self.extract_phrase,
+            &self.search_phrase,
+            &self.compare_phrase,
+        ]
+    }
+
+    /// Namespaces a state key by repository so one deployment can serve several repos.
+    pub fn state_key(&self, name: &str) -> String {
+        format!("{}/{}:{}", self.owner, self.repo, name)
+    }
+}
+
+/// Returns every `(owner, repo)` this deployment serves. `github_repos` holds a
+/// comma-separated registry of `owner/repo` entries across any number of orgs or users;
+/// without it the single `github_owner`/`github_repo` pair is used.
+pub fn registry() -> Vec<(String, String)> {
+    let entries = list_var("github_repos")
+        .iter()
+        .filter_map(|entry| match entry.split_once('/') {
+            Some((owner, repo)) if !owner.is_empty() && !repo.is_empty() => Some((owner.to_string(), repo.to_string())),
+            _ => {
+                log::error!("Ignoring malformed github_repos entry '{}'", entry);
+                None
+            }
+        })
+        .collect::<Vec<(String, String)>>();
+    if !entries.is_empty() {
+        return entries;
+    }
+
+    let owner = env::var("github_owner").expect("github_owner not set");
+    let repo = env::var("github_repo").expect("github_repo not set");
+    vec![(owner, repo)]
+}
+
+/// Reads a comma-separated environment variable into a list, skipping empty entries.
+pub fn list_var(name: &str) -> Vec<String> {
+    env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().trim_start_matches('@').to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
@@ -0,0 +1,59 @@
+use crate::config::Config;
+use crate::store;
+use github_flows::octocrab::models::webhook_events::WebhookEventPayload;
+use serde_json::json;
+use std::env;
+
+/// Builds an idempotency key for an event. The delivery GUID isn't exposed to the handler,
+/// so the key is derived from the subject's id, the action and its last update time.
+pub fn delivery_key(specific: &WebhookEventPayload) -> Option<String> {
+    match specific {
+        WebhookEventPayload::IssueComment(e) => Some(format!(
+            "comment:{}:{:?}:{}",
+            e.comment.id,
+            e.action,
+            e.comment.updated_at.map(|t| t.timestamp()).unwrap_or_default()
+        )),
+        WebhookEventPayload::Issues(e) => Some(format!(
+            "issue:{}:{:?}:{}",
+            e.issue.id,
+            e.action,
+            e.issue.updated_at.timestamp()
+        )),
+        WebhookEventPayload::Release(e) => {
+            let release = serde_json::to_value(e).ok()?;
+            Some(format!(
+                "release:{}:{}",
+                release["release"]["id"],
+                release["action"].as_str().unwrap_or_default()
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if the event was already handled, otherwise marks it as handled.
+/// The store has no compare-and-set, so the mark is written before any work starts to
+/// keep the window for concurrent redeliveries as small as possible.
+pub fn seen_before(config: &Config, key: &str) -> bool {
+    let name = format!("delivery:{}", key);
+    if store::get(config, &name).is_some() {
+        return true;
+    }
+    let ttl = env::var("dedup_ttl_secs").ok().and_then(|n| n.parse::<i64>().ok()).unwrap_or(86400);
+    store::set(config, &name, json!(true), Some(ttl));
+    false
+}
+
+/// Returns true if a trigger in this comment ran within the last `edit_debounce_mins`,
+/// otherwise records that it runs now. Editing a comment re-delivers it, so this keeps a
+/// quick typo fix from running the same command twice.
+pub fn debounce(config: &Config, comment_id: &str) -> bool {
+    let name = format!("triggered:{}", comment_id);
+    if store::get(config, &name).is_some() {
+        return true;
+    }
+    let mins = env::var("edit_debounce_mins").ok().and_then(|n| n.parse::<i64>().ok()).unwrap_or(5);
+    store::set(config, &name, json!(true), Some(mins * 60));
+    false
+}
@@ -0,0 +1,72 @@
+use crate::config::Config;
+use crate::summary::{self, Detail};
+use crate::{code, gist, images, llm, references};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use std::env;
+use std::time::Duration;
+
+const MAX_CODE_REFS: usize = 5;
+const MAX_CODE_LINES: usize = 120;
+
+/// Multi-pass analysis: a full-detail thread summary, related issues and linked code feed
+/// a hypothesis pass, which then feeds the long-form report.
+async fn analyze(config: &Config, octo: &Octocrab, issue: &Issue) -> anyhow::Result<String> {
+    let mut context = summary::issue_context(octo, &config.owner, &config.repo, issue).await?;
+    images::annotate(&config.llm, &mut context).await;
+
+    let related = references::expand(config, octo, issue).await;
+    context.push_str(&references::context_block(&related));
+
+    let thread = summary::summarize(&config.llm, issue, &context, Detail::Full)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let mut code_context = String::new();
+    for code_ref in code::extract_links(&context).iter().take(MAX_CODE_REFS) {
+        if let Some(excerpt) = code::fetch_excerpt(octo, code_ref, MAX_CODE_LINES).await {
+            code_context.push_str(&format!("{}\n\n", excerpt));
+        }
+    }
+
+    let cfg = config.llm.profile("deep_dive");
+    let sys_prompt = "You are a senior maintainer of the WasmEdge project investigating a difficult issue.";
+    let hypotheses_prompt = format!(
+        "Thread analysis:\n{}\n\nLinked code:\n{}\n\nList the most plausible root-cause hypotheses, the evidence for and against each, and the experiments that would confirm or rule them out.",
+        thread,
+        if code_context.is_empty() { "(none)" } else { code_context.as_str() }
+    );
+    let hypotheses = llm::chat(&cfg, &format!("deepdive_{}", issue.html_url), sys_prompt, &hypotheses_prompt, 1024)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let report_prompt = format!(
+        "Thread analysis:\n{}\n\nHypotheses:\n{}\n\nWrite a long-form Markdown report with sections: Summary, Timeline, Environment, Root-cause hypotheses, Relevant code, Related issues, Recommended next steps.",
+        thread, hypotheses
+    );
+    let report = llm::chat(&cfg, &format!("deepdive_report_{}", issue.html_url), sys_prompt, &report_prompt, 2048)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    Ok(format!("# Deep dive: {}\n\n{}\n{}", issue.title, report, references::section(&related)))
+}
+
+/// Runs a deep dive within the `deep_dive_timeout_secs` budget and attaches the report as
+/// a gist, falling back to a collapsed comment when gists are unavailable.
+pub async fn run(config: &Config, octo: &Octocrab, issue: &Issue) -> anyhow::Result<String> {
+    let timeout = env::var("deep_dive_timeout_secs").ok().and_then(|n| n.parse::<u64>().ok()).unwrap_or(300);
+    let report = tokio::time::timeout(Duration::from_secs(timeout), analyze(config, octo, issue))
+        .await
+        .map_err(|_| anyhow::anyhow!("deep dive exceeded {}s", timeout))??;
+
+    let filename = format!("{}-{}-issue-{}-deep-dive.md", config.owner, config.repo, issue.number);
+    match gist::create(config, octo, &format!("Deep dive: {}", issue.html_url), &[(filename, report.clone())]).await {
+        Ok(url) => Ok(format!("🔬 Deep-dive report for #{}: {}", issue.number, url)),
+        Err(error) => {
+            log::warn!("Could not create gist, posting report inline: {}", error);
+            Ok(format!(
+                "<details><summary>🔬 Deep-dive report for #{}</summary>\n\n{}\n\n</details>",
+                issue.number, report
+            ))
+        }
+    }
+}
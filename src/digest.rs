@@ -0,0 +1,178 @@
+use crate::author::Author;
+use crate::config::Config;
+use crate::dry_run;
+use crate::outbox;
+use crate::priority;
+use crate::repo_config::RepoConfig;
+use crate::store;
+use github_flows::octocrab::{models::issues::Issue, params, Octocrab};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const DIGEST_LIMIT: usize = 30;
+const SIGNOFF_KEY: &str = "digest:signoff";
+
+/// A sign-off request posted for one component in a weekly digest.
+#[derive(Serialize, Deserialize)]
+struct SignOff {
+    component: String,
+    comment_id: u64,
+}
+
+/// Authors are named by login rather than mentioned, so a digest doesn't ping every reporter.
+fn line(issue: &Issue) -> String {
+    format!("* #{} {} ({})", issue.number, issue.title, Author::from_login(&issue.user.login))
+}
+
+fn lines(issues: &[&Issue]) -> String {
+    issues.iter().take(DIGEST_LIMIT).map(|i| line(i)).collect::<Vec<String>>().join("\n")
+}
+
+fn mentions(owners: &[String]) -> String {
+    owners.iter().map(|o| format!("@{}", o)).collect::<Vec<String>>().join(" ")
+}
+
+fn logins(owners: &[String]) -> String {
+    owners.join(", ")
+}
+
+/// Builds a Markdown digest of open issues, listing priority issues first and then
+/// grouping the rest by configured component label.
+pub async fn build(config: &Config, repo_config: &RepoConfig, octo: &Octocrab) -> anyhow::Result<String> {
+    let open = octo
+        .issues(&config.owner, &config.repo)
+        .list()
+        .state(params::State::Open)
+        .sort(params::issues::Sort::Updated)
+        .per_page(100)
+        .send()
+        .await?
+        .items
+        .into_iter()
+        .filter(|i| i.pull_request.is_none())
+        .collect::<Vec<Issue>>();
+
+    let (prioritized, others): (Vec<&Issue>, Vec<&Issue>) = open.iter().partition(|i| {
+        priority::is_priority_reporter(config, &i.user.login)
+            || i.labels.iter().any(|l| l.name == config.priority_label)
+    });
+
+    let mut out = format!("## Open issue digest for {}/{}\n", config.owner, config.repo);
+    if !prioritized.is_empty() {
+        out.push_str(&format!("\n### Priority\n\n{}\n", lines(&prioritized)));
+    }
+
+    let components = &repo_config.digest.components;
+    for component in components {
+        let issues = others
+            .iter()
+            .filter(|i| i.labels.iter().any(|l| l.name == component.label))
+            .copied()
+            .collect::<Vec<&Issue>>();
+        if issues.is_empty() {
+            continue;
+        }
+        out.push_str(&format!(
+            "\n### {} ({} open, owners: {})\n\n{}\n",
+            component.label,
+            issues.len(),
+            logins(&component.owners),
+            lines(&issues)
+        ));
+    }
+
+    let unassigned = others
+        .iter()
+        .filter(|i| !components.iter().any(|c| i.labels.iter().any(|l| l.name == c.label)))
+        .copied()
+        .collect::<Vec<&Issue>>();
+    let heading = if components.is_empty() { "Recently updated" } else { "Other" };
+    out.push_str(&format!("\n### {}\n\n{}\n", heading, lines(&unassigned)));
+    if unassigned.len() > DIGEST_LIMIT {
+        out.push_str(&format!("\n…and {} more.\n", unassigned.len() - DIGEST_LIMIT));
+    }
+
+    Ok(out)
+}
+
+/// Whether any component owner reacted 👍 to last week's sign-off comment.
+async fn signed_off(config: &Config, octo: &Octocrab, signoff: &SignOff, owners: &[String]) -> bool {
+    let route = format!(
+        "/repos/{}/{}/issues/comments/{}/reactions?content=%2B1&per_page=100",
+        config.owner, config.repo, signoff.comment_id
+    );
+    match octo.get::<Value, _, ()>(route, None).await {
+        Ok(reactions) => reactions.as_array().into_iter().flatten().any(|r| {
+            r["user"]["login"]
+                .as_str()
+                .map_or(false, |l| owners.iter().any(|o| o.eq_ignore_ascii_case(l)))
+        }),
+        Err(error) => {
+            log::error!("Error reading sign-off reactions for {}: {}", signoff.component, error);
+            false
+        }
+    }
+}
+
+/// Opens this week's digest issue, escalating components whose owners didn't sign off on
+/// last week's digest, and posts one sign-off comment per component for owners to 👍.
+pub async fn publish_weekly(config: &Config, repo_config: &RepoConfig, octo: &Octocrab) -> anyhow::Result<()> {
+    let digest_config = &repo_config.digest;
+    let previous = store::get(config, SIGNOFF_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<SignOff>>(v).ok())
+        .unwrap_or_default();
+
+    let mut escalated = Vec::new();
+    for signoff in &previous {
+        let Some(component) = digest_config.components.iter().find(|c| c.label == signoff.component) else {
+            continue;
+        };
+        if !signed_off(config, octo, signoff, &component.owners).await {
+            escalated.push(component);
+        }
+    }
+
+    let mut body = build(config, repo_config, octo).await?;
+    if !escalated.is_empty() {
+        let escalate_to = digest_config.escalate_to.clone().unwrap_or_default();
+        body.push_str(&format!(
+            "\n### ⚠️ Escalated: no sign-off last week {}\n\n{}\n",
+            escalate_to,
+            escalated
+                .iter()
+                .map(|c| format!("* {} (owners: {})", c.label, logins(&c.owners)))
+                .collect::<Vec<String>>()
+                .join("\n")
+        ));
+    }
+
+    let issues = octo.issues(&config.owner, &config.repo);
+    let title = format!("Weekly issue digest ({})", chrono::Utc::now().format("%Y-%m-%d"));
+    if dry_run::intercept(config, "create_issue", None, json!({ "title": title, "body": body })) {
+        return Ok(());
+    }
+    let digest_issue = issues.create(title).body(outbox::scrub(config, &body)).labels(vec![digest_config.label.clone()]).send().await?;
+
+    let mut signoffs = Vec::new();
+    for component in digest_config.components.iter().filter(|c| !c.owners.is_empty()) {
+        let request = format!(
+            "**{}** sign-off: {} please review this component's open issues above and react 👍 to this comment to sign off. Components without sign-off are escalated next week.",
+            component.label,
+            mentions(&component.owners)
+        );
+        match outbox::post_parts(config, octo, digest_issue.number, &[outbox::scrub(config, &request)]).await {
+            Ok(posted) => match posted.first() {
+                Some(id) => signoffs.push(SignOff {
+                    component: component.label.clone(),
+                    comment_id: *id,
+                }),
+                None => log::info!("Sign-off request for {} was held for quiet hours and is not tracked", component.label),
+            },
+            Err(error) => log::error!("Error requesting sign-off for {}: {}", component.label, error),
+        }
+    }
+    store::set(config, SIGNOFF_KEY, serde_json::to_value(&signoffs)?, None);
+
+    log::info!("Published weekly digest #{} for {}/{}", digest_issue.number, config.owner, config.repo);
+    Ok(())
+}
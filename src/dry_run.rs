@@ -0,0 +1,61 @@
+use crate::audit;
+use crate::config::Config;
+use serde_json::Value;
+
+/// Audit actor under which dry-run writes are recorded.
+const ACTOR: &str = "dry-run";
+/// Characters of a would-be comment shown by `last-dry-run`.
+const PREVIEW_CHARS: usize = 1500;
+/// Writes addressed to maintainers only; `last-dry-run` replies publicly, so their content
+/// is never shown, only where they would have gone and how long they were.
+const PRIVATE_ACTIONS: &[&str] = &["mirror_comment", "tracking_comment", "team_discussion_comment", "report_leaks", "notify"];
+
+/// In `bot_mode=dry-run`, logs the write the bot would have made, records it in the audit
+/// log and returns true so the caller skips it. Otherwise returns false.
+pub fn intercept(config: &Config, action: &str, issue: Option<u64>, details: Value) -> bool {
+    if !config.dry_run {
+        return false;
+    }
+    log::info!("[dry-run] Would {} on {:?}: {}", action, issue, details);
+    audit::record(config, ACTOR, action, issue, details);
+    true
+}
+
+/// Renders the most recent would-be writes, newest first, for `@flows_bot last-dry-run [n]`.
+pub fn last(config: &Config, args: &str) -> String {
+    let limit = args.trim().parse::<usize>().unwrap_or(10);
+    let entries = audit::recent(config)
+        .into_iter()
+        .filter(|e| e.actor == ACTOR)
+        .rev()
+        .take(limit)
+        .collect::<Vec<audit::Entry>>();
+    if entries.is_empty() {
+        return "No dry-run activity recorded.".to_string();
+    }
+
+    let lines = entries
+        .iter()
+        .map(|e| {
+            let when = chrono::DateTime::from_timestamp(e.at, 0)
+                .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_default();
+            let target = e.issue.map(|n| format!(" on #{}", n)).unwrap_or_default();
+            match e.details["body"].as_str() {
+                Some(body) if PRIVATE_ACTIONS.contains(&e.action.as_str()) => {
+                    let destination = e.details["repo"].as_str().or(e.details["team"].as_str());
+                    let destination = destination.map(|d| format!(" in {}", d)).unwrap_or_default();
+                    format!("* {} **{}**{}{}: {} characters, withheld as private", when, e.action, target, destination, body.chars().count())
+                }
+                Some(body) => {
+                    let preview = body.chars().take(PREVIEW_CHARS).collect::<String>();
+                    format!("* {} **{}**{}\n  <details><summary>Would have posted</summary>\n\n{}\n\n  </details>", when, e.action, target, preview)
+                }
+                None => format!("* {} **{}**{}: `{}`", when, e.action, target, e.details),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    let mode = if config.dry_run { "on" } else { "off" };
+    format!("🧪 **Last dry-run writes** (dry-run is {})\n\n{}", mode, lines)
+}
@@ -0,0 +1,76 @@
+use crate::config::Config;
+use crate::{audit, llm, mutations, outbox, permissions, persona};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use serde_json::json;
+
+/// Characters of each issue body shown to the model.
+const MAX_BODY_CHARS: usize = 2000;
+
+/// Reads the target from `#123`, `123` or an issue URL in this repository.
+fn target(args: &str) -> Option<u64> {
+    let arg = args.split_whitespace().next()?;
+    let arg = arg.rsplit('/').next().unwrap_or(arg);
+    arg.trim_start_matches('#').parse::<u64>().ok()
+}
+
+fn excerpt(issue: &Issue) -> String {
+    let body = issue.body.clone().unwrap_or_default().chars().take(MAX_BODY_CHARS).collect::<String>();
+    format!("#{} '{}':\n{}", issue.number, issue.title, body)
+}
+
+/// One sentence on why the two issues report the same thing, or an empty string if the
+/// model is unavailable.
+async fn explain(config: &Config, issue: &Issue, original: &Issue) -> String {
+    let cfg = config.llm.profile("duplicate");
+    let sys_prompt = cfg
+        .persona
+        .apply("You explain to GitHub users why one issue duplicates another. Reply with a single sentence naming the shared problem; do not restate the titles.");
+    let usr_prompt = format!("Duplicate {}\n\nOriginal {}", excerpt(issue), excerpt(original));
+    let conv_id = format!("duplicate_{}", issue.html_url);
+    match llm::chat(&cfg, &conv_id, &sys_prompt, &usr_prompt, 96).await {
+        Ok(reply) => persona::enforce(&cfg, &conv_id, reply, 96).await.lines().next().unwrap_or_default().trim().to_string(),
+        Err(error) => {
+            log::error!("Error explaining duplicate #{} of #{}: {}", issue.number, original.number, error);
+            String::new()
+        }
+    }
+}
+
+/// `duplicate #123`: cross-links both issues, labels this one `duplicate` and closes it as
+/// not planned. Returns the comment for this issue.
+pub async fn run(config: &Config, octo: &Octocrab, issue: &Issue, requester: &str, args: &str) -> anyhow::Result<String> {
+    let number = match target(args) {
+        Some(number) => number,
+        None => return Ok("Usage: `duplicate #123`.".to_string()),
+    };
+    if number == issue.number {
+        return Ok("An issue cannot duplicate itself.".to_string());
+    }
+    let original = match octo.issues(&config.owner, &config.repo).get(number).await {
+        Ok(original) if original.pull_request.is_none() => original,
+        Ok(_) => return Ok(format!("#{} is a pull request, not an issue.", number)),
+        Err(error) => {
+            log::debug!("Could not get duplicate target #{}: {}", number, error);
+            return Ok(format!("Issue #{} was not found in this repository.", number));
+        }
+    };
+
+    let why = explain(config, issue, &original).await;
+    let why = if why.is_empty() { String::new() } else { format!(" {}", why) };
+
+    let back_link = format!("#{} was closed as a duplicate of this issue.{}", issue.number, why);
+    if let Err(error) = outbox::reply(config, octo, original.number, &back_link).await {
+        permissions::check(config, octo, "cross-link duplicates", permissions::ISSUES_WRITE, &error).await;
+    }
+
+    if let Err(error) = mutations::add_labels(config, octo, issue.number, &["duplicate".to_string()]).await {
+        permissions::check(config, octo, "label duplicates", permissions::ISSUES_WRITE, &error).await;
+    }
+    if let Err(error) = mutations::close_issue(config, octo, requester, issue.number, "not_planned").await {
+        permissions::check(config, octo, "close duplicates", permissions::ISSUES_WRITE, &error).await;
+        return Err(error.into());
+    }
+
+    audit::record(config, requester, "duplicate", Some(issue.number), json!({ "duplicate_of": original.number }));
+    Ok(format!("Closing as a duplicate of #{}.{}", original.number, why))
+}
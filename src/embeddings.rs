@@ -0,0 +1,116 @@
+use crate::config::Config;
+use crate::llm::LlmConfig;
+use crate::store;
+use github_flows::octocrab::models::issues::Issue;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const INDEX_KEY: &str = "embedding:index";
+/// Issues kept in the semantic index; the oldest are evicted first.
+const MAX_INDEXED: usize = 1000;
+/// Characters of the issue body embedded along with the title.
+const MAX_BODY_CHARS: usize = 2000;
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    number: u64,
+    title: String,
+}
+
+fn vector_key(number: u64) -> String {
+    format!("embedding:{}", number)
+}
+
+fn index(config: &Config) -> Vec<Entry> {
+    store::get(config, INDEX_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<Entry>>(v).ok())
+        .unwrap_or_default()
+}
+
+/// Calls the OpenAI-compatible `/embeddings` endpoint with `llm_embedding_model`.
+pub async fn embed(cfg: &LlmConfig, text: &str) -> Result<Vec<f32>, String> {
+    let model = cfg.embedding_model.as_deref().ok_or("llm_embedding_model is not set")?;
+    let url = format!("{}/embeddings", cfg.api_endpoint.trim_end_matches('/'));
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy) = &cfg.proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy).map_err(|e| e.to_string())?);
+    }
+    let client = builder.build().map_err(|e| e.to_string())?;
+
+    let mut req = client.post(&url).json(&json!({ "model": model, "input": text }));
+    if !cfg.api_key.is_empty() {
+        req = req.bearer_auth(&cfg.api_key);
+    }
+    for (name, value) in &cfg.headers {
+        req = req.header(name.as_str(), value.as_str());
+    }
+
+    let resp = req.send().await.map_err(|e| e.to_string())?;
+    let status = resp.status();
+    let value: Value = resp.json().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(format!("{}: {}", status, value));
+    }
+    value["data"][0]["embedding"]
+        .as_array()
+        .map(|v| v.iter().filter_map(|x| x.as_f64()).map(|x| x as f32).collect())
+        .ok_or_else(|| format!("Unexpected response: {}", value))
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot = a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denom = norm(a) * norm(b);
+    if denom == 0.0 {
+        0.0
+    } else {
+        dot / denom
+    }
+}
+
+/// Embeds an issue's title and body into the repository's semantic index.
+pub async fn index_issue(config: &Config, issue: &Issue) {
+    if config.llm.embedding_model.is_none() {
+        return;
+    }
+    let body = issue.body.clone().unwrap_or_default().chars().take(MAX_BODY_CHARS).collect::<String>();
+    let vector = match embed(&config.llm, &format!("{}\n{}", issue.title, body)).await {
+        Ok(vector) => vector,
+        Err(error) => {
+            log::error!("Error embedding #{}: {}", issue.number, error);
+            return;
+        }
+    };
+    store::set(config, &vector_key(issue.number), json!(vector), None);
+
+    let mut entries = index(config);
+    entries.retain(|e| e.number != issue.number);
+    entries.push(Entry {
+        number: issue.number,
+        title: issue.title.clone(),
+    });
+    if entries.len() > MAX_INDEXED {
+        for evicted in entries.drain(..entries.len() - MAX_INDEXED) {
+            store::del(config, &vector_key(evicted.number));
+        }
+    }
+    match serde_json::to_value(&entries) {
+        Ok(value) => store::set(config, INDEX_KEY, value, None),
+        Err(error) => log::error!("Error serializing embedding index: {}", error),
+    }
+}
+
+/// Returns the `limit` indexed issues closest to `query`, as `(number, title, similarity)`.
+pub async fn search(config: &Config, query: &str, limit: usize) -> Result<Vec<(u64, String, f32)>, String> {
+    let query_vector = embed(&config.llm, query).await?;
+    let mut scored = index(config)
+        .into_iter()
+        .filter_map(|e| {
+            let vector = serde_json::from_value::<Vec<f32>>(store::get(config, &vector_key(e.number))?).ok()?;
+            Some((e.number, e.title, cosine(&query_vector, &vector)))
+        })
+        .collect::<Vec<(u64, String, f32)>>();
+    scored.sort_by(|a, b| b.2.total_cmp(&a.2));
+    scored.truncate(limit);
+    Ok(scored)
+}
@@ -0,0 +1,209 @@
+use crate::config::Config;
+use crate::llm;
+use crate::mutations;
+use crate::permissions;
+use crate::repo_config::RepoConfig;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref VERSION: Regex = Regex::new(r"(?i)wasmedge(?:[ \t]+version)?[ \t:-]*v?(\d+\.\d+(?:\.\d+)?(?:-(?:alpha|beta|rc)[\w.]*)?)").unwrap();
+}
+
+/// Normalized names and the spellings that identify them, checked in order.
+const OS: &[(&str, &[&str])] = &[
+    ("android", &["android"]),
+    ("macos", &["macos", "mac os", "os x", "darwin", "apple silicon", "macbook"]),
+    ("windows", &["windows", "win10", "win11", "msvc", "powershell"]),
+    ("linux", &["linux", "ubuntu", "debian", "fedora", "centos", "rhel", "alpine", "arch linux", "wsl", "manylinux"]),
+];
+const ARCH: &[(&str, &[&str])] = &[
+    ("arm64", &["aarch64", "arm64", "apple silicon", "apple m1", "apple m2", "apple m3"]),
+    ("x86_64", &["x86_64", "x86-64", "amd64", "x64"]),
+    ("riscv64", &["riscv64", "risc-v"]),
+    ("arm", &["armv7", "armhf", "arm32"]),
+];
+
+/// Where a bug report says it ran. Fields the report doesn't state are `None`.
+#[derive(Default)]
+pub struct Environment {
+    pub version: Option<String>,
+    pub os: Option<String>,
+    pub arch: Option<String>,
+}
+
+impl Environment {
+    fn is_complete(&self) -> bool {
+        self.version.is_some() && self.os.is_some() && self.arch.is_some()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.version.is_none() && self.os.is_none() && self.arch.is_none()
+    }
+
+    /// `os/…`, `arch/…` and `version/<major>.<minor>` labels for the detected values.
+    pub fn labels(&self) -> Vec<String> {
+        let mut labels = Vec::new();
+        if let Some(os) = &self.os {
+            labels.push(format!("os/{}", os));
+        }
+        if let Some(arch) = &self.arch {
+            labels.push(format!("arch/{}", arch));
+        }
+        if let Some(version) = &self.version {
+            let minor = version.split('.').take(2).collect::<Vec<&str>>().join(".");
+            labels.push(format!("version/{}", minor));
+        }
+        labels
+    }
+}
+
+fn keyword(text: &str, table: &[(&str, &[&str])]) -> Option<String> {
+    let lower = text.to_lowercase();
+    table
+        .iter()
+        .find(|(_, spellings)| {
+            spellings.iter().any(|s| {
+                lower.match_indices(s).any(|(i, _)| {
+                    let before = lower[..i].chars().next_back();
+                    let after = lower[i + s.len()..].chars().next();
+                    !before.map_or(false, |c| c.is_alphanumeric()) && !after.map_or(false, |c| c.is_alphanumeric())
+                })
+            })
+        })
+        .map(|(name, _)| name.to_string())
+}
+
+/// Normalizes a value the model returned against one of the tables.
+fn normalize(value: Option<&str>, table: &[(&str, &[&str])]) -> Option<String> {
+    let value = value?.trim().to_lowercase();
+    table
+        .iter()
+        .find(|(name, spellings)| *name == value || spellings.iter().any(|s| *s == value))
+        .map(|(name, _)| name.to_string())
+}
+
+/// Regex and keyword heuristics over the report.
+pub fn heuristics(text: &str) -> Environment {
+    Environment {
+        version: VERSION.captures(text).map(|c| c[1].to_string()),
+        os: keyword(text, OS),
+        arch: keyword(text, ARCH),
+    }
+}
+
+/// Fills what the heuristics missed with an LLM extraction pass. Values the model returns
+/// outside the known OS and architecture names are dropped.
+async fn ask(config: &Config, issue: &Issue, text: &str, env: &mut Environment) {
+    let sys_prompt = "You read WasmEdge bug reports and extract the environment they ran in. Reply only with JSON: {\"version\": \"<WasmEdge version like 0.13.5, or null>\", \"os\": \"linux|macos|windows|android|null\", \"arch\": \"x86_64|arm64|riscv64|arm|null\"}. Use null for anything the report does not state; never guess.";
+    let reply = match llm::chat(&config.llm.profile("environment"), &format!("environment_{}", issue.html_url), sys_prompt, text, 96).await {
+        Ok(reply) => reply,
+        Err(error) => {
+            log::warn!("Could not extract the environment of #{}: {}", issue.number, error);
+            return;
+        }
+    };
+    let Some(value) = llm::extract_json(&reply) else {
+        return;
+    };
+    if env.version.is_none() {
+        env.version = value["version"]
+            .as_str()
+            .map(|v| v.trim().trim_start_matches('v').to_string())
+            .filter(|v| v.split('.').count() >= 2 && v.split('.').take(2).all(|p| p.parse::<u32>().is_ok()));
+    }
+    if env.os.is_none() {
+        env.os = normalize(value["os"].as_str(), OS);
+    }
+    if env.arch.is_none() {
+        env.arch = normalize(value["arch"].as_str(), ARCH);
+    }
+}
+
+/// Detects the environment from `text`, asking the LLM about missing fields when
+/// `environment.use_llm` is set.
+pub async fn detect(config: &Config, repo_config: &RepoConfig, issue: &Issue, text: &str) -> Environment {
+    let mut env = heuristics(text);
+    if repo_config.environment.use_llm && !env.is_complete() {
+        ask(config, issue, text, &mut env).await;
+    }
+    env
+}
+
+/// Applies the environment labels the issue doesn't have yet.
+pub async fn label(config: &Config, octo: &Octocrab, issue: &Issue, env: &Environment) {
+    let labels = env
+        .labels()
+        .into_iter()
+        .filter(|l| !issue.labels.iter().any(|existing| existing.name == *l))
+        .collect::<Vec<String>>();
+    if labels.is_empty() {
+        return;
+    }
+    if let Err(error) = mutations::add_labels(config, octo, issue.number, &labels).await {
+        permissions::check(config, octo, "apply environment labels", permissions::ISSUES_WRITE, &error).await;
+    }
+}
+
+/// Detects and labels the environment of a newly opened issue.
+pub async fn on_opened(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue) {
+    if !repo_config.environment.enabled {
+        return;
+    }
+    let text = format!("{}\n{}", issue.title, issue.body.clone().unwrap_or_default());
+    let env = detect(config, repo_config, issue, &text).await;
+    label(config, octo, issue, &env).await;
+}
+
+/// The normalized environment block shown at the top of the triage comment, or an empty
+/// string when nothing was detected.
+pub fn block(env: &Environment) -> String {
+    if env.is_empty() {
+        return String::new();
+    }
+    let unknown = "not stated".to_string();
+    format!(
+        "| WasmEdge | OS | Arch |\n|---|---|---|\n| {} | {} | {} |\n\n",
+        env.version.as_ref().unwrap_or(&unknown),
+        env.os.as_ref().unwrap_or(&unknown),
+        env.arch.as_ref().unwrap_or(&unknown)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heuristics_read_version_os_and_arch() {
+        let env = heuristics("WasmEdge version 0.13.5 on Ubuntu 22.04 (x86_64)");
+        assert_eq!(env.version.as_deref(), Some("0.13.5"));
+        assert_eq!(env.os.as_deref(), Some("linux"));
+        assert_eq!(env.arch.as_deref(), Some("x86_64"));
+        assert_eq!(env.labels(), vec!["os/linux", "arch/x86_64", "version/0.13"]);
+    }
+
+    #[test]
+    fn heuristics_read_prereleases_and_apple_silicon() {
+        let env = heuristics("Running wasmedge 0.14.0-rc.1 on a MacBook with Apple Silicon");
+        assert_eq!(env.version.as_deref(), Some("0.14.0-rc.1"));
+        assert_eq!(env.os.as_deref(), Some("macos"));
+        assert_eq!(env.arch.as_deref(), Some("arm64"));
+    }
+
+    #[test]
+    fn heuristics_match_whole_words_only() {
+        let env = heuristics("The winner is darwinian, see x640 and armv70.");
+        assert!(env.is_empty());
+    }
+
+    #[test]
+    fn heuristics_leave_unstated_fields_empty() {
+        let env = heuristics("It crashes when I call the function twice on Windows.");
+        assert_eq!(env.version, None);
+        assert_eq!(env.os.as_deref(), Some("windows"));
+        assert_eq!(env.arch, None);
+        assert!(!env.is_complete());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::config::Config;
+use crate::llm::{self, LlmConfig};
+use crate::store;
+use crate::summary::{self, Detail};
+use github_flows::octocrab::models::issues::Issue;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::env;
+
+const INDEX_KEY: &str = "eval:index";
+const MAX_STORED: usize = 50;
+
+/// An issue context captured at summarization time for later replay.
+#[derive(Serialize, Deserialize)]
+struct Sample {
+    issue: Issue,
+    context: String,
+}
+
+/// A model and prompt variant to compare.
+struct Candidate {
+    model: String,
+    detail: Detail,
+}
+
+/// Stores the context a summary was generated from when `eval_capture` is enabled, keeping
+/// the most recent `MAX_STORED` samples.
+pub fn capture(config: &Config, issue: &Issue, context: &str) {
+    if env::var("eval_capture").unwrap_or_default() != "true" {
+        return;
+    }
+
+    let name = format!("eval:sample:{}", issue.number);
+    let sample = Sample {
+        issue: issue.clone(),
+        context: context.to_string(),
+    };
+    let Ok(value) = serde_json::to_value(&sample) else {
+        return;
+    };
+    store::set(config, &name, value, None);
+
+    let mut index = store::get(config, INDEX_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<u64>>(v).ok())
+        .unwrap_or_default();
+    index.retain(|n| *n != issue.number);
+    index.push(issue.number);
+    if index.len() > MAX_STORED {
+        for old in index.drain(..index.len() - MAX_STORED) {
+            store::del(config, &format!("eval:sample:{}", old));
+        }
+    }
+    store::set(config, INDEX_KEY, json!(index), None);
+}
+
+/// Scores a summary from 1 to 10 with the judge model.
+async fn judge(cfg: &LlmConfig, sample: &Sample, output: &str) -> Option<u8> {
+    let sys_prompt = "You grade summaries of GitHub issue threads for accuracy, coverage of the central problem and proposed solutions, and concision. Reply only with JSON: {\"score\": <1-10>}.";
+    let usr_prompt = format!("Issue thread:\n{}\n\nSummary to grade:\n{}", sample.context, output);
+    let reply = llm::chat(&cfg.profile("judge"), &format!("judge_{}", sample.issue.html_url), sys_prompt, &usr_prompt, 32)
+        .await
+        .ok()?;
+    let value = llm::extract_json(&reply)?;
+    value["score"].as_u64().map(|s| s.clamp(1, 10) as u8)
+}
+
+/// Replays stored samples through each candidate and reports the judge's average scores.
+/// Arguments: `models=a,b` (default: configured model), `details=brief,full` (default:
+/// configured detail) and `limit=N` samples (default 5).
+pub async fn run(config: &Config, args: &HashMap<String, String>) -> anyhow::Result<String> {
+    let models = args
+        .get("models")
+        .map(|m| m.split(',').map(|s| s.to_string()).collect::<Vec<String>>())
+        .unwrap_or(vec![config.llm.model_name.clone()]);
+    let details = args
+        .get("details")
+        .map(|d| d.split(',').filter_map(Detail::parse).collect::<Vec<Detail>>())
+        .unwrap_or(vec![config.summary_detail]);
+    let limit = args.get("limit").and_then(|l| l.parse::<usize>().ok()).unwrap_or(5);
+
+    let candidates = models
+        .iter()
+        .flat_map(|m| details.iter().map(move |d| Candidate { model: m.clone(), detail: *d }))
+        .collect::<Vec<Candidate>>();
+
+    let index = store::get(config, INDEX_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<u64>>(v).ok())
+        .unwrap_or_default();
+    let samples = index
+        .iter()
+        .rev()
+        .take(limit)
+        .filter_map(|n| store::get(config, &format!("eval:sample:{}", n)))
+        .filter_map(|v| serde_json::from_value::<Sample>(v).ok())
+        .collect::<Vec<Sample>>();
+    if samples.is_empty() {
+        return Ok("No stored issue contexts to evaluate. Set `eval_capture=true` and run some summaries first.".to_string());
+    }
+
+    let judge_cfg = match env::var("llm_judge_model") {
+        Ok(model) => config.llm.with_model(&model),
+        Err(_) => config.llm.clone(),
+    };
+
+    let mut rows = Vec::new();
+    for candidate in &candidates {
+        // Profiles could set their own model and make every candidate run on it, so they are
+        // left out; candidates differ only in model and detail.
+        let mut cfg = config.llm.with_model(&candidate.model);
+        cfg.profiles.clear();
+        cfg.label_profiles.clear();
+        let mut scores = Vec::new();
+        for sample in &samples {
+            match summary::summarize(&cfg, &sample.issue, &sample.context, candidate.detail).await {
+                Ok(output) => {
+                    if let Some(score) = judge(&judge_cfg, sample, &output).await {
+                        scores.push(score as f32);
+                    }
+                }
+                Err(error) => log::error!("Candidate {} failed on #{}: {}", candidate.model, sample.issue.number, error),
+            }
+        }
+        let avg = if scores.is_empty() {
+            "n/a".to_string()
+        } else {
+            format!("{:.1}", scores.iter().sum::<f32>() / scores.len() as f32)
+        };
+        rows.push(format!(
+            "| {} | {:?} | {} | {}/{} |",
+            candidate.model,
+            candidate.detail,
+            avg,
+            scores.len(),
+            samples.len()
+        ));
+    }
+
+    Ok(format!(
+        "## Summarization evaluation\n\nJudge: `{}`, samples: {}\n\n| Model | Detail | Avg score | Scored |\n|---|---|---|---|\n{}",
+        judge_cfg.model_name,
+        samples.len(),
+        rows.join("\n")
+    ))
+}
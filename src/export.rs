@@ -0,0 +1,74 @@
+use crate::config::Config;
+use crate::graphql;
+use crate::redact::sanitize;
+use crate::references;
+use crate::{gist, summary};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use serde_json::{json, Value};
+
+/// Assembles the issue thread and its related issues as sanitized JSON and Markdown.
+async fn assemble(config: &Config, octo: &Octocrab, issue: &Issue) -> anyhow::Result<(Value, String)> {
+    let thread = graphql::fetch_thread(octo, &config.owner, &config.repo, issue.number).await?;
+    let related = references::expand(config, octo, issue).await;
+    let context = summary::issue_context(octo, &config.owner, &config.repo, issue).await?;
+
+    let json = json!({
+        "repository": format!("{}/{}", config.owner, config.repo),
+        "number": issue.number,
+        "url": issue.html_url.as_str(),
+        "title": sanitize(&thread.title),
+        "author": thread.author,
+        "labels": thread.labels,
+        "body": sanitize(&thread.body),
+        "comments": thread.comments.iter().map(|c| json!({
+            "id": c.id,
+            "url": c.url,
+            "author": c.author,
+            "body": sanitize(&c.body),
+        })).collect::<Vec<Value>>(),
+        "related": related.iter().map(|r| json!({
+            "repository": format!("{}/{}", r.reference.owner, r.reference.repo),
+            "number": r.reference.number,
+            "title": r.issue.title,
+            "url": r.issue.html_url.as_str(),
+            "summary": sanitize(&r.summary),
+        })).collect::<Vec<Value>>(),
+        "context": sanitize(&context),
+    });
+
+    let mut markdown = format!(
+        "# {} ({}/{}#{})\n\nOpened by {} · labels: {}\n\n{}\n",
+        sanitize(&thread.title),
+        config.owner,
+        config.repo,
+        issue.number,
+        thread.author.mention(),
+        thread.labels.join(", "),
+        sanitize(&thread.body)
+    );
+    for comment in &thread.comments {
+        markdown.push_str(&format!("\n---\n\n**{}** ([link]({}))\n\n{}\n", comment.author.mention(), comment.url, sanitize(&comment.body)));
+    }
+    markdown.push_str(&references::section(&related));
+
+    Ok((json, markdown))
+}
+
+/// Exports the issue context to a gist, falling back to a collapsed comment.
+pub async fn run(config: &Config, octo: &Octocrab, issue: &Issue) -> anyhow::Result<String> {
+    let (json, markdown) = assemble(config, octo, issue).await?;
+    let json = serde_json::to_string_pretty(&json)?;
+    let stem = format!("{}-{}-issue-{}", config.owner, config.repo, issue.number);
+    let files = vec![(format!("{}.json", stem), json.clone()), (format!("{}.md", stem), markdown)];
+
+    match gist::create(config, octo, &format!("Export of {}", issue.html_url), &files).await {
+        Ok(url) => Ok(format!("📦 Exported the context of #{}: {}", issue.number, url)),
+        Err(error) => {
+            log::warn!("Could not create gist, exporting inline: {}", error);
+            Ok(format!(
+                "<details><summary>📦 Export of #{} (JSON)</summary>\n\n```json\n{}\n```\n\n</details>",
+                issue.number, json
+            ))
+        }
+    }
+}
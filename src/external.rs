@@ -0,0 +1,190 @@
+use crate::config;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
+use std::env;
+
+lazy_static! {
+    static ref STACK_OVERFLOW: Regex = Regex::new(r"https?://(?:www\.)?stackoverflow\.com/(?:questions|q)/(\d+)").unwrap();
+    static ref URL: Regex = Regex::new(r#"https?://([\w.-]+)(/[^\s)\]>"']*)?"#).unwrap();
+    static ref TAG: Regex = Regex::new(r"(?s)<[^>]*>").unwrap();
+    static ref SPACE: Regex = Regex::new(r"\s+").unwrap();
+}
+
+const USER_AGENT: &str = "flows-issue-bot";
+/// Responses larger than this are cut off while reading.
+const MAX_BYTES: usize = 512 * 1024;
+/// External links fetched per thread.
+const MAX_LINKS: usize = 5;
+
+/// A Stack Overflow question or forum topic linked from an issue thread.
+pub struct ExternalPost {
+    pub url: String,
+    pub site: String,
+    pub title: String,
+    pub excerpt: String,
+    /// Body of the accepted answer or solution post, when the site marks one.
+    pub accepted_answer: Option<String>,
+}
+
+/// Reads a response body up to `MAX_BYTES`.
+async fn get_limited(client: &reqwest::Client, url: &str) -> anyhow::Result<String> {
+    let mut resp = client.get(url).header("User-Agent", USER_AGENT).send().await?.error_for_status()?;
+    if resp.content_length().unwrap_or_default() as usize > MAX_BYTES {
+        anyhow::bail!("{} is larger than {} bytes", url, MAX_BYTES);
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_BYTES {
+            body.truncate(MAX_BYTES);
+            break;
+        }
+    }
+    Ok(String::from_utf8_lossy(&body).to_string())
+}
+
+/// Checks `path` against the `Disallow` rules for all user agents in the host's robots.txt.
+async fn robots_allow(client: &reqwest::Client, host: &str, path: &str) -> bool {
+    let Ok(robots) = get_limited(client, &format!("https://{}/robots.txt", host)).await else {
+        return true;
+    };
+    let mut applies = false;
+    for line in robots.lines().map(|l| l.split('#').next().unwrap_or_default().trim()) {
+        let Some((field, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match field.trim().to_lowercase().as_str() {
+            "user-agent" => applies = value == "*" || value.eq_ignore_ascii_case(USER_AGENT),
+            "disallow" if applies && !value.is_empty() && path.starts_with(value) => return false,
+            _ => {}
+        }
+    }
+    true
+}
+
+/// Strips markup and shortens the text to `external_excerpt_chars`.
+fn condense(html: &str) -> String {
+    let max_chars = env::var("external_excerpt_chars").ok().and_then(|n| n.parse::<usize>().ok()).unwrap_or(1500);
+    let text = SPACE.replace_all(&TAG.replace_all(html, " "), " ").trim().to_string();
+    if text.chars().count() > max_chars {
+        format!("{}…", text.chars().take(max_chars).collect::<String>())
+    } else {
+        text
+    }
+}
+
+async fn stack_overflow(client: &reqwest::Client, id: &str) -> anyhow::Result<ExternalPost> {
+    let api = "https://api.stackexchange.com/2.3";
+    let question: Value = serde_json::from_str(&get_limited(client, &format!("{}/questions/{}?site=stackoverflow&filter=withbody", api, id)).await?)?;
+    let question = &question["items"][0];
+    if question.is_null() {
+        anyhow::bail!("Stack Overflow question {} not found", id);
+    }
+
+    let accepted_answer = match question["accepted_answer_id"].as_u64() {
+        Some(answer_id) => {
+            let answer: Value =
+                serde_json::from_str(&get_limited(client, &format!("{}/answers/{}?site=stackoverflow&filter=withbody", api, answer_id)).await?)?;
+            answer["items"][0]["body"].as_str().map(condense)
+        }
+        None => None,
+    };
+    Ok(ExternalPost {
+        url: question["link"].as_str().unwrap_or_default().to_string(),
+        site: "Stack Overflow".to_string(),
+        title: condense(question["title"].as_str().unwrap_or_default()),
+        excerpt: condense(question["body"].as_str().unwrap_or_default()),
+        accepted_answer,
+    })
+}
+
+/// Fetches a topic from a Discourse forum through its JSON view. The solved plugin marks
+/// the accepted post with `accepted_answer`.
+async fn forum(client: &reqwest::Client, host: &str, path: &str) -> anyhow::Result<ExternalPost> {
+    if !robots_allow(client, host, path).await {
+        anyhow::bail!("robots.txt disallows {}{}", host, path);
+    }
+    let topic: Value = serde_json::from_str(&get_limited(client, &format!("https://{}{}.json", host, path.trim_end_matches('/'))).await?)?;
+    let posts = topic["post_stream"]["posts"].as_array().cloned().unwrap_or_default();
+    let accepted_answer = posts
+        .iter()
+        .find(|p| p["accepted_answer"].as_bool().unwrap_or(false))
+        .and_then(|p| p["cooked"].as_str())
+        .map(condense);
+    Ok(ExternalPost {
+        url: format!("https://{}{}", host, path),
+        site: host.to_string(),
+        title: topic["title"].as_str().unwrap_or_default().to_string(),
+        excerpt: posts.first().and_then(|p| p["cooked"].as_str()).map(condense).unwrap_or_default(),
+        accepted_answer,
+    })
+}
+
+/// Fetches the Stack Overflow questions and `forum_hosts` topics linked from `text`.
+pub async fn fetch(text: &str) -> Vec<ExternalPost> {
+    if env::var("external_links").unwrap_or_default() == "false" {
+        return Vec::new();
+    }
+    let client = reqwest::Client::new();
+    let forum_hosts = config::list_var("forum_hosts");
+    let mut seen = Vec::new();
+    let mut posts = Vec::new();
+
+    for m in URL.captures_iter(text) {
+        if posts.len() >= MAX_LINKS {
+            break;
+        }
+        let url = m[0].to_string();
+        if seen.contains(&url) {
+            continue;
+        }
+        seen.push(url.clone());
+
+        let host = m[1].to_lowercase();
+        let path = m.get(2).map(|p| p.as_str()).unwrap_or("/");
+        let fetched = if let Some(c) = STACK_OVERFLOW.captures(&url) {
+            stack_overflow(&client, &c[1]).await
+        } else if forum_hosts.iter().any(|h| h.eq_ignore_ascii_case(&host)) && path.starts_with("/t/") {
+            forum(&client, &host, path).await
+        } else {
+            continue;
+        };
+        match fetched {
+            Ok(post) => posts.push(post),
+            Err(error) => log::debug!("Skipping external link {}: {}", url, error),
+        }
+    }
+    posts
+}
+
+/// Renders the linked posts as additional LLM context.
+pub fn context_block(posts: &[ExternalPost]) -> String {
+    posts
+        .iter()
+        .map(|p| {
+            let answer = match &p.accepted_answer {
+                Some(answer) => format!(" Its accepted answer says: '{}'.", answer),
+                None => " It has no accepted answer.".to_string(),
+            };
+            format!("Linked {} post '{}' says: '{}'.{}\n", p.site, p.title, p.excerpt, answer)
+        })
+        .collect()
+}
+
+/// Renders the "External references" section appended to summaries.
+pub fn section(posts: &[ExternalPost]) -> String {
+    if posts.is_empty() {
+        return String::new();
+    }
+    let lines = posts
+        .iter()
+        .map(|p| {
+            let status = if p.accepted_answer.is_some() { "has an accepted answer" } else { "no accepted answer" };
+            format!("* [{}]({}) ({}, {})", p.title, p.url, p.site, status)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!("\n\n**External references**\n{}", lines)
+}
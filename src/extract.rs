@@ -0,0 +1,94 @@
+use crate::config::Config;
+use crate::store;
+use crate::{llm, redact, summary};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+
+const SCHEMA: &str = r#"{"environment": {"<name, e.g. os, arch, compiler>": "<value>"}, "version": "<affected version or null>", "repro_steps": ["<step>"], "expected": "<expected behavior or null>", "actual": "<actual behavior or null>", "stack_traces": ["<verbatim trace>"]}"#;
+
+/// The structured form of a bug report. Unknown fields are rejected so malformed model
+/// output fails validation and is retried.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Extraction {
+    #[serde(default)]
+    pub environment: BTreeMap<String, String>,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub repro_steps: Vec<String>,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    #[serde(default)]
+    pub stack_traces: Vec<String>,
+}
+
+fn store_key(number: u64) -> String {
+    format!("extract:{}", number)
+}
+
+/// Returns the extraction stored for an issue, if any.
+pub fn stored(config: &Config, number: u64) -> Option<serde_json::Value> {
+    store::get(config, &store_key(number))
+}
+
+/// Parses and checks a model reply against the schema.
+fn validate(reply: &str) -> Result<Extraction, String> {
+    let value = llm::extract_json(reply).ok_or("the reply contains no JSON object")?;
+    let extraction = serde_json::from_value::<Extraction>(value).map_err(|e| e.to_string())?;
+    if extraction.repro_steps.iter().chain(&extraction.stack_traces).any(|s| s.trim().is_empty()) {
+        return Err("repro_steps and stack_traces must not contain empty strings".to_string());
+    }
+    Ok(extraction)
+}
+
+/// Asks for the schema, feeding validation errors back on each retry.
+async fn extract(config: &Config, issue: &Issue, context: &str) -> anyhow::Result<Extraction> {
+    let retries = env::var("extract_retries").ok().and_then(|n| n.parse::<usize>().ok()).unwrap_or(2);
+    let sys_prompt = format!(
+        "You convert GitHub bug reports into JSON. Reply only with one JSON object matching exactly this schema, using null or empty collections for anything not stated in the thread: {}",
+        SCHEMA
+    );
+    let mut usr_prompt = format!("Issue thread:\n{}", context);
+
+    let mut last_error = String::new();
+    for attempt in 0..=retries {
+        let conversation_id = format!("extract_{}#{}", issue.html_url, attempt);
+        let reply = llm::chat(&config.llm.profile("extract"), &conversation_id, &sys_prompt, &usr_prompt, 1024)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+        match validate(&reply) {
+            Ok(extraction) => return Ok(extraction),
+            Err(error) => {
+                log::warn!("Invalid extraction for #{} (attempt {}): {}", issue.number, attempt + 1, error);
+                usr_prompt = format!(
+                    "Issue thread:\n{}\n\nYour previous reply was invalid ({}). Reply again with only the JSON object.",
+                    context, error
+                );
+                last_error = error;
+            }
+        }
+    }
+    anyhow::bail!("no valid JSON after {} attempts: {}", retries + 1, last_error)
+}
+
+/// Handles the extract phrase. With `--store` (or `extract_store=true`) the result is also
+/// kept in the store under `extract:<number>` for downstream tooling.
+pub async fn run(config: &Config, octo: &Octocrab, issue: &Issue, body: &str) -> anyhow::Result<String> {
+    let context = summary::issue_context(octo, &config.owner, &config.repo, issue).await?;
+    let extraction = extract(config, issue, &redact::sanitize(&context)).await?;
+    let value = serde_json::to_value(&extraction)?;
+
+    let stored = body.contains("--store") || env::var("extract_store").unwrap_or_default() == "true";
+    if stored {
+        store::set(config, &store_key(issue.number), value.clone(), None);
+    }
+
+    Ok(format!(
+        "<details><summary>🧾 Structured report for #{}{}</summary>\n\n```json\n{}\n```\n\n</details>",
+        issue.number,
+        if stored { " (stored)" } else { "" },
+        serde_json::to_string_pretty(&value)?
+    ))
+}
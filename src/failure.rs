@@ -0,0 +1,76 @@
+use crate::config::Config;
+use crate::dry_run;
+use crate::outbox;
+use github_flows::octocrab::Octocrab;
+use serde_json::{json, Value};
+use std::env;
+use std::fmt::Display;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How a failed run is surfaced to the person who triggered it, set with `failure_feedback`.
+#[derive(PartialEq)]
+enum Feedback {
+    None,
+    React,
+    Comment,
+    Both,
+}
+
+fn feedback() -> Feedback {
+    match env::var("failure_feedback").unwrap_or_default().as_str() {
+        "none" => Feedback::None,
+        "react" => Feedback::React,
+        "comment" => Feedback::Comment,
+        _ => Feedback::Both,
+    }
+}
+
+/// Builds an ID tying the user-facing notice to the detailed log line.
+pub fn correlation_id(comment_id: &str) -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    format!("{:x}-{}", millis, comment_id)
+}
+
+/// Logs the full error and tells the requester that their request failed, without
+/// exposing the error details publicly.
+pub async fn report(
+    config: &Config,
+    octo: &Octocrab,
+    issue_number: u64,
+    comment_id: &str,
+    requester: &str,
+    what: &str,
+    error: &dyn Display,
+) {
+    let cid = correlation_id(comment_id);
+    log::error!("[{}] Error {} on #{}: {}", cid, what, issue_number, error);
+
+    let mode = feedback();
+    if dry_run::intercept(config, "report_failure", Some(issue_number), json!({ "what": what, "correlation_id": cid })) {
+        return;
+    }
+    if mode == Feedback::React || mode == Feedback::Both {
+        // GitHub has no ❌ reaction; "confused" is the closest.
+        // Requests made in an issue's opening post are identified as `issue-<number>`.
+        let route = match comment_id.strip_prefix("issue-") {
+            Some(_) => format!("/repos/{}/{}/issues/{}/reactions", config.owner, config.repo, issue_number),
+            None => format!("/repos/{}/{}/issues/comments/{}/reactions", config.owner, config.repo, comment_id),
+        };
+        let reacted: Result<Value, _> = octo.post(route, Some(&json!({ "content": "confused" }))).await;
+        if let Err(error) = reacted {
+            log::error!("[{}] Error reacting to comment: {}", cid, error);
+        }
+    }
+    if mode == Feedback::Comment || mode == Feedback::Both {
+        let resp = format!(
+            "❌ @{} sorry, something went wrong while {}. Please try again later or share correlation ID `{}` with the maintainers.",
+            requester, what, cid
+        );
+        if let Err(error) = outbox::reply(config, octo, issue_number, &resp).await {
+            log::error!("[{}] Error posting failure notice: {}", cid, error);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::config::Config;
+use crate::notify;
+use crate::repo_config::RepoConfig;
+use serde::Deserialize;
+use std::env;
+
+/// The feature manifest compiled into the module; keep it updated alongside new features.
+const MANIFEST: &str = include_str!("features.json");
+
+#[derive(Deserialize)]
+struct Manifest {
+    features: Vec<Feature>,
+    changelog: Vec<Release>,
+}
+
+#[derive(Deserialize)]
+struct Feature {
+    id: String,
+    name: String,
+    since: String,
+    description: String,
+}
+
+#[derive(Deserialize)]
+struct Release {
+    version: String,
+    changes: Vec<String>,
+}
+
+/// Whether a manifest feature is switched on for this repository.
+fn is_enabled(id: &str, config: &Config, repo_config: &RepoConfig) -> bool {
+    match id {
+        "priority_reporters" => !config.priority_reporters.is_empty(),
+        "quality_check" => !repo_config.required_info.is_empty(),
+        "sponsor_tiers" => !repo_config.sponsor_tiers.is_empty(),
+        "dry_run" => config.dry_run,
+        "vision" => config.llm.vision_model.is_some(),
+        "azure" => config.llm.azure.is_some(),
+        "severity" => repo_config.severity.enabled,
+        "weekly_digest" => repo_config.digest.weekly,
+        "spam" => repo_config.spam.enabled,
+        "security" => repo_config.security.enabled,
+        "mirror" => repo_config.mirror.repo.is_some(),
+        "greeting" => repo_config.greeting.enabled,
+        "heat" => repo_config.heat.enabled,
+        "notify" => !notify::sinks().is_empty(),
+        "good_first_issue" => repo_config.good_first_issue.weekly,
+        "milestones" => repo_config.milestones.enabled,
+        "burndown" => repo_config.milestones.burndown,
+        "accessible" => repo_config.accessible,
+        "handoff" => repo_config.handoff,
+        "leak_audit" => repo_config.leak_audit,
+        "persona" => repo_config.persona.tone.is_some() || repo_config.persona.voice.is_some(),
+        "related_issues" => env::var("related_max").unwrap_or_default() != "0",
+        "external_links" => env::var("external_links").unwrap_or_default() != "false",
+        "code_context" => env::var("code_context").unwrap_or_default() != "false",
+        "output_channels" => !repo_config.outputs.is_empty(),
+        "label_profiles" => !repo_config.label_profiles.is_empty(),
+        "private_channel" => repo_config.private.tracking_repo.is_some() || repo_config.private.team.is_some(),
+        "journal" => repo_config.journal,
+        "assignee_suggestion" => repo_config.assignees.suggest,
+        "environment" => repo_config.environment.enabled,
+        "ci_logs" => env::var("ci_logs").unwrap_or_default() != "false",
+        "titles" => repo_config.titles.enabled,
+        "filters" => {
+            let filters = &repo_config.filters;
+            !filters.ignore_comments.is_empty()
+                || !filters.ignore_users.is_empty()
+                || filters.ignore_bots
+                || !filters.labels.is_empty()
+                || !config.quiet_hours.is_empty()
+        }
+        "summarize" | "release_notes" | "evaluate" | "deep_dive" | "todos" | "export" | "triage_import" | "suggest_fix"
+        | "confidence" | "extract" | "reactions" | "search" | "edit_trigger" | "replay" | "coalesce" | "assign" | "quoted_scope"
+        | "duplicate" | "capabilities" | "state" | "compare" | "stats" | "backfill" | "feedback" | "moderation" | "pin_summary"
+        | "healthcheck" | "whatsnew" => true,
+        _ => false,
+    }
+}
+
+/// Renders the `whatsnew` reply.
+pub fn whatsnew(config: &Config, repo_config: &RepoConfig) -> anyhow::Result<String> {
+    let manifest: Manifest = serde_json::from_str(MANIFEST)?;
+
+    let features = manifest
+        .features
+        .iter()
+        .map(|f| {
+            let mark = if is_enabled(&f.id, config, repo_config) { "✅" } else { "⬜" };
+            format!("| {} | {} | {} | {} |", mark, f.name, f.since, f.description)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let changes = manifest
+        .changelog
+        .iter()
+        .take(3)
+        .map(|r| {
+            format!(
+                "**{}**\n{}",
+                r.version,
+                r.changes.iter().map(|c| format!("* {}", c)).collect::<Vec<String>>().join("\n")
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n");
+
+    Ok(format!(
+        "## github-issue-handler v{}\n\nFeatures for {}/{}:\n\n| | Feature | Since | Description |\n|---|---|---|---|\n{}\n\n### Recent changes\n\n{}",
+        env!("CARGO_PKG_VERSION"),
+        config.owner,
+        config.repo,
+        features,
+        changes
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(v: &str) -> Vec<u32> {
+        v.split('.').map(|p| p.parse::<u32>().unwrap()).collect()
+    }
+
+    #[test]
+    fn manifest_parses_with_released_versions() {
+        let manifest: Manifest = serde_json::from_str(MANIFEST).unwrap();
+        let current = version(env!("CARGO_PKG_VERSION"));
+        for feature in &manifest.features {
+            assert!(version(&feature.since) <= current, "{} is newer than the crate", feature.id);
+        }
+        for release in &manifest.changelog {
+            assert!(version(&release.version) <= current, "{} is newer than the crate", release.version);
+        }
+    }
+
+    #[test]
+    fn manifest_does_not_trigger_the_bot() {
+        assert!(!MANIFEST.contains("@flows_"));
+    }
+}
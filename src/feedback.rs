@@ -0,0 +1,195 @@
+use crate::config::Config;
+use crate::store;
+use github_flows::octocrab::{models::CommentId, Octocrab};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::env;
+
+const TRACKED_KEY: &str = "feedback:tracked";
+const TOTALS_KEY: &str = "feedback:totals";
+/// Bounds the reaction polling per run.
+const MAX_TRACKED: usize = 200;
+const MAX_WORST: usize = 5;
+
+/// A bot output whose reactions are being watched.
+#[derive(Clone, Serialize, Deserialize)]
+struct Tracked {
+    comment_id: u64,
+    issue: u64,
+    /// Prompt profile the output was generated with, e.g. `summary` or `compare`.
+    profile: String,
+    model: String,
+    posted_at: i64,
+    up: u64,
+    down: u64,
+}
+
+/// Reactions to the outputs of one profile.
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct Tally {
+    comments: u64,
+    /// Comments with at least one 👍 or 👎.
+    rated: u64,
+    up: u64,
+    down: u64,
+}
+
+impl Tally {
+    fn add(&mut self, tracked: &Tracked) {
+        self.comments += 1;
+        if tracked.up + tracked.down > 0 {
+            self.rated += 1;
+        }
+        self.up += tracked.up;
+        self.down += tracked.down;
+    }
+}
+
+/// Comments are watched this long before their reactions are folded into the totals.
+fn window_secs() -> i64 {
+    env::var("feedback_window_days").ok().and_then(|d| d.parse::<i64>().ok()).unwrap_or(14) * 24 * 3600
+}
+
+fn tracked(config: &Config) -> Vec<Tracked> {
+    store::get(config, TRACKED_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<Tracked>>(v).ok())
+        .unwrap_or_default()
+}
+
+fn totals(config: &Config) -> BTreeMap<String, Tally> {
+    store::get(config, TOTALS_KEY)
+        .and_then(|v| serde_json::from_value::<BTreeMap<String, Tally>>(v).ok())
+        .unwrap_or_default()
+}
+
+/// Starts watching the reactions on a bot output.
+pub fn track(config: &Config, comment_id: CommentId, issue: u64, profile: &str, model: &str) {
+    let mut items = tracked(config);
+    items.push(Tracked {
+        comment_id: comment_id.0,
+        issue,
+        profile: profile.to_string(),
+        model: model.to_string(),
+        posted_at: chrono::Utc::now().timestamp(),
+        up: 0,
+        down: 0,
+    });
+    if items.len() > MAX_TRACKED {
+        let overflow = items.len() - MAX_TRACKED;
+        let mut totals = totals(config);
+        for retired in items.drain(..overflow) {
+            totals.entry(retired.profile.clone()).or_default().add(&retired);
+        }
+        store::set(config, TOTALS_KEY, json!(totals), None);
+    }
+    store::set(config, TRACKED_KEY, json!(items), None);
+}
+
+/// 👍 and 👎 counts on a comment, from people other than the bot.
+async fn count(config: &Config, octo: &Octocrab, comment_id: u64) -> anyhow::Result<(u64, u64)> {
+    let route = format!(
+        "/repos/{}/{}/issues/comments/{}/reactions?per_page=100",
+        config.owner, config.repo, comment_id
+    );
+    let reactions = octo.get::<Value, _, ()>(route, None).await?;
+    let (mut up, mut down) = (0, 0);
+    for reaction in reactions.as_array().into_iter().flatten() {
+        if reaction["user"]["type"].as_str() == Some("Bot") {
+            continue;
+        }
+        match reaction["content"].as_str() {
+            Some("+1") => up += 1,
+            Some("-1") => down += 1,
+            _ => {}
+        }
+    }
+    Ok((up, down))
+}
+
+/// Refreshes the reaction counts of every watched comment, folding those past the window
+/// into the per-profile totals. Deleted comments are dropped.
+pub async fn poll(config: &Config, octo: &Octocrab) {
+    let items = tracked(config);
+    if items.is_empty() {
+        return;
+    }
+    let cutoff = chrono::Utc::now().timestamp() - window_secs();
+    let mut totals = totals(config);
+    let mut kept = Vec::new();
+
+    for mut item in items {
+        match count(config, octo, item.comment_id).await {
+            Ok((up, down)) => {
+                item.up = up;
+                item.down = down;
+            }
+            Err(error) if error.to_string().contains("Not Found") => {
+                log::info!("Dropping feedback for deleted comment {}", item.comment_id);
+                continue;
+            }
+            Err(error) => log::warn!("Could not read reactions on comment {}: {}", item.comment_id, error),
+        }
+        if item.posted_at < cutoff {
+            totals.entry(item.profile.clone()).or_default().add(&item);
+        } else {
+            kept.push(item);
+        }
+    }
+
+    store::set(config, TOTALS_KEY, json!(totals), None);
+    store::set(config, TRACKED_KEY, json!(kept), None);
+}
+
+/// The accuracy report for `@flows_bot feedback`: reactions per prompt profile and model,
+/// and the recent outputs with the most 👎 for prompt tuning.
+pub async fn report(config: &Config, octo: &Octocrab) -> String {
+    poll(config, octo).await;
+    let items = tracked(config);
+    let mut tallies = totals(config);
+    for item in &items {
+        tallies.entry(item.profile.clone()).or_default().add(item);
+    }
+    if tallies.is_empty() {
+        return "No feedback yet: react with 👍 or 👎 on the bot's summaries to rate them.".to_string();
+    }
+
+    let mut out = "**Feedback on bot outputs**\n\n| Profile | Outputs | Rated | 👍 | 👎 | Satisfaction |\n|---|---|---|---|---|---|\n".to_string();
+    for (profile, tally) in &tallies {
+        let satisfaction = if tally.up + tally.down == 0 {
+            "n/a".to_string()
+        } else {
+            format!("{:.0}%", 100.0 * tally.up as f64 / (tally.up + tally.down) as f64)
+        };
+        out.push_str(&format!(
+            "| `{}` | {} | {} | {} | {} | {} |\n",
+            profile, tally.comments, tally.rated, tally.up, tally.down, satisfaction
+        ));
+    }
+
+    let mut models = BTreeMap::<(String, String), (u64, u64)>::new();
+    for item in &items {
+        let entry = models.entry((item.profile.clone(), item.model.clone())).or_default();
+        entry.0 += item.up;
+        entry.1 += item.down;
+    }
+    if models.len() > tallies.len() {
+        out.push_str("\nRecent outputs by model:\n");
+        for ((profile, model), (up, down)) in &models {
+            out.push_str(&format!("* `{}` with `{}`: {} 👍 / {} 👎\n", profile, model, up, down));
+        }
+    }
+
+    let mut worst = items.iter().filter(|i| i.down > i.up).collect::<Vec<&Tracked>>();
+    worst.sort_by_key(|i| std::cmp::Reverse(i.down - i.up));
+    if !worst.is_empty() {
+        out.push_str("\nMost disliked recent outputs:\n");
+        for item in worst.iter().take(MAX_WORST) {
+            out.push_str(&format!(
+                "* [#{} ({})](https://github.com/{}/{}/issues/{}#issuecomment-{}): {} 👍 / {} 👎\n",
+                item.issue, item.profile, config.owner, config.repo, item.issue, item.comment_id, item.up, item.down
+            ));
+        }
+    }
+    out
+}
@@ -0,0 +1,126 @@
+use crate::author::Author;
+use crate::config::Config;
+use crate::outbox;
+use crate::repo_config::FilterConfig;
+use crate::store;
+use chrono::{FixedOffset, Timelike};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+const HELD_KEY: &str = "quiet:held";
+const DAY_MINUTES: u32 = 24 * 60;
+
+/// A reply held back during quiet hours, already split into comment parts.
+#[derive(Serialize, Deserialize)]
+struct Held {
+    issue: u64,
+    parts: Vec<String>,
+}
+
+/// Why an issue or comment is excluded by the repo's filters, or `None` when the bot should
+/// act on it. `comment` is the comment body, or `None` for the issue itself.
+pub fn skip_reason(filters: &FilterConfig, issue: &Issue, author: &str, comment: Option<&str>) -> Option<String> {
+    if filters.ignore_users.iter().any(|u| u.trim_start_matches('@').eq_ignore_ascii_case(author)) {
+        return Some(format!("@{} is in filters.ignore_users", author));
+    }
+    if filters.ignore_bots && !Author::from_login(author).is_human() {
+        return Some(format!("@{} is a bot", author));
+    }
+    if !filters.labels.is_empty() && !issue.labels.iter().any(|l| filters.labels.contains(&l.name)) {
+        return Some(format!("#{} has none of filters.labels", issue.number));
+    }
+    let body = comment?;
+    for pattern in &filters.ignore_comments {
+        match Regex::new(pattern) {
+            Ok(re) if re.is_match(body) => return Some(format!("the comment matches `{}`", pattern)),
+            Ok(_) => {}
+            Err(error) => log::warn!("Invalid filters.ignore_comments pattern `{}`: {}", pattern, error),
+        }
+    }
+    None
+}
+
+/// Parses `HH:MM`.
+fn minutes(time: &str) -> Option<u32> {
+    let (hours, mins) = time.trim().split_once(':')?;
+    let (hours, mins) = (hours.parse::<u32>().ok()?, mins.parse::<u32>().ok()?);
+    (hours <= 24 && mins < 60).then_some(hours * 60 + mins)
+}
+
+/// The quiet-hours windows as `[start, end)` minutes of the UTC day; a window may wrap
+/// past midnight.
+pub fn quiet_windows(filters: &FilterConfig) -> Vec<(u32, u32)> {
+    let offset = match filters.utc_offset.as_deref().map(|o| o.parse::<FixedOffset>()) {
+        Some(Ok(offset)) => offset.local_minus_utc() / 60,
+        Some(Err(error)) => {
+            log::warn!("Invalid filters.utc_offset, using UTC: {}", error);
+            0
+        }
+        None => 0,
+    };
+    let to_utc = |m: u32| (m as i32 - offset).rem_euclid(DAY_MINUTES as i32) as u32;
+    filters
+        .quiet_hours
+        .iter()
+        .filter_map(|window| {
+            let parsed = window.split_once('-').and_then(|(start, end)| Some((minutes(start)?, minutes(end)?)));
+            if parsed.is_none() {
+                log::warn!("Invalid filters.quiet_hours window `{}`, expected HH:MM-HH:MM", window);
+            }
+            parsed
+        })
+        .map(|(start, end)| (to_utc(start), to_utc(end)))
+        .collect()
+}
+
+/// Whether the current time falls in one of the repo's quiet-hours windows.
+pub fn is_quiet(config: &Config) -> bool {
+    let now = chrono::Utc::now();
+    let minute = now.hour() * 60 + now.minute();
+    config.quiet_hours.iter().any(|&(start, end)| {
+        if start <= end {
+            start <= minute && minute < end
+        } else {
+            minute >= start || minute < end
+        }
+    })
+}
+
+fn held(config: &Config) -> Vec<Held> {
+    store::get(config, HELD_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<Held>>(v).ok())
+        .unwrap_or_default()
+}
+
+/// Queues a reply until quiet hours are over.
+pub fn hold(config: &Config, issue_number: u64, parts: &[String]) {
+    let mut queue = held(config);
+    queue.push(Held {
+        issue: issue_number,
+        parts: parts.to_vec(),
+    });
+    store::set(config, HELD_KEY, json!(queue), None);
+    log::info!("Quiet hours: holding a reply on #{} until they end", issue_number);
+}
+
+/// Posts the replies held during quiet hours, once they are over. Replies that fail to post
+/// stay queued for the next run.
+pub async fn release(config: &Config, octo: &Octocrab) {
+    if is_quiet(config) {
+        return;
+    }
+    let queue = held(config);
+    if queue.is_empty() {
+        return;
+    }
+    let mut failed = Vec::new();
+    for item in queue {
+        if let Err(error) = outbox::post_parts(config, octo, item.issue, &item.parts).await {
+            log::error!("Error posting held reply on #{}: {}", item.issue, error);
+            failed.push(item);
+        }
+    }
+    store::set(config, HELD_KEY, json!(failed), None);
+}
@@ -0,0 +1,63 @@
+use crate::config::Config;
+use crate::store;
+use github_flows::octocrab::Octocrab;
+use serde_json::json;
+use std::env;
+
+/// Hidden marker appended to every bot comment, so the bot can tell its own messages from
+/// users' when it later edits or deletes them.
+pub const MARKER: &str = "<!-- flows-issue-bot -->";
+
+/// Cached login of the account the bot's token posts as; empty when it can't be looked up.
+const LOGIN_KEY: &str = "footer:own_login";
+
+/// The default `footer_template`. `{triggered}` expands to ` Triggered by @user` when the
+/// output was requested, and `{requester}` to the requester alone.
+pub const DEFAULT_TEMPLATE: &str = "This result is generated by flows.network.{triggered}";
+
+/// The footer for a bot output, with its leading blank line, or an empty string when the
+/// template is empty or `none`. `triggered_by` is the already formatted requester list.
+pub fn render(config: &Config, triggered_by: Option<&str>) -> String {
+    let template = config.footer_template.trim();
+    if template.is_empty() || template.eq_ignore_ascii_case("none") {
+        return String::new();
+    }
+    let triggered = triggered_by.map(|r| format!(" Triggered by {}", r)).unwrap_or_default();
+    let footer = template
+        .replace("{triggered}", &triggered)
+        .replace("{requester}", triggered_by.unwrap_or_default());
+    format!("\n\n{}", footer.trim())
+}
+
+/// Appends the hidden marker to a comment body.
+pub fn marked(body: &str) -> String {
+    if body.contains(MARKER) {
+        return body.to_string();
+    }
+    format!("{}\n\n{}", body, MARKER)
+}
+
+/// Whether a comment was posted by this bot.
+pub fn is_own(body: &str) -> bool {
+    body.contains(MARKER)
+}
+
+/// The login the bot's GitHub token posts as: `bot_login` when set, otherwise looked up once
+/// a day. Installation tokens can't read the authenticated user, so set `bot_login` for those.
+pub async fn own_login(config: &Config, octo: &Octocrab) -> Option<String> {
+    if let Ok(login) = env::var("bot_login") {
+        return Some(login);
+    }
+    if let Some(login) = store::get(config, LOGIN_KEY).and_then(|v| v.as_str().map(|s| s.to_string())) {
+        return Some(login).filter(|l| !l.is_empty());
+    }
+    let login = match octo.current().user().await {
+        Ok(user) => user.login,
+        Err(error) => {
+            log::debug!("Could not look up the bot's own login: {}", error);
+            String::new()
+        }
+    };
+    store::set(config, LOGIN_KEY, json!(login), Some(24 * 3600));
+    Some(login).filter(|l| !l.is_empty())
+}
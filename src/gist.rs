@@ -0,0 +1,27 @@
+use crate::config::Config;
+use crate::dry_run;
+use github_flows::octocrab::Octocrab;
+use serde_json::{json, Map, Value};
+
+/// Creates a secret gist from `(filename, content)` pairs and returns its URL. GitHub App
+/// installation tokens cannot create gists, so callers should be ready to fall back to a comment.
+pub async fn create(config: &Config, octo: &Octocrab, description: &str, files: &[(String, String)]) -> anyhow::Result<String> {
+    let names = files.iter().map(|(name, _)| name.as_str()).collect::<Vec<&str>>();
+    if dry_run::intercept(config, "create_gist", None, json!({ "description": description, "files": names })) {
+        anyhow::bail!("gists are not created in dry-run mode");
+    }
+    let files = files
+        .iter()
+        .map(|(name, content)| (name.clone(), json!({ "content": content })))
+        .collect::<Map<String, Value>>();
+    let body = json!({
+        "description": description,
+        "public": false,
+        "files": files,
+    });
+    let gist: Value = octo.post("/gists", Some(&body)).await?;
+    gist["html_url"]
+        .as_str()
+        .map(|u| u.to_string())
+        .ok_or_else(|| anyhow::anyhow!("Gist response without html_url"))
+}
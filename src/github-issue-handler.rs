@@ -3,14 +3,107 @@ use flowsnet_platform_sdk::logger;
 use github_flows::{
     event_handler, get_octo, listen_to_event,
     octocrab::models::webhook_events::{WebhookEvent, WebhookEventPayload},
-    octocrab::models::webhook_events::payload::IssueCommentWebhookEventAction,
+    octocrab::models::{issues::Issue, CommentId, IssueState},
+    octocrab::Octocrab,
+    octocrab::models::webhook_events::payload::{
+        IssueCommentWebhookEventAction, IssueCommentWebhookEventPayload, IssuesWebhookEventAction,
+        IssuesWebhookEventPayload,
+    },
     GithubLogin,
 };
-use llmservice_flows::{
-    chat::ChatOptions,
-    LLMServiceFlows,
-};
+use schedule_flows::{schedule_cron_job, schedule_handler};
+use serde_json::Value;
+use std::collections::HashMap;
 use std::env;
+use webhook_flows::{create_endpoint, request_handler, send_response};
+
+mod a11y;
+mod assign;
+mod audit;
+mod author;
+mod backfill;
+mod batch;
+mod capabilities;
+mod chunk;
+mod ci;
+mod clarify;
+mod code;
+mod coalesce;
+mod commands;
+mod compare;
+mod confidence;
+mod config;
+mod dedup;
+mod deep_dive;
+mod digest;
+mod dry_run;
+mod duplicate;
+mod embeddings;
+mod environment;
+mod evaluate;
+mod export;
+mod external;
+mod extract;
+mod failure;
+mod features;
+mod feedback;
+mod filters;
+mod footer;
+mod gist;
+mod good_first;
+mod graphql;
+mod greeting;
+mod handoff;
+mod healthcheck;
+mod heat;
+mod images;
+mod journal;
+mod leak_audit;
+mod llm;
+mod metrics;
+mod milestone;
+mod mirror;
+mod moderation;
+mod mutations;
+mod notify;
+mod outbox;
+mod output;
+mod pending;
+mod permissions;
+mod persona;
+mod pin;
+mod priority;
+mod private;
+mod progress;
+mod quality;
+mod quota;
+mod reactions;
+mod redact;
+mod references;
+mod release_notes;
+mod replay;
+mod repo_config;
+mod schedule;
+mod scope;
+mod search;
+mod security;
+mod severity;
+mod spam;
+mod sponsors;
+mod state;
+mod store;
+mod suggest_fix;
+mod summary;
+mod titles;
+mod todos;
+mod triage_import;
+mod workload;
+
+use author::Author;
+use config::Config;
+use outbox::Outbox;
+use progress::Placeholder;
+use repo_config::{RepoConfig, SponsorTier};
 
 #[no_mangle]
 #[tokio::main(flavor = "current_thread")]
@@ -19,10 +112,105 @@ pub async fn on_deploy() {
     logger::init();
     log::info!("Deploying github-issue-handler");
 
-    let owner = env::var("github_owner").expect("github_owner not set");
-    let repo = env::var("github_repo").expect("github_repo not set");
+    let octo = get_octo(&GithubLogin::Default);
+    for (owner, repo) in config::registry() {
+        log::info!("Subscribing to {}/{}", owner, repo);
+        listen_to_event(&GithubLogin::Default, &owner, &repo, vec!["issue_comment", "issues", "release"]).await;
+
+        if env::var("capability_audit").unwrap_or_default() != "false" {
+            let config = Config::from_env(&owner, &repo);
+            let repo_config = repo_config::load(&octo, &owner, &repo).await;
+            capabilities::audit_on_deploy(&config, &repo_config, &octo).await;
+        }
+    }
+
+    let cron = env::var("schedule_cron").unwrap_or("0 * * * *".to_string());
+    schedule_cron_job(cron, "tick".to_string()).await;
 
-    listen_to_event(&GithubLogin::Default, &owner, &repo, vec!["issue_comment"]).await;
+    create_endpoint().await;
+}
+
+#[schedule_handler]
+async fn scheduled(_body: Vec<u8>) {
+    dotenv().ok();
+    logger::init();
+    log::info!("Running github-issue-handler scheduled()");
+
+    schedule::tick().await;
+}
+
+/// HTTP entry point for external tools, authenticated by the `X-Import-Token` header
+/// matching `import_token`. `POST /import?repo=owner/repo` applies a JSON triage decision;
+/// `POST /replay?repo=owner/repo&hours=6` redelivers events missed during an outage.
+#[request_handler]
+async fn http_handler(headers: Vec<(String, String)>, subpath: String, qry: HashMap<String, Value>, body: Vec<u8>) {
+    dotenv().ok();
+    logger::init();
+    log::info!("Running github-issue-handler http_handler() for {}", subpath);
+
+    let respond = |status: u16, msg: String| {
+        send_response(status, vec![(String::from("content-type"), String::from("text/plain"))], msg.into_bytes())
+    };
+
+    let expected = env::var("import_token").unwrap_or_default();
+    let token = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("x-import-token"))
+        .map(|(_, v)| v.as_str())
+        .unwrap_or_default();
+    if expected.is_empty() || token != expected {
+        respond(401, "Unauthorized".to_string());
+        return;
+    }
+
+    let Some((owner, repo)) = qry.get("repo").and_then(|r| r.as_str()).and_then(|r| r.split_once('/')) else {
+        respond(400, "Missing repo=owner/repo".to_string());
+        return;
+    };
+    if !config::registry()
+        .iter()
+        .any(|(o, r)| o.eq_ignore_ascii_case(owner) && r.eq_ignore_ascii_case(repo))
+    {
+        respond(404, format!("{}/{} is not served by this deployment", owner, repo));
+        return;
+    }
+
+    match subpath.trim_matches('/') {
+        "import" => {
+            let decision = match serde_json::from_slice::<triage_import::Decision>(&body) {
+                Ok(decision) => decision,
+                Err(error) => {
+                    respond(400, format!("Invalid decision: {}", error));
+                    return;
+                }
+            };
+            let Some(number) = decision.issue else {
+                respond(400, "Missing issue number".to_string());
+                return;
+            };
+            let config = Config::from_env(owner, repo);
+            let octo = get_octo(&GithubLogin::Default);
+            let repo_config = repo_config::load(&octo, owner, repo).await;
+            match triage_import::apply(&config, &repo_config, &octo, "http-import", number, &decision).await {
+                Ok(report) => respond(200, report),
+                Err(error) => respond(422, error),
+            }
+        }
+        "replay" => {
+            let args = ["hours", "all"]
+                .iter()
+                .filter_map(|k| Some(format!("{}={}", k, qry.get(*k)?.as_str()?)))
+                .collect::<Vec<String>>()
+                .join(" ");
+            let config = Config::from_env(owner, repo);
+            let octo = get_octo(&GithubLogin::Default);
+            match replay::run(&config, &octo, &args).await {
+                Ok(report) => respond(200, report),
+                Err(error) => respond(502, error.to_string()),
+            }
+        }
+        other => respond(404, format!("Unknown endpoint '{}'", other)),
+    }
 }
 
 #[event_handler]
@@ -31,14 +219,6 @@ async fn handler(event: Result<WebhookEvent, serde_json::Error>) {
     logger::init();
     log::info!("Running github-issue-handler handler()");
 
-    let owner = env::var("github_owner").expect("github_owner not set");
-    let repo = env::var("github_repo").expect("github_repo not set");
-    let trigger_phrase = env::var("trigger_phrase").unwrap_or("@flows_summarize".to_string());
-    let llm_api_endpoint = env::var("llm_api_endpoint").expect("llm_api_endpoint not set");
-    let llm_model_name = env::var("llm_model_name").unwrap_or("gpt-4".to_string());
-    let llm_ctx_size = env::var("llm_ctx_size").unwrap_or("16384".to_string()).parse::<u32>().expect("Invalid llm_ctx_size");
-    let llm_api_key = env::var("llm_api_key").expect("llm_api_key not set");
-
     let payload = match event {
         Ok(payload) => payload,
         Err(e) => {
@@ -47,96 +227,552 @@ async fn handler(event: Result<WebhookEvent, serde_json::Error>) {
         }
     };
 
-    if let WebhookEventPayload::IssueComment(e) = payload.specific {
-        if e.action != IssueCommentWebhookEventAction::Created {
-            log::debug!("Ignoring non-created issue comment event");
+    let Some((owner, repo)) = payload
+        .repository
+        .as_ref()
+        .and_then(|r| Some((r.owner.as_ref()?.login.clone(), r.name.clone())))
+    else {
+        log::warn!("Received event without repository");
+        return;
+    };
+    if !config::registry()
+        .iter()
+        .any(|(o, r)| o.eq_ignore_ascii_case(&owner) && r.eq_ignore_ascii_case(&repo))
+    {
+        log::warn!("Ignoring event from unregistered repo {}/{}", owner, repo);
+        return;
+    }
+    let mut config = Config::from_env(&owner, &repo);
+
+    if let Some(key) = dedup::delivery_key(&payload.specific) {
+        if dedup::seen_before(&config, &key) {
+            log::info!("Skipping duplicate delivery {}", key);
+            return;
+        }
+    }
+
+    let octo = get_octo(&GithubLogin::Default);
+    let repo_config = repo_config::load(&octo, &owner, &repo).await;
+    if let Some(azure) = &repo_config.azure {
+        config.llm.azure = Some(azure.clone());
+    }
+    config.llm.persona = repo_config.persona.clone();
+    if let Some(footer) = &repo_config.footer {
+        config.footer_template = footer.clone();
+    }
+    config.quiet_hours = filters::quiet_windows(&repo_config.filters);
+    config.private_repos = repo_config.mirror.repo.iter().chain(&repo_config.private.tracking_repo).cloned().collect();
+    config.llm.profiles.extend(repo_config.llm_profiles.clone());
+    config.llm.label_profiles = repo_config.label_profiles.clone();
+    config.llm.labels = match &payload.specific {
+        WebhookEventPayload::IssueComment(e) => e.issue.labels.iter().map(|l| l.name.clone()).collect(),
+        WebhookEventPayload::Issues(e) => e.issue.labels.iter().map(|l| l.name.clone()).collect(),
+        _ => Vec::new(),
+    };
+
+    let run = metrics::start(&octo).await;
+    match payload.specific {
+        WebhookEventPayload::IssueComment(e) => handle_issue_comment(&config, &repo_config, *e).await,
+        WebhookEventPayload::Issues(e) => handle_issues(&config, &repo_config, *e).await,
+        WebhookEventPayload::Release(e) => {
+            release_notes::on_release(&config, &octo, serde_json::to_value(&e).unwrap_or_default()).await;
+        }
+        _ => log::warn!("Received unsupported event"),
+    }
+    metrics::finish(&config, &octo, run).await;
+}
+
+async fn handle_issues(config: &Config, repo_config: &RepoConfig, e: IssuesWebhookEventPayload) {
+    let octo = get_octo(&GithubLogin::Default);
+
+    match e.action {
+        IssuesWebhookEventAction::Opened => {}
+        IssuesWebhookEventAction::Closed => {
+            mirror::sync_state(config, &repo_config.mirror, &octo, e.issue.number, IssueState::Closed).await;
             return;
         }
-        
-        let body = e.comment.body.unwrap_or_else(String::new);
-        if !body.contains(&trigger_phrase) {
-            log::info!("Ignoring comment without trigger phrase");
+        IssuesWebhookEventAction::Reopened => {
+            mirror::sync_state(config, &repo_config.mirror, &octo, e.issue.number, IssueState::Open).await;
             return;
         }
+        IssuesWebhookEventAction::Assigned if repo_config.handoff => {
+            if let Some(assignee) = &e.assignee {
+                handoff::on_assigned(config, &octo, &e.issue, &assignee.login).await;
+            }
+            return;
+        }
+        IssuesWebhookEventAction::Unassigned if repo_config.handoff => {
+            if let Some(assignee) = &e.assignee {
+                handoff::on_unassigned(config, e.issue.number, &assignee.login);
+            }
+            return;
+        }
+        _ => {
+            log::debug!("Ignoring issues event {:?}", e.action);
+            return;
+        }
+    }
+
+    if let Some(reason) = filters::skip_reason(&repo_config.filters, &e.issue, &e.issue.user.login, None) {
+        log::info!("Ignoring issue #{}: {}", e.issue.number, reason);
+        return;
+    }
+
+    if repo_config.spam.enabled {
+        let text = format!("{}\n{}", e.issue.title, e.issue.body.clone().unwrap_or_default());
+        if spam::screen_issue(config, repo_config, &octo, e.issue.number, &e.issue.user.login, &text).await {
+            return;
+        }
+    }
+
+    embeddings::index_issue(config, &e.issue).await;
+
+    let outbox = Outbox::new(config);
+    if repo_config.security.enabled && security::screen_issue(config, repo_config, &octo, &outbox, &e.issue).await {
+        outbox.flush(config, &octo).await;
+        return;
+    }
+
+    greeting::on_opened(config, &repo_config.greeting, &octo, &e.issue, &outbox).await;
+    quality::on_opened(config, repo_config, &octo, &e.issue, &outbox).await;
+    titles::on_opened(config, repo_config, &octo, &e.issue, &outbox).await;
+    environment::on_opened(config, repo_config, &octo, &e.issue).await;
+
+    if repo_config.severity.enabled {
+        severity::triage(config, repo_config, &octo, &e.issue, &outbox).await;
+    }
+
+    if repo_config.milestones.enabled {
+        milestone::triage(config, repo_config, &octo, &outbox, &e.issue).await;
+    }
+
+    let author = &e.issue.user.login;
+    if priority::is_priority_reporter(config, author) {
+        log::info!("Issue #{} opened by priority reporter {}", e.issue.number, author);
+        let reason = format!("reported by priority reporter @{}", author);
+        priority::expedite(config, repo_config, &config.llm, &octo, &outbox, &e.issue, &config.priority_label, &reason).await;
+    } else if let Some(tier) = sponsors::tier_for_user(config, &octo, repo_config, author).await {
+        log::info!("Issue #{} opened by {} sponsor {}", e.issue.number, tier.name, author);
+        let llm = match &tier.model {
+            Some(model) => config.llm.with_model(model),
+            None => config.llm.clone(),
+        };
+        let label = tier.label.clone().unwrap_or(format!("sponsor/{}", tier.name));
+        if tier.expedite {
+            let reason = format!("{} sponsor @{}", tier.name, author);
+            priority::expedite(config, repo_config, &llm, &octo, &outbox, &e.issue, &label, &reason).await;
+        } else if let Err(error) = mutations::add_labels(config, &octo, e.issue.number, &[label]).await {
+            permissions::check(config, &octo, "label sponsor issues", permissions::ISSUES_WRITE, &error).await;
+        }
+    }
+
+    outbox.flush(config, &octo).await;
+
+    // A trigger phrase in the opening post works like one in a follow-up comment.
+    let body = e.issue.body.clone().unwrap_or_default();
+    if config.phrases().iter().any(|p| body.contains(p)) {
+        log::info!("Issue #{} was opened with a trigger phrase", e.issue.number);
+        let trigger = Trigger {
+            issue: &e.issue,
+            body: &body,
+            requester: &e.issue.user.login,
+            comment_id: format!("issue-{}", e.issue.number),
+            comment: None,
+        };
+        handle_trigger(config, repo_config, &octo, &trigger).await;
+    }
+}
+
+async fn handle_issue_comment(config: &Config, repo_config: &RepoConfig, e: IssueCommentWebhookEventPayload) {
+    let edited = match e.action {
+        IssueCommentWebhookEventAction::Created => false,
+        IssueCommentWebhookEventAction::Edited => true,
+        _ => {
+            log::debug!("Ignoring issue comment event {:?}", e.action);
+            return;
+        }
+    };
+
+    let body = e.comment.body.unwrap_or_else(String::new);
+    if footer::is_own(&body) {
+        log::debug!("Ignoring the bot's own comment {}", e.comment.id);
+        return;
+    }
+    if let Some(reason) = filters::skip_reason(&repo_config.filters, &e.issue, &e.comment.user.login, Some(&body)) {
+        log::info!("Ignoring comment {}: {}", e.comment.id, reason);
+        return;
+    }
+    let octo = get_octo(&GithubLogin::Default);
+    if footer::own_login(config, &octo).await.as_deref() == Some(e.comment.user.login.as_str()) {
+        log::debug!("Ignoring comment {} posted with the bot's own token", e.comment.id);
+        return;
+    }
+    let issue_number = e.issue.number;
+    let comment_id = e.comment.id.to_string();
+
+    let triggered = config.phrases().iter().any(|p| body.contains(p));
+    if edited && !triggered {
+        log::debug!("Ignoring edit of comment {} without a trigger phrase", comment_id);
+        return;
+    }
+    if triggered && dedup::debounce(config, &comment_id) {
+        log::info!("Skipping comment {}: already triggered recently", comment_id);
+        return;
+    }
+
+    if repo_config.spam.enabled
+        && spam::screen_comment(config, repo_config, &octo, issue_number, &e.comment.user.login, &e.comment.node_id, &body).await
+    {
+        return;
+    }
 
-        let issue_creator_name = e.issue.user.login;
-        let issue_title = e.issue.title;
-        let issue_number = e.issue.number;
-        let issue_html_url = e.issue.html_url;
-        let issue_body = e.issue.body.unwrap_or_default();
+    if repo_config.heat.enabled && !edited {
+        heat::on_comment(config, repo_config, &octo, &e.issue, &body).await;
+    }
 
-        let labels = e.issue.labels.iter().map(|lab| lab.name.clone()).collect::<Vec<String>>().join(", ");
-        let mut all_text_from_issue = format!(
-            "User '{}', opened an issue titled '{}', labeled '{}', with the following post: '{}'.\n",
-            issue_creator_name, issue_title, labels, issue_body
-        );
+    if repo_config.journal && !edited {
+        journal::on_comment(config, &octo, &e.issue).await;
+    }
 
-        let octo = get_octo(&GithubLogin::Default);
-        let issues = octo.issues(owner.clone(), repo.clone());
+    if e.comment.user.login == e.issue.user.login && Author::from_login(&e.issue.user.login).is_human() && !edited {
+        quality::on_author_reply(config, repo_config, &octo, &e.issue).await;
+        clarify::on_author_reply(config, repo_config, &octo, &e.issue, &body).await;
+    }
 
-        log::debug!("Fetching comments for issue #{}", issue_number);
-        let comments = match issues.list_comments(issue_number).per_page(100).send().await {
-            Ok(comments_page) => comments_page.items,
+    let trigger = Trigger {
+        issue: &e.issue,
+        body: &body,
+        requester: &e.comment.user.login,
+        comment_id,
+        comment: Some(e.comment.id),
+    };
+    handle_trigger(config, repo_config, &octo, &trigger).await;
+}
+
+/// A request to the bot: a comment, or the body of a newly opened issue.
+struct Trigger<'a> {
+    issue: &'a Issue,
+    body: &'a str,
+    requester: &'a str,
+    /// Identifies the request in failure reports; `issue-<number>` for an issue body.
+    comment_id: String,
+    comment: Option<CommentId>,
+}
+
+/// Runs whichever command or trigger phrase the request contains.
+async fn handle_trigger(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, t: &Trigger<'_>) {
+    let issues = octo.issues(config.owner.clone(), config.repo.clone());
+    let issue_number = t.issue.number;
+    let accessible = a11y::enabled(config, repo_config, t.requester);
+
+    if t.body.contains(&config.release_notes_phrase) {
+        if !quota::enforce(config, octo, issue_number, t.requester).await {
+            return;
+        }
+        match release_notes::on_command(config, octo, t.body, t.requester).await {
+            Ok(resp) => {
+                if let Err(error) = outbox::reply(config, octo, issue_number, &a11y::format(accessible, &resp)).await {
+                    permissions::check(config, octo, "post release notes", permissions::ISSUES_WRITE, &error).await;
+                }
+            }
             Err(error) => {
-                log::error!("Error getting comments from issue: {}", error);
-                return;
+                failure::report(config, octo, issue_number, &t.comment_id, t.requester, "drafting release notes", &error).await;
             }
-        };
+        }
+        return;
+    }
+
+    if t.body.contains(&config.todos_phrase) {
+        if !quota::enforce(config, octo, issue_number, t.requester).await {
+            return;
+        }
+        match todos::run(config, octo, t.issue, t.body).await {
+            Ok(Some(resp)) => {
+                if let Err(error) = outbox::reply(config, octo, issue_number, &a11y::format(accessible, &resp)).await {
+                    permissions::check(config, octo, "post action items", permissions::ISSUES_WRITE, &error).await;
+                }
+            }
+            Ok(None) => {}
+            Err(error) => {
+                failure::report(config, octo, issue_number, &t.comment_id, t.requester, "extracting action items", &error).await;
+            }
+        }
+        return;
+    }
+
+    if t.body.contains(&config.search_phrase) {
+        if !quota::enforce(config, octo, issue_number, t.requester).await {
+            return;
+        }
+        match search::run(config, octo, search::query(t.body, &config.search_phrase)).await {
+            Ok(resp) => {
+                if let Err(error) = outbox::reply(config, octo, issue_number, &a11y::format(accessible, &resp)).await {
+                    permissions::check(config, octo, "post search results", permissions::ISSUES_WRITE, &error).await;
+                }
+            }
+            Err(error) => {
+                failure::report(config, octo, issue_number, &t.comment_id, t.requester, "searching issues", &error).await;
+            }
+        }
+        return;
+    }
+
+    if t.body.contains(&config.extract_phrase) {
+        if !quota::enforce(config, octo, issue_number, t.requester).await {
+            return;
+        }
+        match extract::run(config, octo, t.issue, t.body).await {
+            Ok(resp) => {
+                if let Err(error) = outbox::reply(config, octo, issue_number, &a11y::format(accessible, &resp)).await {
+                    permissions::check(config, octo, "post structured reports", permissions::ISSUES_WRITE, &error).await;
+                }
+            }
+            Err(error) => {
+                failure::report(config, octo, issue_number, &t.comment_id, t.requester, "extracting a structured report", &error).await;
+            }
+        }
+        return;
+    }
+
+    if t.body.contains(&config.compare_phrase) {
+        if !quota::enforce(config, octo, issue_number, t.requester).await {
+            return;
+        }
+        let placeholder = Placeholder::post(config, octo, issue_number, "⏳ Comparing the two threads…").await;
+        match compare::run(config, octo, t.issue, t.body).await {
+            Ok(resp) => match output::route(config, repo_config, octo, t.issue, "compare", &resp).await {
+                Some(resp) => {
+                    let comment = placeholder.id();
+                    match placeholder.finish(config, octo, &a11y::format(accessible, &resp)).await {
+                        Ok(()) => {
+                            if let Some(id) = comment {
+                                feedback::track(config, id, issue_number, "compare", &config.llm.profile("compare").model_name);
+                            }
+                        }
+                        Err(error) => {
+                            permissions::check(config, octo, "post comparisons", permissions::ISSUES_WRITE, &error).await;
+                        }
+                    }
+                }
+                None => placeholder.discard(config, octo).await,
+            },
+            Err(error) => {
+                placeholder.discard(config, octo).await;
+                failure::report(config, octo, issue_number, &t.comment_id, t.requester, "comparing issues", &error).await;
+            }
+        }
+        return;
+    }
+
+    if t.body.contains(&config.suggest_fix_phrase) {
+        let requester = t.requester;
+        if !permissions::is_maintainer(config, octo, requester).await {
+            let resp = format!("@{} suggested fixes are restricted to maintainers.", requester);
+            if let Err(error) = outbox::reply(config, octo, issue_number, &a11y::format(accessible, &resp)).await {
+                permissions::check(config, octo, "post comments", permissions::ISSUES_WRITE, &error).await;
+            }
+            return;
+        }
+        let placeholder = Placeholder::post(config, octo, issue_number, "⏳ Drafting a suggested fix…").await;
+        match suggest_fix::run(config, octo, t.issue).await {
+            Ok(resp) => match output::route(config, repo_config, octo, t.issue, "suggest_fix", &resp).await {
+                Some(resp) => {
+                    let comment = placeholder.id();
+                    match placeholder.finish(config, octo, &a11y::format(accessible, &resp)).await {
+                        Ok(()) => {
+                            if let Some(id) = comment {
+                                feedback::track(config, id, issue_number, "suggest_fix", &config.llm.profile("suggest_fix").model_name);
+                            }
+                        }
+                        Err(error) => {
+                            permissions::check(config, octo, "post suggested fixes", permissions::ISSUES_WRITE, &error).await;
+                        }
+                    }
+                }
+                None => placeholder.discard(config, octo).await,
+            },
+            Err(error) => {
+                placeholder.discard(config, octo).await;
+                failure::report(config, octo, issue_number, &t.comment_id, requester, "suggesting a fix", &error).await;
+            }
+        }
+        return;
+    }
 
-        for comment in comments {
-            let comment_body = comment.body.unwrap_or_else(String::new);
-            let commenter = comment.user.login;
-            all_text_from_issue.push_str(&format!("{} commented: {}\n", commenter, comment_body));
-        }
-
-        log::debug!("Preparing LLM prompts");
-        let sys_prompt = format!(
-            "Given the information that user '{}' opened an issue titled '{}', your task is to deeply analyze the content of the issue posts. Distill the crux of the issue, the potential solutions suggested.",
-            issue_creator_name, issue_title
-        );
-        
-        let co = ChatOptions {
-            model: Some(&llm_model_name),
-            token_limit: llm_ctx_size,
-            restart: true,
-            system_prompt: Some(&sys_prompt),
-            temperature: Some(0.7),
-            max_tokens: Some(192),
-            ..Default::default()
+    if let Some((command, args)) = commands::bot_subcommand(t.body, &config.bot_phrase) {
+        let requester = t.requester;
+        let inv = commands::Invocation {
+            issue: t.issue,
+            requester,
+            command: &command,
+            args,
+            body: t.body,
+        };
+        let placeholder = match commands::progress_text(&command) {
+            Some(text) => Some(Placeholder::post(config, octo, issue_number, text).await),
+            None => None,
         };
-        
-        let usr_prompt = format!(
-            "Analyze the GitHub issue content: {}. Provide a concise analysis touching upon: The central problem discussed in the issue. The main solutions proposed or agreed upon. Aim for a succinct, analytical summary that stays under 128 tokens.",
-            all_text_from_issue
-        );
-
-        log::debug!("Initializing LLM service");
-        let mut llm = LLMServiceFlows::new(&llm_api_endpoint);
-        llm.set_api_key(&llm_api_key);
-        
-        log::debug!("Generating summary with LLM");
-        let summary = match llm.chat_completion(&format!("issue_{}", issue_number), &usr_prompt, &co).await {
-            Ok(r) => r.choice,
+        let resp = match commands::dispatch(config, repo_config, octo, &inv).await {
+            Ok(resp) => resp,
             Err(error) => {
-                log::error!("Error generating issue summary #{}: {}", issue_number, error);
+                if let Some(placeholder) = placeholder {
+                    placeholder.discard(config, octo).await;
+                }
+                let what = format!("running `{}`", command);
+                failure::report(config, octo, issue_number, &t.comment_id, requester, &what, &error).await;
                 return;
             }
         };
+        let routed = match command.as_str() {
+            "last-dry-run" => Some(resp),
+            _ => output::route(config, repo_config, octo, t.issue, &command, &resp).await,
+        };
+        let Some(resp) = routed else {
+            if let Some(placeholder) = placeholder {
+                placeholder.discard(config, octo).await;
+            }
+            return;
+        };
+        let posted = match placeholder {
+            Some(placeholder) => placeholder.finish(config, octo, &a11y::format(accessible, &resp)).await,
+            // The dry-run report is the one reply that has to get through in dry-run mode.
+            None if command == "last-dry-run" => issues
+                .create_comment(issue_number, footer::marked(&outbox::scrub(config, &resp)))
+                .await
+                .map(|_| ())
+                .map_err(anyhow::Error::from),
+            None => outbox::reply(config, octo, issue_number, &a11y::format(accessible, &resp)).await,
+        };
+        if let Err(error) = posted {
+            permissions::check(config, octo, "post comments", permissions::ISSUES_WRITE, &error).await;
+        }
+        return;
+    }
+
+    if !t.body.contains(&config.trigger_phrase) {
+        log::info!("Ignoring comment without trigger phrase");
+        return;
+    }
 
-        let resp = format!(
-            "{}\n{}\n{}\n\nThis result is generated by flows.network. Triggered by @{}",
-            issue_title, issue_html_url, summary, e.comment.user.login
-        );
-        
-        log::debug!("Posting summary comment");
-        if let Err(error) = issues.create_comment(issue_number, &resp).await {
-            log::error!("Error posting issue summary: {}", error);
-        } else {
-            log::info!("Successfully posted issue summary for issue #{}", issue_number);
+    if repo_config.security.enabled && security::is_flagged(&repo_config.security, t.issue) {
+        let notice = security::disclosure_notice(config, &repo_config.security, t.requester);
+        if let Err(error) = outbox::reply(config, octo, issue_number, &notice).await {
+            permissions::check(config, octo, "post disclosure notices", permissions::ISSUES_WRITE, &error).await;
         }
+        return;
+    }
+
+    if coalesce::join(config, issue_number, t.requester) {
+        return;
+    }
+
+    if !quota::enforce(config, octo, issue_number, t.requester).await {
+        return;
+    }
+
+    let placeholder = Placeholder::post(config, octo, issue_number, "⏳ Generating summary…").await;
+    coalesce::start(config, issue_number, t.requester).await;
+
+    let context = match journal::context(config, octo, t.issue).await {
+        Some(context) => Ok(context),
+        None => summary::issue_context(octo, &config.owner, &config.repo, t.issue).await,
+    };
+    let mut all_text_from_issue = match context {
+        Ok(text) => text,
+        Err(error) => {
+            coalesce::finish(config, issue_number, t.requester);
+            placeholder.discard(config, octo).await;
+            permissions::check(config, octo, "read issue comments", permissions::ISSUES_READ, &error).await;
+            failure::report(config, octo, issue_number, &t.comment_id, t.requester, "reading the issue comments", &error).await;
+            return;
+        }
+    };
+
+    images::annotate(&config.llm, &mut all_text_from_issue).await;
+
+    let env_block = if repo_config.environment.enabled {
+        let env = environment::detect(config, repo_config, t.issue, &all_text_from_issue).await;
+        environment::label(config, octo, t.issue, &env).await;
+        environment::block(&env)
     } else {
-        log::warn!("Received non-issue comment event");
+        String::new()
+    };
+
+    match octo.repos(&config.owner, &config.repo).get().await {
+        Ok(repository) => {
+            let default_branch = repository.default_branch.unwrap_or("main".to_string());
+            let code_context = code::context_block(octo, &config.owner, &config.repo, &default_branch, &all_text_from_issue).await;
+            all_text_from_issue.push_str(&code_context);
+        }
+        Err(error) => log::warn!("Could not get the default branch for code context: {}", error),
     }
-}
 
+    let related = references::expand(config, octo, t.issue).await;
+    all_text_from_issue.push_str(&references::context_block(&related));
+
+    let external = external::fetch(&all_text_from_issue).await;
+    all_text_from_issue.push_str(&external::context_block(&external));
+
+    let ci_failures = ci::analyze(config, octo, &all_text_from_issue).await;
+    all_text_from_issue.push_str(&ci::context_block(&ci_failures));
+
+    let llm = match sponsors::tier_for_user(config, octo, repo_config, t.requester).await {
+        Some(SponsorTier { model: Some(model), .. }) => config.llm.with_model(model),
+        _ => config.llm.clone(),
+    };
+
+    let detail = summary::Detail::from_args(t.body)
+        .or(repo_config.summary_detail)
+        .unwrap_or(config.summary_detail);
+
+    evaluate::capture(config, t.issue, &all_text_from_issue);
+
+    let excerpts = scope::resolve(config, octo, t.issue, t.body, t.comment).await;
 
+    let summary = match summary::summarize_focused(&llm, t.issue, &all_text_from_issue, &scope::context_block(&excerpts), detail).await {
+        Ok(summary) => summary,
+        Err(error) => {
+            coalesce::finish(config, issue_number, t.requester);
+            placeholder.discard(config, octo).await;
+            failure::report(config, octo, issue_number, &t.comment_id, t.requester, "generating the summary", &error).await;
+            return;
+        }
+    };
+
+    pin::refresh(config, octo, t.issue, &summary).await;
+
+    let sentiment = reactions::section(octo, &config.owner, &config.repo, issue_number).await;
+    let assignee = workload::section(config, repo_config, octo, t.issue).await;
+    let requesters = coalesce::finish(config, issue_number, t.requester);
+    let resp = format!(
+        "{}\n{}\n{}{}{}{}{}{}{}{}{}",
+        t.issue.title,
+        t.issue.html_url,
+        env_block,
+        summary,
+        scope::section(&excerpts),
+        sentiment,
+        assignee,
+        references::section(&related),
+        external::section(&external),
+        ci::section(&ci_failures),
+        footer::render(config, Some(&coalesce::credits(&requesters)))
+    );
+
+    let Some(resp) = output::route(config, repo_config, octo, t.issue, "summary", &resp).await else {
+        placeholder.discard(config, octo).await;
+        return;
+    };
+
+    log::debug!("Posting summary comment");
+    let comment = placeholder.id();
+    if let Err(error) = placeholder.finish(config, octo, &a11y::format(accessible, &resp)).await {
+        permissions::check(config, octo, "post issue summaries", permissions::ISSUES_WRITE, &error).await;
+    } else {
+        log::info!("Successfully posted issue summary for issue #{}", issue_number);
+        if let Some(id) = comment {
+            feedback::track(config, id, issue_number, "summary", &llm.profile("summary").model_name);
+        }
+        notify::forward(config, &format!("Summary of #{}: {}", issue_number, t.issue.title), t.issue.html_url.as_str(), &summary).await;
+    }
+}
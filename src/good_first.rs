@@ -0,0 +1,133 @@
+use crate::config::Config;
+use crate::repo_config::RepoConfig;
+use crate::{audit, code, confidence, llm, mutations, outbox, permissions, persona, store};
+use github_flows::octocrab::{models::issues::Issue, params, Octocrab};
+use serde_json::json;
+
+/// Candidate paths offered to the model as likely files to touch.
+const MAX_CANDIDATE_PATHS: usize = 40;
+/// Issues are re-evaluated at most this often.
+const CHECKED_TTL_SECS: i64 = 30 * 24 * 3600;
+
+fn checked_key(number: u64) -> String {
+    format!("good_first:{}", number)
+}
+
+struct Assessment {
+    confidence: f64,
+    reason: String,
+    files: Vec<String>,
+    hint: String,
+}
+
+async fn assess(config: &Config, issue: &Issue, paths: &[String]) -> anyhow::Result<Option<Assessment>> {
+    let text = format!("{}\n{}", issue.title, issue.body.clone().unwrap_or_default());
+    let candidates = code::candidate_paths(paths, &text, MAX_CANDIDATE_PATHS);
+    let sys_prompt = format!(
+        "You judge whether a WasmEdge GitHub issue suits a first-time contributor: a clear scope, a change localized to a few files, and no deep knowledge of the runtime's architecture needed. Reply only with JSON: {{\"suitable\": <bool>, \"reason\": \"<one sentence>\", \"files\": [\"<likely file to touch, chosen from the candidates>\"], \"hint\": \"<two or three sentences on how to get started>\", \"confidence\": <0-1>}}. {}",
+        confidence::INSTRUCTION
+    );
+    let usr_prompt = format!(
+        "Issue titled '{}':\n{}\n\nCandidate files:\n{}",
+        issue.title,
+        issue.body.clone().unwrap_or_default(),
+        if candidates.is_empty() { "(none found)".to_string() } else { candidates.join("\n") }
+    );
+    let reply = llm::chat(&config.llm.profile("good_first_issue"), &format!("good_first_{}", issue.html_url), &sys_prompt, &usr_prompt, 384)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let value = llm::extract_json(&reply).ok_or(anyhow::anyhow!("Unexpected reply: {}", reply))?;
+    if value["suitable"].as_bool() != Some(true) {
+        return Ok(None);
+    }
+    Ok(Some(Assessment {
+        confidence: confidence::parse(&value),
+        reason: value["reason"].as_str().unwrap_or_default().to_string(),
+        files: value["files"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|f| f.as_str())
+            .filter(|f| paths.is_empty() || paths.iter().any(|p| p == f))
+            .map(|f| f.to_string())
+            .collect(),
+        hint: value["hint"].as_str().unwrap_or_default().to_string(),
+    }))
+}
+
+/// Labels the issue and posts a getting-started hint.
+async fn mark(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue, assessment: &Assessment) -> anyhow::Result<()> {
+    let label = &repo_config.good_first_issue.label;
+    mutations::add_labels(config, octo, issue.number, &[label.clone()]).await?;
+
+    let hint = persona::enforce(&config.llm, &format!("good_first_{}", issue.html_url), assessment.hint.clone(), 256).await;
+    let files = if assessment.files.is_empty() {
+        String::new()
+    } else {
+        let blob = format!("https://github.com/{}/{}/blob/HEAD", config.owner, config.repo);
+        let lines = assessment.files.iter().map(|f| format!("* [`{}`]({}/{})", f, blob, f)).collect::<Vec<String>>().join("\n");
+        format!("\n\n**Likely files to touch**\n{}", lines)
+    };
+    let resp = format!("🌱 **Getting started**\n\nThis looks like a good first issue: {}\n\n{}{}", assessment.reason, hint.trim(), files);
+    outbox::reply(config, octo, issue.number, &resp).await?;
+    audit::record(config, "bot", "good_first_issue", Some(issue.number), json!({ "confidence": assessment.confidence }));
+    Ok(())
+}
+
+/// Evaluates open, unassigned and unlabeled-as-such issues that were not checked recently,
+/// labeling the newcomer-friendly ones. Returns a report of what was marked.
+pub async fn run(config: &Config, repo_config: &RepoConfig, octo: &Octocrab) -> anyhow::Result<String> {
+    let settings = &repo_config.good_first_issue;
+    let open = octo
+        .issues(&config.owner, &config.repo)
+        .list()
+        .state(params::State::Open)
+        .per_page(100)
+        .send()
+        .await?
+        .items
+        .into_iter()
+        .filter(|i| i.pull_request.is_none() && i.assignees.is_empty())
+        .filter(|i| !i.labels.iter().any(|l| l.name == settings.label))
+        .filter(|i| store::get(config, &checked_key(i.number)).is_none())
+        .take(settings.max_per_run)
+        .collect::<Vec<Issue>>();
+    if open.is_empty() {
+        return Ok("No open unassigned issues are due for a good-first-issue check.".to_string());
+    }
+
+    let default_branch = octo
+        .repos(&config.owner, &config.repo)
+        .get()
+        .await?
+        .default_branch
+        .unwrap_or("main".to_string());
+    let paths = code::tree(octo, &config.owner, &config.repo, &default_branch).await.unwrap_or_else(|error| {
+        log::warn!("Could not list repository files: {}", error);
+        Vec::new()
+    });
+
+    let mut marked = Vec::new();
+    for issue in &open {
+        store::set(config, &checked_key(issue.number), json!(true), Some(CHECKED_TTL_SECS));
+        let assessment = match assess(config, issue, &paths).await {
+            Ok(Some(assessment)) if confidence::is_confident(repo_config, "good_first_issue", assessment.confidence) => assessment,
+            Ok(_) => continue,
+            Err(error) => {
+                log::error!("Error assessing #{} for newcomers: {}", issue.number, error);
+                continue;
+            }
+        };
+        if let Err(error) = mark(config, repo_config, octo, issue, &assessment).await {
+            permissions::check(config, octo, "label good first issues", permissions::ISSUES_WRITE, &error).await;
+            continue;
+        }
+        marked.push(format!("* #{} {}", issue.number, issue.title));
+    }
+
+    Ok(if marked.is_empty() {
+        format!("Checked {} issue(s); none looked like a good first issue.", open.len())
+    } else {
+        format!("Checked {} issue(s) and labeled {} as `{}`:\n\n{}", open.len(), marked.len(), settings.label, marked.join("\n"))
+    })
+}
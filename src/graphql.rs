@@ -0,0 +1,115 @@
+use crate::author::Author;
+use crate::pin;
+use github_flows::octocrab::Octocrab;
+use serde_json::{json, Value};
+
+const THREAD_QUERY: &str = r#"
+query($owner: String!, $repo: String!, $number: Int!, $cursor: String) {
+  repository(owner: $owner, name: $repo) {
+    issue(number: $number) {
+      title
+      body
+      author { login __typename }
+      labels(first: 50) { nodes { name } }
+      reactionGroups { content reactors { totalCount } }
+      comments(first: 100, after: $cursor) {
+        pageInfo { hasNextPage endCursor }
+        nodes {
+          databaseId
+          url
+          body
+          author { login __typename }
+          reactionGroups { content reactors { totalCount } }
+        }
+      }
+    }
+  }
+}"#;
+
+/// Upper bound on comment pages fetched for one thread.
+const MAX_PAGES: usize = 10;
+
+pub struct ThreadComment {
+    pub id: u64,
+    pub url: String,
+    pub author: Author,
+    pub body: String,
+    pub reactions: Vec<(String, u64)>,
+}
+
+/// An issue with its labels, reactions and every comment, fetched in as few calls as
+/// the comment count allows.
+pub struct Thread {
+    pub title: String,
+    pub author: Author,
+    pub body: String,
+    pub labels: Vec<String>,
+    pub reactions: Vec<(String, u64)>,
+    pub comments: Vec<ThreadComment>,
+}
+
+fn reactions(groups: &Value) -> Vec<(String, u64)> {
+    groups
+        .as_array()
+        .map(|groups| {
+            groups
+                .iter()
+                .filter_map(|g| Some((g["content"].as_str()?.to_string(), g["reactors"]["totalCount"].as_u64()?)))
+                .filter(|(_, count)| *count > 0)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fetches an issue thread through GraphQL, paginating comments with cursors.
+pub async fn fetch_thread(octo: &Octocrab, owner: &str, repo: &str, number: u64) -> anyhow::Result<Thread> {
+    let mut thread: Option<Thread> = None;
+    let mut cursor: Option<String> = None;
+
+    for _ in 0..MAX_PAGES {
+        let resp: Value = octo
+            .graphql(&json!({
+                "query": THREAD_QUERY,
+                "variables": { "owner": owner, "repo": repo, "number": number, "cursor": cursor },
+            }))
+            .await?;
+        if let Some(errors) = resp.get("errors") {
+            anyhow::bail!("GraphQL errors: {}", errors);
+        }
+
+        let issue = &resp["data"]["repository"]["issue"];
+        if issue.is_null() {
+            anyhow::bail!("Issue #{} not found", number);
+        }
+
+        let t = thread.get_or_insert_with(|| Thread {
+            title: issue["title"].as_str().unwrap_or_default().to_string(),
+            author: Author::from_graphql(&issue["author"]),
+            body: pin::strip(issue["body"].as_str().unwrap_or_default()),
+            labels: issue["labels"]["nodes"]
+                .as_array()
+                .map(|l| l.iter().filter_map(|n| n["name"].as_str().map(|s| s.to_string())).collect())
+                .unwrap_or_default(),
+            reactions: reactions(&issue["reactionGroups"]),
+            comments: Vec::new(),
+        });
+
+        let comments = &issue["comments"];
+        for node in comments["nodes"].as_array().into_iter().flatten() {
+            t.comments.push(ThreadComment {
+                id: node["databaseId"].as_u64().unwrap_or_default(),
+                url: node["url"].as_str().unwrap_or_default().to_string(),
+                author: Author::from_graphql(&node["author"]),
+                body: node["body"].as_str().unwrap_or_default().to_string(),
+                reactions: reactions(&node["reactionGroups"]),
+            });
+        }
+
+        if !comments["pageInfo"]["hasNextPage"].as_bool().unwrap_or(false) {
+            break;
+        }
+        cursor = comments["pageInfo"]["endCursor"].as_str().map(|c| c.to_string());
+    }
+
+    thread.ok_or_else(|| anyhow::anyhow!("Empty response for issue #{}", number))
+}
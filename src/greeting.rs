@@ -0,0 +1,74 @@
+use crate::author::Author;
+use crate::config::Config;
+use crate::outbox::Outbox;
+use crate::permissions;
+use crate::repo_config::GreetingConfig;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use serde_json::Value;
+
+const DEFAULT_TEMPLATE: &str = "Welcome to {repo}, @{author}, and thanks for opening your first issue here! 🎉\n\nPlease have a look at our [contribution guide]({contributing}) if you haven't yet. A maintainer will follow up soon.{labels}";
+
+/// Whether `login` has no issues or pull requests in the repo other than `issue`.
+async fn is_first_interaction(config: &Config, octo: &Octocrab, issue: &Issue) -> anyhow::Result<bool> {
+    let query = format!("repo:{}/{} author:{}", config.owner, config.repo, issue.user.login);
+    let result = octo.search().issues_and_pull_requests(&query).per_page(5).send().await?;
+    Ok(result.items.iter().all(|i| i.number == issue.number))
+}
+
+/// Repo labels starting with the configured area prefix.
+async fn area_labels(config: &Config, octo: &Octocrab, prefix: &str) -> Vec<String> {
+    let route = format!("/repos/{}/{}/labels?per_page=100", config.owner, config.repo);
+    octo.get::<Value, _, ()>(route, None)
+        .await
+        .ok()
+        .and_then(|v| v.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|l| l["name"].as_str().map(|s| s.to_string()))
+        .filter(|l| l.starts_with(prefix))
+        .collect()
+}
+
+/// Welcomes authors opening their first issue or PR in the repo, unless opted out.
+pub async fn on_opened(config: &Config, greeting: &GreetingConfig, octo: &Octocrab, issue: &Issue, outbox: &Outbox) {
+    if !greeting.enabled || !Author::from_login(&issue.user.login).is_human() {
+        return;
+    }
+    match is_first_interaction(config, octo, issue).await {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(error) => {
+            log::error!("Error checking prior contributions of {}: {}", issue.user.login, error);
+            return;
+        }
+    }
+
+    let areas = area_labels(config, octo, &greeting.area_label_prefix).await;
+    let labels = if areas.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "\n\nThese area labels help route issues to the right people: {}",
+            areas.iter().map(|l| format!("`{}`", l)).collect::<Vec<String>>().join(", ")
+        )
+    };
+    let contributing = greeting.contributing_url.clone().unwrap_or(format!(
+        "https://github.com/{}/{}/blob/HEAD/CONTRIBUTING.md",
+        config.owner, config.repo
+    ));
+
+    let resp = greeting
+        .template
+        .as_deref()
+        .unwrap_or(DEFAULT_TEMPLATE)
+        .replace("{repo}", &format!("{}/{}", config.owner, config.repo))
+        .replace("{author}", &issue.user.login)
+        .replace("{contributing}", &contributing)
+        .replace("{labels}", &labels);
+
+    if let Err(error) = outbox.post(config, octo, issue.number, &resp).await {
+        permissions::check(config, octo, "greet new contributors", permissions::ISSUES_WRITE, &error).await;
+    } else {
+        log::info!("Greeted first-time contributor {} on #{}", issue.user.login, issue.number);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::config::Config;
+use crate::permissions;
+use crate::store;
+use crate::{audit, extract, llm, outbox, persona, redact, summary};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use serde_json::{json, Value};
+
+const LINKS_QUERY: &str = r#"
+query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    issue(number: $number) {
+      linkedBranches(first: 10) { nodes { ref { name } } }
+      timelineItems(first: 100, itemTypes: [CROSS_REFERENCED_EVENT, CONNECTED_EVENT]) {
+        nodes {
+          ... on CrossReferencedEvent { source { ... on PullRequest { number title url state headRefName } } }
+          ... on ConnectedEvent { subject { ... on PullRequest { number title url state headRefName } } }
+        }
+      }
+    }
+  }
+}"#;
+
+/// Audit entries about the issue included in the brief.
+const MAX_AUDIT_ENTRIES: usize = 10;
+
+/// Remembers an unassigned login for a day so the following assignment reads as a hand-off.
+const PREVIOUS_TTL_SECS: i64 = 24 * 3600;
+
+fn previous_key(number: u64) -> String {
+    format!("handoff:{}", number)
+}
+
+/// Records who was just unassigned from the issue.
+pub fn on_unassigned(config: &Config, number: u64, login: &str) {
+    store::set(config, &previous_key(number), json!(login), Some(PREVIOUS_TTL_SECS));
+}
+
+/// Lists the pull requests and branches linked to the issue.
+async fn linked_work(config: &Config, octo: &Octocrab, number: u64) -> anyhow::Result<Vec<String>> {
+    let resp: Value = octo
+        .graphql(&json!({
+            "query": LINKS_QUERY,
+            "variables": { "owner": config.owner, "repo": config.repo, "number": number },
+        }))
+        .await?;
+    if let Some(errors) = resp.get("errors") {
+        anyhow::bail!("GraphQL errors: {}", errors);
+    }
+
+    let issue = &resp["data"]["repository"]["issue"];
+    let mut links = Vec::new();
+    for node in issue["timelineItems"]["nodes"].as_array().into_iter().flatten() {
+        let pr = if node["source"].is_object() { &node["source"] } else { &node["subject"] };
+        let Some(pr_number) = pr["number"].as_u64() else {
+            continue;
+        };
+        let line = format!(
+            "* PR [#{} {}]({}) ({}, branch `{}`)",
+            pr_number,
+            pr["title"].as_str().unwrap_or_default(),
+            pr["url"].as_str().unwrap_or_default(),
+            pr["state"].as_str().unwrap_or_default().to_lowercase(),
+            pr["headRefName"].as_str().unwrap_or_default()
+        );
+        if !links.contains(&line) {
+            links.push(line);
+        }
+    }
+    for node in issue["linkedBranches"]["nodes"].as_array().into_iter().flatten() {
+        if let Some(name) = node["ref"]["name"].as_str() {
+            links.push(format!("* Branch `{}`", name));
+        }
+    }
+    Ok(links)
+}
+
+/// What the bot itself knows about the issue: stored extractions and recent audit entries.
+fn bot_state(config: &Config, number: u64) -> String {
+    let mut state = String::new();
+    if let Some(extraction) = extract::stored(config, number) {
+        state.push_str(&format!("Structured report: {}\n", extraction));
+    }
+    let entries = audit::recent(config)
+        .into_iter()
+        .filter(|e| e.issue == Some(number))
+        .collect::<Vec<audit::Entry>>();
+    for entry in entries.iter().rev().take(MAX_AUDIT_ENTRIES).rev() {
+        state.push_str(&format!("Bot action by {}: {} {}\n", entry.actor, entry.action, entry.details));
+    }
+    state
+}
+
+/// Posts a hand-off brief for the new assignee of an issue with some history: what has
+/// been tried, the current hypothesis and the linked PRs and branches.
+pub async fn on_assigned(config: &Config, octo: &Octocrab, issue: &Issue, assignee: &str) {
+    let previous = store::get(config, &previous_key(issue.number)).and_then(|v| v.as_str().map(|s| s.to_string()));
+    store::del(config, &previous_key(issue.number));
+    if issue.comments == 0 && previous.is_none() {
+        log::debug!("Skipping hand-off brief for #{}: nothing to hand off", issue.number);
+        return;
+    }
+
+    let context = match summary::issue_context(octo, &config.owner, &config.repo, issue).await {
+        Ok(context) => context,
+        Err(error) => {
+            log::error!("Error getting comments for hand-off of #{}: {}", issue.number, error);
+            return;
+        }
+    };
+    let links = linked_work(config, octo, issue.number).await.unwrap_or_else(|error| {
+        log::warn!("Could not fetch linked work for #{}: {}", issue.number, error);
+        Vec::new()
+    });
+
+    let sys_prompt = config.llm.persona.apply(
+        "You brief an engineer taking over a WasmEdge GitHub issue. Write concise Markdown with the sections: What's been tried, Current hypothesis, Open questions, Suggested next step. Only state what the thread supports.",
+    );
+    let usr_prompt = format!(
+        "Issue titled '{}'.\n\nThread:\n{}\n\nBot notes:\n{}\n\nLinked work:\n{}",
+        issue.title,
+        redact::sanitize(&context),
+        bot_state(config, issue.number),
+        if links.is_empty() { "(none)".to_string() } else { links.join("\n") }
+    );
+    let conversation_id = format!("handoff_{}", issue.html_url);
+    let brief = match llm::chat(&config.llm.profile("handoff"), &conversation_id, &sys_prompt, &usr_prompt, 768).await {
+        Ok(brief) => persona::enforce(&config.llm, &conversation_id, brief, 768).await,
+        Err(error) => {
+            log::error!("Error generating hand-off brief for #{}: {}", issue.number, error);
+            return;
+        }
+    };
+
+    let heading = match &previous {
+        Some(previous) if !previous.eq_ignore_ascii_case(assignee) => {
+            format!("🤝 **Hand-off brief** for @{}, taking over from @{}", assignee, previous)
+        }
+        _ => format!("🤝 **Hand-off brief** for @{}", assignee),
+    };
+    let links_section = if links.is_empty() {
+        String::new()
+    } else {
+        format!("\n\n**Linked PRs and branches**\n{}", links.join("\n"))
+    };
+    let resp = format!("{}\n\n{}{}", heading, brief.trim(), links_section);
+    if let Err(error) = outbox::reply(config, octo, issue.number, &resp).await {
+        permissions::check(config, octo, "post hand-off briefs", permissions::ISSUES_WRITE, &error).await;
+    }
+}
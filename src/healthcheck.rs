@@ -0,0 +1,173 @@
+use crate::capabilities;
+use crate::config::Config;
+use crate::llm;
+use crate::repo_config::RepoConfig;
+use crate::store;
+use github_flows::octocrab::Octocrab;
+use serde_json::{json, Value};
+use std::fmt::Display;
+use std::time::Instant;
+
+const PROBE_KEY: &str = "healthcheck:probe";
+const MAX_DETAIL_CHARS: usize = 120;
+
+/// One row of the health report.
+struct Check {
+    name: &'static str,
+    ok: bool,
+    latency_ms: u128,
+    details: String,
+}
+
+impl Check {
+    fn new(name: &'static str, started: Instant, result: Result<String, impl Display>) -> Self {
+        let latency_ms = started.elapsed().as_millis();
+        match result {
+            Ok(details) => Check { name, ok: true, latency_ms, details },
+            Err(error) => {
+                log::error!("Health check '{}' failed: {}", name, error);
+                Check {
+                    name,
+                    ok: false,
+                    latency_ms,
+                    details: error.to_string(),
+                }
+            }
+        }
+    }
+
+    fn row(&self) -> String {
+        let details = self
+            .details
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .take(MAX_DETAIL_CHARS)
+            .collect::<String>()
+            .replace('|', "\\|");
+        let status = if self.ok { "✅ ok" } else { "❌ failed" };
+        format!("| {} | {} | {} ms | {} |\n", self.name, status, self.latency_ms, details)
+    }
+}
+
+/// Credentials and rate limit of the GitHub token.
+async fn github(octo: &Octocrab) -> anyhow::Result<String> {
+    let limits = octo.get::<Value, _, ()>("/rate_limit", None).await?;
+    let core = &limits["resources"]["core"];
+    Ok(format!(
+        "{} of {} REST calls left",
+        core["remaining"].as_u64().unwrap_or_default(),
+        core["limit"].as_u64().unwrap_or_default()
+    ))
+}
+
+async fn repository(config: &Config, octo: &Octocrab) -> anyhow::Result<String> {
+    let repository = octo.repos(&config.owner, &config.repo).get().await?;
+    Ok(format!("default branch `{}`", repository.default_branch.unwrap_or("main".to_string())))
+}
+
+async fn permissions(config: &Config, repo_config: &RepoConfig, octo: &Octocrab) -> Result<String, String> {
+    let (_, missing) = capabilities::audit(config, repo_config, octo).await;
+    if missing.is_empty() {
+        Ok("every enabled feature has its permissions".to_string())
+    } else {
+        Err(format!("missing {}; run `capabilities` for details", missing.join(", ")))
+    }
+}
+
+/// Sends a tiny prompt to the configured model.
+async fn model(config: &Config) -> Result<String, String> {
+    let cfg = config.llm.profile("healthcheck");
+    let conversation_id = format!("healthcheck_{}", chrono::Utc::now().timestamp_millis());
+    let reply = llm::chat(&cfg, &conversation_id, "You are a health check. Reply with the single word OK.", "ping", 4).await?;
+    if reply.trim().is_empty() {
+        return Err(format!("`{}` returned an empty reply", cfg.model_name));
+    }
+    Ok(format!("`{}` replied", cfg.model_name))
+}
+
+/// Writes, reads back and deletes a value.
+fn kv(config: &Config) -> Result<String, String> {
+    let token = chrono::Utc::now().timestamp_millis();
+    store::set(config, PROBE_KEY, json!(token), Some(60));
+    let read = store::get(config, PROBE_KEY);
+    store::del(config, PROBE_KEY);
+    match read.and_then(|v| v.as_i64()) {
+        Some(value) if value == token => Ok("write, read and delete succeeded".to_string()),
+        Some(value) => Err(format!("read back {} instead of {}", value, token)),
+        None => Err("the value written could not be read back".to_string()),
+    }
+}
+
+/// `@flows_bot healthcheck`: verifies the GitHub token and its permissions, the LLM endpoint
+/// and the key-value store, and reports each with its latency.
+pub async fn run(config: &Config, repo_config: &RepoConfig, octo: &Octocrab) -> String {
+    let mut checks = Vec::new();
+
+    let started = Instant::now();
+    checks.push(Check::new("GitHub API", started, github(octo).await));
+    let started = Instant::now();
+    checks.push(Check::new("Repository access", started, repository(config, octo).await));
+    let started = Instant::now();
+    checks.push(Check::new("Permissions", started, permissions(config, repo_config, octo).await));
+    let started = Instant::now();
+    checks.push(Check::new("LLM", started, model(config).await));
+    let started = Instant::now();
+    checks.push(Check::new("Key-value store", started, kv(config)));
+
+    let failed = checks.iter().filter(|c| !c.ok).count();
+    let mut report = format!(
+        "**Health check for {}/{}**\n\n| Check | Status | Latency | Details |\n|---|---|---|---|\n",
+        config.owner, config.repo
+    );
+    for check in &checks {
+        report.push_str(&check.row());
+    }
+    report.push_str(&format!(
+        "\nModel `{}`, {} mode. ",
+        config.llm.model_name,
+        if config.dry_run { "dry-run" } else { "live" }
+    ));
+    if failed == 0 {
+        report.push_str("Everything is working.");
+    } else {
+        report.push_str(&format!("{} check(s) failed; see the flow's logs for the full errors.", failed));
+        log::warn!("Health check for {}/{} found {} failing check(s)", config.owner, config.repo, failed);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(ok: bool, details: &str) -> Check {
+        Check {
+            name: "store",
+            ok,
+            latency_ms: 12,
+            details: details.to_string(),
+        }
+    }
+
+    #[test]
+    fn row_shows_status_and_latency() {
+        assert_eq!(check(true, "round trip ok").row(), "| store | ✅ ok | 12 ms | round trip ok |\n");
+        assert_eq!(check(false, "timed out").row(), "| store | ❌ failed | 12 ms | timed out |\n");
+    }
+
+    #[test]
+    fn row_keeps_details_to_one_escaped_line() {
+        assert_eq!(check(false, "a | b\nbacktrace").row(), "| store | ❌ failed | 12 ms | a \\| b |\n");
+        let row = check(false, &"x".repeat(500)).row();
+        assert!(row.ends_with(&format!("| {} |\n", "x".repeat(MAX_DETAIL_CHARS))));
+    }
+
+    #[test]
+    fn new_records_the_error_as_details() {
+        let check = Check::new("llm", Instant::now(), Err::<String, _>("HTTP 401"));
+        assert!(!check.ok);
+        assert_eq!(check.details, "HTTP 401");
+    }
+}
@@ -0,0 +1,109 @@
+use crate::audit;
+use crate::config::Config;
+use crate::graphql;
+use crate::llm;
+use crate::moderation;
+use crate::mutations;
+use crate::notify;
+use crate::outbox;
+use crate::permissions;
+use crate::private;
+use crate::repo_config::RepoConfig;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::json;
+
+lazy_static! {
+    static ref FRUSTRATION: Regex = Regex::new(
+        r"(?i)\b(unacceptable|ridiculous|still broken|still not|any update|wtf|useless|waste of|disappointed|frustrat\w*|angry|ignored|months?)\b|!{2,}"
+    )
+    .unwrap();
+}
+
+/// Recent comments scored for temperature.
+const RECENT_COMMENTS: usize = 10;
+
+/// Cheap pre-check so the LLM is only asked about comments that show some sign of heat.
+fn looks_heated(text: &str) -> bool {
+    let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+    let caps = text.chars().filter(|c| c.is_uppercase()).count();
+    FRUSTRATION.is_match(text) || (letters > 40 && caps * 2 > letters)
+}
+
+/// Scores the emotional temperature of the latest comments from 0 (calm) to 1 (hostile).
+async fn score(config: &Config, octo: &Octocrab, issue: &Issue) -> anyhow::Result<(f64, String)> {
+    let thread = graphql::fetch_thread(octo, &config.owner, &config.repo, issue.number).await?;
+    let recent = thread
+        .comments
+        .iter()
+        .rev()
+        .take(RECENT_COMMENTS)
+        .rev()
+        .map(|c| format!("{}: {}", c.author, c.body))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let sys_prompt = "You gauge the emotional temperature of open source issue threads. Reply only with JSON: {\"heat\": <0-1, where 0 is calm and technical and 1 is hostile or very frustrated>, \"reason\": \"<one sentence>\"}.";
+    let usr_prompt = format!("Issue '{}'. Latest comments:\n{}", thread.title, recent);
+    let reply = llm::chat(&config.llm.profile("heat"), &format!("heat_{}", issue.html_url), sys_prompt, &usr_prompt, 96)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let value = llm::extract_json(&reply).ok_or(anyhow::anyhow!("Unexpected reply: {}", reply))?;
+    Ok((
+        value["heat"].as_f64().unwrap_or(0.0).clamp(0.0, 1.0),
+        value["reason"].as_str().unwrap_or_default().to_string(),
+    ))
+}
+
+/// Flags a thread for maintainer attention when a new comment pushes its temperature past
+/// the configured threshold.
+pub async fn on_comment(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue, body: &str) {
+    let heat = &repo_config.heat;
+    if !looks_heated(body) || issue.labels.iter().any(|l| l.name == heat.label) {
+        return;
+    }
+    let (temperature, reason) = match score(config, octo, issue).await {
+        Ok(scored) => scored,
+        Err(error) => {
+            log::error!("Error scoring thread temperature of #{}: {}", issue.number, error);
+            return;
+        }
+    };
+    log::debug!("Thread #{} temperature {:.2}: {}", issue.number, temperature, reason);
+    if temperature < heat.threshold {
+        return;
+    }
+
+    if let Err(error) = mutations::add_labels(config, octo, issue.number, &[heat.label.clone()]).await {
+        permissions::check(config, octo, "flag heated threads", permissions::ISSUES_WRITE, &error).await;
+        return;
+    }
+    audit::record(config, "heat", "flagged", Some(issue.number), json!({ "heat": temperature, "reason": reason }));
+    let headline = format!("#{} needs maintainer attention: {}", issue.number, issue.title);
+    notify::forward(config, &headline, issue.html_url.as_str(), &reason).await;
+
+    let proposal = if heat.suggest_lock {
+        moderation::propose_lock(config, issue, "heated", "heat")
+    } else {
+        None
+    };
+    let mut note = format!("Thread temperature {:.2}: {}", temperature, reason);
+    if let Some(proposal) = &proposal {
+        note.push_str(&format!("\n\n{}", proposal));
+    }
+    if private::deliver(config, repo_config, octo, issue.number, "heat", &note).await {
+        return;
+    }
+    let mut resp = match &heat.notify {
+        Some(mention) => format!("{} this thread may need a maintainer's attention: {}", mention, reason),
+        None if proposal.is_some() => format!("This thread may need a maintainer's attention: {}", reason),
+        None => return,
+    };
+    if let Some(proposal) = &proposal {
+        resp.push_str(&format!("\n\n{}", proposal));
+    }
+    if let Err(error) = outbox::post_parts(config, octo, issue.number, &[resp]).await {
+        log::error!("Error notifying maintainers about #{}: {}", issue.number, error);
+    }
+}
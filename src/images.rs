@@ -0,0 +1,50 @@
+use crate::llm::{self, LlmConfig};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::env;
+
+lazy_static! {
+    static ref MARKDOWN_IMAGE: Regex = Regex::new(r"!\[[^\]]*\]\((https?://[^)\s]+)").unwrap();
+    static ref HTML_IMAGE: Regex = Regex::new(r#"<img[^>]*\ssrc="(https?://[^"]+)""#).unwrap();
+}
+
+/// Finds image attachments in Markdown or HTML form, in order of appearance and deduplicated.
+pub fn extract_image_urls(text: &str) -> Vec<String> {
+    let mut urls: Vec<(usize, String)> = MARKDOWN_IMAGE
+        .captures_iter(text)
+        .chain(HTML_IMAGE.captures_iter(text))
+        .filter_map(|c| c.get(1))
+        .map(|m| (m.start(), m.as_str().to_string()))
+        .collect();
+    urls.sort_by_key(|(pos, _)| *pos);
+
+    let mut seen = Vec::new();
+    for (_, url) in urls {
+        if !seen.contains(&url) {
+            seen.push(url);
+        }
+    }
+    seen
+}
+
+/// Appends a description of any screenshots in `context` produced by the vision model,
+/// so image-only reports still give the summarizer something to work with.
+pub async fn annotate(cfg: &LlmConfig, context: &mut String) {
+    let Some(model) = &cfg.vision_model else {
+        return;
+    };
+    let max_images = env::var("max_images").ok().and_then(|n| n.parse::<usize>().ok()).unwrap_or(4);
+    let urls = extract_image_urls(context).into_iter().take(max_images).collect::<Vec<String>>();
+    if urls.is_empty() {
+        return;
+    }
+
+    log::debug!("Describing {} image attachment(s)", urls.len());
+    let prompt = "These screenshots are attached to a GitHub issue. For each image, transcribe any error messages, logs or code verbatim, then briefly describe what it shows.";
+    match llm::describe_images(cfg, model, prompt, &urls, 512).await {
+        Ok(description) => {
+            context.push_str(&format!("Attached screenshots show: {}\n", description));
+        }
+        Err(error) => log::error!("Error describing image attachments: {}", error),
+    }
+}
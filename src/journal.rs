@@ -0,0 +1,165 @@
+use crate::config::Config;
+use crate::graphql::{self, ThreadComment};
+use crate::{footer, llm, store};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::env;
+
+/// Characters of each comment passed to the condensing prompt.
+const MAX_COMMENT_CHARS: usize = 1500;
+/// Once the journal grows past this many entries, the oldest half is merged into one.
+const MAX_ENTRIES: usize = 12;
+
+/// A rolling, compressed record of an issue thread, so long threads can be summarized
+/// from the journal and the few comments since its last entry.
+#[derive(Serialize, Deserialize, Default)]
+struct Journal {
+    entries: Vec<Entry>,
+    /// Database id of the newest comment the journal covers.
+    last_comment: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    at: i64,
+    /// How many comments the entry condenses.
+    comments: usize,
+    text: String,
+}
+
+fn key(number: u64) -> String {
+    format!("journal:{}", number)
+}
+
+fn load(config: &Config, number: u64) -> Option<Journal> {
+    store::get(config, &key(number)).and_then(|v| serde_json::from_value(v).ok())
+}
+
+/// New comments needed before they are condensed into an entry, from `journal_burst`.
+fn burst() -> usize {
+    env::var("journal_burst").ok().and_then(|n| n.parse::<usize>().ok()).unwrap_or(10).max(1)
+}
+
+async fn condense(config: &Config, issue: &Issue, comments: &[&ThreadComment]) -> Result<String, String> {
+    // The bot's own outputs are summaries of the thread, not part of it.
+    let text = comments
+        .iter()
+        .filter(|c| !footer::is_own(&c.body))
+        .map(|c| format!("{} commented: {}\n", c.author, c.body.chars().take(MAX_COMMENT_CHARS).collect::<String>()))
+        .collect::<String>();
+    let sys_prompt = "You keep a terse journal of a GitHub issue discussion. Record only what is new: findings, reproductions, environment details, decisions, proposed fixes and open questions, each attributed to its author.";
+    let usr_prompt = format!(
+        "New comments on issue '{}':\n{}\nWrite the journal entry for these comments in at most 120 words.",
+        issue.title, text
+    );
+    llm::chat(&config.llm.profile("journal"), &format!("journal_{}", issue.html_url), sys_prompt, &usr_prompt, 256).await
+}
+
+/// Merges the oldest half of the entries into one, keeping the journal's size flat.
+async fn compact(config: &Config, issue: &Issue, journal: &mut Journal) {
+    if journal.entries.len() <= MAX_ENTRIES {
+        return;
+    }
+    let oldest = journal.entries.drain(..MAX_ENTRIES / 2).collect::<Vec<Entry>>();
+    let text = oldest.iter().map(|e| format!("- {}\n", e.text)).collect::<String>();
+    let usr_prompt = format!(
+        "Journal entries for issue '{}', oldest first:\n{}\nMerge them into one entry of at most 200 words, dropping anything later superseded.",
+        issue.title, text
+    );
+    let merged = llm::chat(
+        &config.llm.profile("journal"),
+        &format!("journal_compact_{}", issue.html_url),
+        "You keep a terse journal of a GitHub issue discussion.",
+        &usr_prompt,
+        384,
+    )
+    .await;
+    match merged {
+        Ok(merged) => journal.entries.insert(
+            0,
+            Entry {
+                at: oldest.last().map(|e| e.at).unwrap_or_default(),
+                comments: oldest.iter().map(|e| e.comments).sum(),
+                text: merged,
+            },
+        ),
+        Err(error) => {
+            log::error!("Error compacting journal of #{}: {}", issue.number, error);
+            let mut entries = oldest;
+            entries.append(&mut journal.entries);
+            journal.entries = entries;
+        }
+    }
+}
+
+/// Appends a condensed entry once a burst of `journal_burst` comments has built up since
+/// the last one.
+pub async fn on_comment(config: &Config, octo: &Octocrab, issue: &Issue) {
+    let mut journal = load(config, issue.number).unwrap_or_default();
+    // The payload's comment count saves fetching the thread on most comments.
+    let covered = journal.entries.iter().map(|e| e.comments).sum::<usize>();
+    if (issue.comments as usize).saturating_sub(covered) < burst() {
+        return;
+    }
+    let thread = match graphql::fetch_thread(octo, &config.owner, &config.repo, issue.number).await {
+        Ok(thread) => thread,
+        Err(error) => {
+            log::warn!("Could not fetch #{} for its journal: {}", issue.number, error);
+            return;
+        }
+    };
+    let pending = thread.comments.iter().filter(|c| c.id > journal.last_comment).collect::<Vec<&ThreadComment>>();
+    if pending.len() < burst() {
+        return;
+    }
+
+    match condense(config, issue, &pending).await {
+        Ok(text) => {
+            journal.entries.push(Entry {
+                at: chrono::Utc::now().timestamp(),
+                comments: pending.len(),
+                text,
+            });
+            journal.last_comment = pending.iter().map(|c| c.id).max().unwrap_or(journal.last_comment);
+        }
+        Err(error) => {
+            log::error!("Error journaling #{}: {}", issue.number, error);
+            return;
+        }
+    }
+    compact(config, issue, &mut journal).await;
+
+    log::info!("Journaled {} comment(s) on #{}", pending.len(), issue.number);
+    store::set(config, &key(issue.number), json!(journal), None);
+}
+
+/// The issue as the summarizer sees it when it has a journal: the opening post, the
+/// journal entries and the comments since the last entry. `None` without a journal.
+pub async fn context(config: &Config, octo: &Octocrab, issue: &Issue) -> Option<String> {
+    let journal = load(config, issue.number)?;
+    let thread = match graphql::fetch_thread(octo, &config.owner, &config.repo, issue.number).await {
+        Ok(thread) => thread,
+        Err(error) => {
+            log::warn!("Could not fetch #{} to summarize from its journal: {}", issue.number, error);
+            return None;
+        }
+    };
+
+    let mut context = format!(
+        "User '{}', opened an issue titled '{}', labeled '{}', with the following post: '{}'.\n",
+        thread.author,
+        thread.title,
+        thread.labels.join(", "),
+        thread.body
+    );
+    let covered = journal.entries.iter().map(|e| e.comments).sum::<usize>();
+    context.push_str(&format!("Journal of the first {} comments, oldest first:\n", covered));
+    for entry in &journal.entries {
+        context.push_str(&format!("- {}\n", entry.text));
+    }
+    for comment in thread.comments.iter().filter(|c| c.id > journal.last_comment) {
+        context.push_str(&format!("{} commented: {}\n", comment.author, comment.body));
+    }
+    Some(context)
+}
@@ -0,0 +1,136 @@
+use crate::author::Author;
+use crate::config::Config;
+use crate::notify;
+use crate::redact::SECRET_PATTERNS;
+use crate::repo_config::RepoConfig;
+use crate::{audit, dry_run, store};
+use github_flows::octocrab::Octocrab;
+use serde_json::{json, Value};
+
+const SINCE_KEY: &str = "leak_audit:since";
+const REPORTED_KEY: &str = "leak_audit:reported";
+/// Findings remembered so a leak that stays in place is reported once.
+const MAX_REPORTED: usize = 1000;
+/// How far back the first scan looks.
+const FIRST_SCAN_SECS: i64 = 7 * 24 * 3600;
+const MAX_PAGES: u32 = 5;
+/// ISO 8601 in UTC without an offset, which would need escaping in the query string.
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// A credential found in an issue, pull request or comment body.
+struct Finding {
+    kind: &'static str,
+    url: String,
+    author: Author,
+    /// The first characters only, enough to identify which credential to rotate.
+    hint: String,
+}
+
+/// Pages through an endpoint that accepts `since`, e.g. all issues or all comments.
+async fn updated_since(octo: &Octocrab, route: &str, since: &str) -> Vec<Value> {
+    let mut items = Vec::new();
+    for page in 1..=MAX_PAGES {
+        let url = format!("{}?since={}&per_page=100&page={}", route, since, page);
+        let batch = match octo.get::<Value, _, ()>(url, None).await {
+            Ok(value) => value.as_array().cloned().unwrap_or_default(),
+            Err(error) => {
+                log::error!("Error listing {} for the leak audit: {}", route, error);
+                break;
+            }
+        };
+        let done = batch.len() < 100;
+        items.extend(batch);
+        if done {
+            break;
+        }
+    }
+    items
+}
+
+fn scan(item: &Value) -> Vec<Finding> {
+    let body = item["body"].as_str().unwrap_or_default();
+    SECRET_PATTERNS
+        .iter()
+        .flat_map(|(kind, re)| {
+            re.find_iter(body).map(move |m| Finding {
+                kind: *kind,
+                url: item["html_url"].as_str().unwrap_or_default().to_string(),
+                author: Author::from_login(item["user"]["login"].as_str().unwrap_or_default()),
+                hint: format!("{}…", m.as_str().chars().take(6).collect::<String>()),
+            })
+        })
+        .collect()
+}
+
+fn report(findings: &[Finding]) -> String {
+    let lines = findings
+        .iter()
+        .map(|f| format!("* {} `{}` posted by {}: {}", f.kind, f.hint, f.author.mention(), f.url))
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!(
+        "🔐 **Possible leaked credentials**\n\n{}\n\n**Remediation**\n1. Rotate or revoke each credential with its provider first; assume it is compromised.\n2. Edit the comment or issue to remove it.\n3. Delete the revision from the edit history (Edited ▾ → the revision → Delete revision), since edits keep the old text visible.\n4. For content that was indexed or forked, contact GitHub Support to purge cached views.",
+        lines
+    )
+}
+
+/// Scans issues, pull requests and comments updated since the last run for credentials and
+/// reports new findings privately: as an issue in the mirror repo and through the
+/// notification sinks. Nothing is posted in the public repo.
+pub async fn run(config: &Config, repo_config: &RepoConfig, octo: &Octocrab) {
+    let now = chrono::Utc::now();
+    let since = store::get(config, SINCE_KEY)
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or((now - chrono::Duration::seconds(FIRST_SCAN_SECS)).format(TIME_FORMAT).to_string());
+
+    let base = format!("/repos/{}/{}/issues", config.owner, config.repo);
+    let mut items = updated_since(octo, &base, &since).await;
+    items.extend(updated_since(octo, &format!("{}/comments", base), &since).await);
+
+    let mut reported = store::get(config, REPORTED_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+        .unwrap_or_default();
+    let findings = items
+        .iter()
+        .flat_map(scan)
+        .filter(|f| {
+            let key = format!("{}|{}|{}", f.url, f.kind, f.hint);
+            if reported.contains(&key) {
+                return false;
+            }
+            reported.push(key);
+            true
+        })
+        .collect::<Vec<Finding>>();
+    store::set(config, SINCE_KEY, json!(now.format(TIME_FORMAT).to_string()), None);
+    if findings.is_empty() {
+        return;
+    }
+    if reported.len() > MAX_REPORTED {
+        reported.drain(..reported.len() - MAX_REPORTED);
+    }
+    store::set(config, REPORTED_KEY, json!(reported), None);
+
+    log::warn!("Leak audit found {} credential(s) in {}/{}", findings.len(), config.owner, config.repo);
+    let body = report(&findings);
+    if dry_run::intercept(config, "report_leaks", None, json!({ "findings": findings.len(), "body": body })) {
+        return;
+    }
+    let mut delivered = false;
+    if let Some((owner, repo)) = repo_config.mirror.repo.as_deref().and_then(|r| r.split_once('/')) {
+        let title = format!("[{}/{}] Credential leak audit {}", config.owner, config.repo, now.format("%Y-%m-%d"));
+        match octo.issues(owner, repo).create(title).body(&body).send().await {
+            Ok(_) => delivered = true,
+            Err(error) => log::error!("Error filing leak audit in {}/{}: {}", owner, repo, error),
+        }
+    }
+    if !notify::sinks().is_empty() {
+        let headline = format!("{} possible credential leak(s)", findings.len());
+        notify::forward(config, &headline, &format!("https://github.com/{}/{}", config.owner, config.repo), &body).await;
+        delivered = true;
+    }
+    if !delivered {
+        log::error!("Leak audit findings for {}/{} could not be reported privately; configure a mirror repo or notify_sinks", config.owner, config.repo);
+    }
+    audit::record(config, "bot", "leak_audit", None, json!({ "findings": findings.len(), "delivered": delivered }));
+}
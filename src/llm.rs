@@ -0,0 +1,340 @@
+use crate::metrics;
+use crate::persona::Persona;
+use llmservice_flows::{chat::ChatOptions, LLMServiceFlows};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::env;
+
+#[derive(Clone)]
+pub struct LlmConfig {
+    pub api_endpoint: String,
+    pub api_key: String,
+    pub model_name: String,
+    pub ctx_size: u32,
+    /// Vision-capable model used to describe image attachments; disabled when unset. With
+    /// Azure this is the name of the vision deployment.
+    pub vision_model: Option<String>,
+    /// Model for the semantic issue index; disabled when unset.
+    pub embedding_model: Option<String>,
+    /// Extra request headers, e.g. Azure's `api-key` or `OpenAI-Organization`.
+    pub headers: Vec<(String, String)>,
+    pub proxy: Option<String>,
+    pub azure: Option<AzureDeployment>,
+    /// Style applied to user-facing prose; set from the repo config.
+    pub persona: Persona,
+    pub temperature: f32,
+    pub top_p: Option<f32>,
+    /// Sampling overrides by command, e.g. `severity` or `summary`.
+    pub profiles: HashMap<String, Sampling>,
+    /// Specializations by issue label; set from the repo config.
+    pub label_profiles: HashMap<String, LabelProfile>,
+    /// Labels of the issue the current event is about, selecting `label_profiles`.
+    pub labels: Vec<String>,
+    /// Domain guidance appended to system prompts, merged in by [`LlmConfig::profile`].
+    pub guidance: Vec<String>,
+}
+
+/// Sampling settings for one command. Unset fields keep the deployment defaults.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Sampling {
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub model: Option<String>,
+}
+
+/// Prompt specialization for issues carrying a label, e.g. WASI-NN background for `wasi-nn`
+/// issues or toolchain guidance for `build` issues.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LabelProfile {
+    /// Appended to the system prompt.
+    pub guidance: Option<String>,
+    /// Commands the profile applies to; every command when empty.
+    pub commands: Vec<String>,
+    /// Overrides on top of the command's own profile.
+    #[serde(flatten)]
+    pub sampling: Sampling,
+}
+
+/// Classifiers should answer the same way every time; prose keeps the default temperature.
+fn default_profiles() -> HashMap<String, Sampling> {
+    ["severity", "spam", "heat", "milestone", "extract", "judge"]
+        .iter()
+        .map(|name| {
+            let sampling = Sampling {
+                temperature: Some(0.1),
+                ..Default::default()
+            };
+            (name.to_string(), sampling)
+        })
+        .collect()
+}
+
+/// Reads `llm_profiles`, a JSON object of profiles keyed by command, over the defaults.
+fn env_profiles() -> HashMap<String, Sampling> {
+    let mut profiles = default_profiles();
+    match serde_json::from_str::<HashMap<String, Sampling>>(&env::var("llm_profiles").unwrap_or("{}".to_string())) {
+        Ok(overrides) => profiles.extend(overrides),
+        Err(error) => log::error!("Invalid llm_profiles: {}", error),
+    }
+    profiles
+}
+
+/// Azure OpenAI addresses models by deployment name in the URL, with the API version
+/// as a query parameter, rather than by the request's `model` field.
+#[derive(Clone, Deserialize)]
+pub struct AzureDeployment {
+    pub deployment: String,
+    #[serde(default = "default_azure_api_version")]
+    pub api_version: String,
+    /// Overrides `llm_api_endpoint`, e.g. `https://my-resource.openai.azure.com`.
+    pub endpoint: Option<String>,
+}
+
+fn default_azure_api_version() -> String {
+    env::var("llm_azure_api_version").unwrap_or("2024-06-01".to_string())
+}
+
+impl LlmConfig {
+    pub fn from_env() -> Self {
+        LlmConfig {
+            api_endpoint: env::var("llm_api_endpoint").expect("llm_api_endpoint not set"),
+            api_key: env::var("llm_api_key").unwrap_or_default(),
+            model_name: env::var("llm_model_name").unwrap_or("gpt-4".to_string()),
+            ctx_size: env::var("llm_ctx_size").unwrap_or("16384".to_string()).parse::<u32>().expect("Invalid llm_ctx_size"),
+            vision_model: env::var("llm_vision_model").ok().filter(|m| !m.is_empty()),
+            embedding_model: env::var("llm_embedding_model").ok().filter(|m| !m.is_empty()),
+            headers: parse_headers(&env::var("llm_headers").unwrap_or_default()),
+            proxy: env::var("llm_proxy").ok().filter(|p| !p.is_empty()),
+            azure: env::var("llm_azure_deployment")
+                .ok()
+                .filter(|d| !d.is_empty())
+                .map(|deployment| AzureDeployment {
+                    deployment,
+                    api_version: default_azure_api_version(),
+                    endpoint: None,
+                }),
+            persona: Persona::default(),
+            temperature: env::var("llm_temperature").ok().and_then(|t| t.parse::<f32>().ok()).unwrap_or(0.7),
+            top_p: env::var("llm_top_p").ok().and_then(|t| t.parse::<f32>().ok()),
+            profiles: env_profiles(),
+            label_profiles: HashMap::new(),
+            labels: Vec::new(),
+            guidance: Vec::new(),
+        }
+    }
+
+    /// Custom headers, proxies and Azure addressing aren't supported by the chat client,
+    /// so such deployments talk to the endpoint over plain HTTP instead.
+    fn needs_http(&self) -> bool {
+        !self.headers.is_empty() || self.proxy.is_some() || self.azure.is_some()
+    }
+
+    fn apply_sampling(&mut self, sampling: &Sampling) {
+        if let Some(model) = &sampling.model {
+            self.model_name = model.clone();
+        }
+        self.temperature = sampling.temperature.unwrap_or(self.temperature);
+        self.top_p = sampling.top_p.or(self.top_p);
+    }
+
+    /// Returns a copy of this config with the sampling profile for `command` applied, then
+    /// every label profile matching the issue's labels, in label order.
+    pub fn profile(&self, command: &str) -> Self {
+        let mut cfg = self.clone();
+        cfg.guidance = Vec::new();
+        if let Some(sampling) = self.profiles.get(command) {
+            cfg.apply_sampling(sampling);
+        }
+
+        let mut matching = self
+            .label_profiles
+            .iter()
+            .filter(|(label, _)| self.labels.iter().any(|l| l.eq_ignore_ascii_case(label)))
+            .filter(|(_, p)| p.commands.is_empty() || p.commands.iter().any(|c| c == command))
+            .collect::<Vec<(&String, &LabelProfile)>>();
+        matching.sort_by(|a, b| a.0.cmp(b.0));
+        for (_, label_profile) in matching {
+            cfg.apply_sampling(&label_profile.sampling);
+            if let Some(guidance) = &label_profile.guidance {
+                cfg.guidance.push(guidance.clone());
+            }
+        }
+        cfg
+    }
+
+    /// Appends the merged label guidance to a system prompt.
+    fn system_prompt(&self, sys_prompt: &str) -> String {
+        if self.guidance.is_empty() {
+            return sys_prompt.to_string();
+        }
+        format!("{}\n\n{}", sys_prompt, self.guidance.join("\n\n"))
+    }
+
+    /// Returns a copy of this config addressing a different model.
+    pub fn with_model(&self, model_name: &str) -> Self {
+        LlmConfig {
+            model_name: model_name.to_string(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Parses `llm_headers`, a `;`-separated list of `Name: value` pairs.
+fn parse_headers(raw: &str) -> Vec<(String, String)> {
+    raw.split(';')
+        .filter_map(|h| h.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+/// Extracts the outermost JSON object from a model reply, tolerating surrounding prose
+/// and code fences.
+pub fn extract_json(reply: &str) -> Option<Value> {
+    let start = reply.find('{')?;
+    let end = reply.rfind('}')?;
+    if end < start {
+        return None;
+    }
+    serde_json::from_str(&reply[start..=end]).ok()
+}
+
+/// Runs a single-turn chat completion and returns the model's reply.
+pub async fn chat(
+    cfg: &LlmConfig,
+    conversation_id: &str,
+    system_prompt: &str,
+    user_prompt: &str,
+    max_tokens: u16,
+) -> Result<String, String> {
+    let system_prompt = cfg.system_prompt(system_prompt);
+    let system_prompt = system_prompt.as_str();
+    if cfg.needs_http() {
+        let body = json!({
+            "model": cfg.model_name,
+            "messages": [
+                { "role": "system", "content": system_prompt },
+                { "role": "user", "content": user_prompt },
+            ],
+            "temperature": cfg.temperature,
+            "top_p": cfg.top_p,
+            "max_tokens": max_tokens,
+        });
+        return post_chat(cfg, &body).await;
+    }
+
+    let co = ChatOptions {
+        model: Some(&cfg.model_name),
+        token_limit: cfg.ctx_size,
+        restart: true,
+        system_prompt: Some(system_prompt),
+        temperature: Some(cfg.temperature),
+        top_p: cfg.top_p,
+        max_tokens: Some(max_tokens),
+        ..Default::default()
+    };
+
+    let mut llm = LLMServiceFlows::new(&cfg.api_endpoint);
+    llm.set_api_key(&cfg.api_key);
+
+    let reply = llm
+        .chat_completion(conversation_id, user_prompt, &co)
+        .await
+        .map(|r| r.choice)
+        .map_err(|e| e.to_string())?;
+    metrics::record_llm(&cfg.model_name, None, &format!("{}\n{}", system_prompt, user_prompt), &reply);
+    Ok(reply)
+}
+
+/// Posts a request body to the OpenAI-compatible `/chat/completions` endpoint, applying the
+/// configured headers and proxy, and returns the first choice's content.
+async fn post_chat(cfg: &LlmConfig, body: &Value) -> Result<String, String> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy) = &cfg.proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy).map_err(|e| e.to_string())?);
+    }
+    let client = builder.build().map_err(|e| e.to_string())?;
+
+    let mut req = match &cfg.azure {
+        Some(azure) => {
+            let endpoint = azure.endpoint.as_deref().unwrap_or(&cfg.api_endpoint);
+            let url = format!(
+                "{}/openai/deployments/{}/chat/completions?api-version={}",
+                endpoint.trim_end_matches('/'),
+                azure.deployment,
+                azure.api_version
+            );
+            let mut body = body.clone();
+            if let Some(obj) = body.as_object_mut() {
+                obj.remove("model");
+            }
+            client.post(&url).header("api-key", cfg.api_key.as_str()).json(&body)
+        }
+        None => {
+            let url = format!("{}/chat/completions", cfg.api_endpoint.trim_end_matches('/'));
+            let req = client.post(&url).json(body);
+            if cfg.api_key.is_empty() {
+                req
+            } else {
+                req.bearer_auth(&cfg.api_key)
+            }
+        }
+    };
+    for (name, value) in &cfg.headers {
+        req = req.header(name.as_str(), value.as_str());
+    }
+
+    let resp = req.send().await.map_err(|e| e.to_string())?;
+    let status = resp.status();
+    let text = resp.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(format!("{}: {}", status, text));
+    }
+    let value: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+
+    let reply = value["choices"][0]["message"]["content"]
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| format!("Unexpected response: {}", value))?;
+    let model = body["model"].as_str().unwrap_or(&cfg.model_name);
+    metrics::record_llm(model, value.get("usage"), &body["messages"].to_string(), &reply);
+    Ok(reply)
+}
+
+/// Sends a multi-modal request (text plus image URLs) over HTTP, since the chat client
+/// only supports text messages.
+pub async fn describe_images(
+    cfg: &LlmConfig,
+    model: &str,
+    prompt: &str,
+    image_urls: &[String],
+    max_tokens: u16,
+) -> Result<String, String> {
+    let mut content = vec![json!({ "type": "text", "text": prompt })];
+    content.extend(
+        image_urls
+            .iter()
+            .map(|url| json!({ "type": "image_url", "image_url": { "url": url } })),
+    );
+    let body = json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": content }],
+        "max_tokens": max_tokens,
+    });
+
+    // Azure ignores `model` and routes by deployment, so the vision model is its own deployment.
+    if let Some(azure) = &cfg.azure {
+        let vision = LlmConfig {
+            azure: Some(AzureDeployment {
+                deployment: model.to_string(),
+                ..azure.clone()
+            }),
+            ..cfg.clone()
+        };
+        return post_chat(&vision, &body).await;
+    }
+    post_chat(cfg, &body).await
+}
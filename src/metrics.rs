@@ -0,0 +1,223 @@
+use crate::config::Config;
+use crate::store;
+use chrono::{Datelike, Duration, Utc};
+use github_flows::octocrab::Octocrab;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Daily aggregates are kept a little longer than the longest report covers.
+const RETENTION_SECS: i64 = 35 * 24 * 3600;
+const MAX_DAYS: i64 = 28;
+
+/// Usage over one or more runs.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Usage {
+    pub runs: u64,
+    pub llm_calls: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Calls whose token counts were estimated because the response had no `usage` field.
+    pub estimated_calls: u64,
+    /// In the currency of `llm_prices`.
+    pub cost: f64,
+    pub latency_ms: u64,
+    pub api_calls: u64,
+}
+
+impl Usage {
+    fn add(&mut self, other: &Usage) {
+        self.runs += other.runs;
+        self.llm_calls += other.llm_calls;
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.estimated_calls += other.estimated_calls;
+        self.cost += other.cost;
+        self.latency_ms += other.latency_ms;
+        self.api_calls += other.api_calls;
+    }
+}
+
+lazy_static! {
+    /// LLM usage of the run in progress. Each instance handles one event at a time.
+    static ref CURRENT: Mutex<Usage> = Mutex::new(Usage::default());
+}
+
+/// Per-1K-token prices by model, from `llm_prices`,
+/// e.g. `{"gpt-4": {"prompt": 0.03, "completion": 0.06}}`.
+#[derive(Deserialize)]
+struct Price {
+    #[serde(default)]
+    prompt: f64,
+    #[serde(default)]
+    completion: f64,
+}
+
+fn price(model: &str) -> Option<Price> {
+    let prices = serde_json::from_str::<HashMap<String, Price>>(&env::var("llm_prices").unwrap_or("{}".to_string()))
+        .map_err(|e| log::error!("Invalid llm_prices: {}", e))
+        .ok()?;
+    prices.into_iter().find(|(name, _)| name.eq_ignore_ascii_case(model)).map(|(_, p)| p)
+}
+
+/// Rough token count for providers that don't report usage: about four characters a token.
+fn estimate(text: &str) -> u64 {
+    (text.chars().count() as u64 + 3) / 4
+}
+
+/// Records one chat completion. `usage` is the response's `usage` object when the
+/// provider returned one; otherwise the counts are estimated from the text.
+pub fn record_llm(model: &str, usage: Option<&Value>, prompt: &str, reply: &str) {
+    let reported = usage.and_then(|u| Some((u["prompt_tokens"].as_u64()?, u["completion_tokens"].as_u64()?)));
+    let (prompt_tokens, completion_tokens) = reported.unwrap_or((estimate(prompt), estimate(reply)));
+    let cost = price(model)
+        .map(|p| (prompt_tokens as f64 * p.prompt + completion_tokens as f64 * p.completion) / 1000.0)
+        .unwrap_or(0.0);
+
+    let Ok(mut current) = CURRENT.lock() else {
+        return;
+    };
+    current.llm_calls += 1;
+    current.prompt_tokens += prompt_tokens;
+    current.completion_tokens += completion_tokens;
+    current.cost += cost;
+    if reported.is_none() {
+        current.estimated_calls += 1;
+    }
+}
+
+/// A run being measured; see [`start`].
+pub struct Run {
+    started: Instant,
+    api_used: Option<u64>,
+}
+
+/// Calls made against the token's REST and GraphQL limits in the current window. Reading
+/// the rate limit doesn't count against it.
+async fn api_used(octo: &Octocrab) -> Option<u64> {
+    let limits = octo.get::<Value, _, ()>("/rate_limit", None).await.ok()?;
+    let resources = &limits["resources"];
+    Some(resources["core"]["used"].as_u64()? + resources["graphql"]["used"].as_u64().unwrap_or(0))
+}
+
+/// Starts measuring a run, clearing anything left over from the previous one.
+pub async fn start(octo: &Octocrab) -> Run {
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = Usage::default();
+    }
+    Run {
+        started: Instant::now(),
+        api_used: api_used(octo).await,
+    }
+}
+
+fn day_key(date: chrono::NaiveDate) -> String {
+    format!("metrics:{}", date.format("%Y-%m-%d"))
+}
+
+fn load_day(config: &Config, date: chrono::NaiveDate) -> Usage {
+    store::get(config, &day_key(date))
+        .and_then(|v| serde_json::from_value::<Usage>(v).ok())
+        .unwrap_or_default()
+}
+
+/// Adds the run's usage to today's aggregate for the repository. The API call count is the
+/// change in the token's rate-limit usage, so it also counts other flows sharing the token,
+/// and reads as zero when the limit window reset mid-run.
+pub async fn finish(config: &Config, octo: &Octocrab, run: Run) {
+    let mut usage = match CURRENT.lock() {
+        Ok(mut current) => std::mem::take(&mut *current),
+        Err(_) => return,
+    };
+    usage.runs = 1;
+    usage.latency_ms = run.started.elapsed().as_millis() as u64;
+    if let (Some(before), Some(after)) = (run.api_used, api_used(octo).await) {
+        usage.api_calls = after.saturating_sub(before);
+    }
+
+    let today = Utc::now().date_naive();
+    let mut day = load_day(config, today);
+    day.add(&usage);
+    store::set(config, &day_key(today), json!(day), Some(RETENTION_SECS));
+    log::info!(
+        "Run took {}ms, {} LLM calls ({} prompt / {} completion tokens), {} API calls",
+        usage.latency_ms,
+        usage.llm_calls,
+        usage.prompt_tokens,
+        usage.completion_tokens,
+        usage.api_calls
+    );
+}
+
+fn row(label: &str, usage: &Usage) -> String {
+    let avg_latency = if usage.runs == 0 { 0 } else { usage.latency_ms / usage.runs };
+    format!(
+        "| {} | {} | {} | {} | {} | {:.2} | {:.1}s | {} |\n",
+        label,
+        usage.runs,
+        usage.llm_calls,
+        usage.prompt_tokens,
+        usage.completion_tokens,
+        usage.cost,
+        avg_latency as f64 / 1000.0,
+        usage.api_calls
+    )
+}
+
+/// Renders usage for `@flows_bot stats`: one row per day, or per week with `weekly`.
+/// A number sets how many days to cover, up to four weeks.
+pub fn report(config: &Config, args: &str) -> String {
+    let weekly = args.split_whitespace().any(|a| a.eq_ignore_ascii_case("weekly"));
+    let days = args
+        .split_whitespace()
+        .find_map(|a| a.parse::<i64>().ok())
+        .unwrap_or(if weekly { MAX_DAYS } else { 7 })
+        .clamp(1, MAX_DAYS);
+
+    let today = Utc::now().date_naive();
+    let mut rows: Vec<(String, Usage)> = Vec::new();
+    let mut total = Usage::default();
+    for offset in 0..days {
+        let date = today - Duration::days(offset);
+        let usage = load_day(config, date);
+        total.add(&usage);
+        let label = if weekly {
+            let week = date.iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        } else {
+            date.format("%Y-%m-%d").to_string()
+        };
+        match rows.last_mut() {
+            Some((last, acc)) if *last == label => acc.add(&usage),
+            _ => rows.push((label, usage)),
+        }
+    }
+
+    let mut out = format!(
+        "**Usage for {}/{} over the last {} days**\n\n| {} | Runs | LLM calls | Prompt tokens | Completion tokens | Cost | Avg latency | API calls |\n|---|---|---|---|---|---|---|---|\n",
+        config.owner,
+        config.repo,
+        days,
+        if weekly { "Week" } else { "Day" }
+    );
+    for (label, usage) in &rows {
+        out.push_str(&row(label, usage));
+    }
+    out.push_str(&row("**Total**", &total));
+
+    if total.estimated_calls > 0 {
+        out.push_str(&format!(
+            "\nToken counts for {} of {} LLM calls are estimated; the provider didn't report usage.",
+            total.estimated_calls, total.llm_calls
+        ));
+    }
+    if env::var("llm_prices").is_err() {
+        out.push_str("\nSet `llm_prices` to per-1K-token prices by model to see costs.");
+    }
+    out
+}
@@ -0,0 +1,276 @@
+use crate::audit;
+use crate::confidence;
+use crate::config::Config;
+use crate::dry_run;
+use crate::llm;
+use crate::outbox::Outbox;
+use crate::permissions;
+use crate::repo_config::RepoConfig;
+use crate::store;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const STATUS_FIELD_QUERY: &str = r#"
+query($project: ID!) {
+  node(id: $project) {
+    ... on ProjectV2 {
+      field(name: "Status") { ... on ProjectV2SingleSelectField { id options { id name } } }
+    }
+  }
+}"#;
+
+const ADD_ITEM_MUTATION: &str = r#"
+mutation($project: ID!, $content: ID!) {
+  addProjectV2ItemById(input: {projectId: $project, contentId: $content}) { item { id } }
+}"#;
+
+const SET_STATUS_MUTATION: &str = r#"
+mutation($project: ID!, $item: ID!, $field: ID!, $option: String!) {
+  updateProjectV2ItemFieldValue(input: {projectId: $project, itemId: $item, fieldId: $field, value: {singleSelectOptionId: $option}}) {
+    projectV2Item { id }
+  }
+}"#;
+
+/// Days of burndown history kept per milestone.
+const MAX_POINTS: usize = 180;
+
+struct Milestone {
+    number: u64,
+    title: String,
+    description: String,
+    open_issues: u64,
+    closed_issues: u64,
+}
+
+/// One day's issue counts for a milestone.
+#[derive(Serialize, Deserialize)]
+struct Point {
+    date: String,
+    open: u64,
+    closed: u64,
+}
+
+fn burndown_key(number: u64) -> String {
+    format!("burndown:{}", number)
+}
+
+fn points(config: &Config, number: u64) -> Vec<Point> {
+    store::get(config, &burndown_key(number))
+        .and_then(|v| serde_json::from_value::<Vec<Point>>(v).ok())
+        .unwrap_or_default()
+}
+
+async fn open_milestones(config: &Config, octo: &Octocrab) -> anyhow::Result<Vec<Milestone>> {
+    let route = format!("/repos/{}/{}/milestones?state=open&per_page=100", config.owner, config.repo);
+    let milestones = octo.get::<Value, _, ()>(route, None).await?;
+    Ok(milestones
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|m| {
+            Some(Milestone {
+                number: m["number"].as_u64()?,
+                title: m["title"].as_str()?.to_string(),
+                description: m["description"].as_str().unwrap_or_default().to_string(),
+                open_issues: m["open_issues"].as_u64().unwrap_or_default(),
+                closed_issues: m["closed_issues"].as_u64().unwrap_or_default(),
+            })
+        })
+        .collect())
+}
+
+/// Asks the LLM which open milestone fits the issue best.
+async fn choose(config: &Config, issue: &Issue, milestones: &[Milestone]) -> Option<(u64, f64, String)> {
+    let list = milestones
+        .iter()
+        .map(|m| format!("{}: {} - {}", m.number, m.title, m.description))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let sys_prompt = format!(
+        "You plan releases for the WasmEdge project. Pick the open milestone that best fits the issue, or null if none does. Reply only with JSON: {{\"milestone\": <number or null>, \"reason\": \"<one sentence>\", \"confidence\": <0-1>}}. {}",
+        confidence::INSTRUCTION
+    );
+    let usr_prompt = format!(
+        "Open milestones:\n{}\n\nIssue titled '{}':\n{}",
+        list,
+        issue.title,
+        issue.body.clone().unwrap_or_default()
+    );
+    let reply = match llm::chat(&config.llm.profile("milestone"), &format!("milestone_{}", issue.html_url), &sys_prompt, &usr_prompt, 128).await {
+        Ok(reply) => reply,
+        Err(error) => {
+            log::error!("Error choosing a milestone for #{}: {}", issue.number, error);
+            return None;
+        }
+    };
+    let value = llm::extract_json(&reply)?;
+    let number = value["milestone"].as_u64().filter(|n| milestones.iter().any(|m| m.number == *n))?;
+    Some((number, confidence::parse(&value), value["reason"].as_str().unwrap_or_default().to_string()))
+}
+
+/// Adds the issue to the configured Projects v2 board, in the configured Status column.
+async fn add_to_project(config: &Config, octo: &Octocrab, project_id: &str, column: Option<&str>, issue: &Issue) -> anyhow::Result<()> {
+    if dry_run::intercept(config, "add_to_project", Some(issue.number), json!({ "project": project_id, "column": column })) {
+        return Ok(());
+    }
+    let added: Value = octo
+        .graphql(&json!({ "query": ADD_ITEM_MUTATION, "variables": { "project": project_id, "content": issue.node_id } }))
+        .await?;
+    let item_id = added["data"]["addProjectV2ItemById"]["item"]["id"]
+        .as_str()
+        .ok_or(anyhow::anyhow!("Unexpected response: {}", added))?;
+
+    let Some(column) = column else {
+        return Ok(());
+    };
+    let fields: Value = octo
+        .graphql(&json!({ "query": STATUS_FIELD_QUERY, "variables": { "project": project_id } }))
+        .await?;
+    let field = &fields["data"]["node"]["field"];
+    let option = field["options"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|o| o["name"].as_str().map_or(false, |n| n.eq_ignore_ascii_case(column)))
+        .and_then(|o| o["id"].as_str())
+        .ok_or(anyhow::anyhow!("Project has no Status column '{}'", column))?;
+
+    let updated: Value = octo
+        .graphql(&json!({
+            "query": SET_STATUS_MUTATION,
+            "variables": { "project": project_id, "item": item_id, "field": field["id"], "option": option },
+        }))
+        .await?;
+    if let Some(errors) = updated.get("errors") {
+        anyhow::bail!("GraphQL errors: {}", errors);
+    }
+    Ok(())
+}
+
+/// Suggests the best open milestone for a new issue, setting it when the suggestion is
+/// confident and `auto_set` is on, and files the issue on the configured project board.
+pub async fn triage(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, outbox: &Outbox, issue: &Issue) {
+    let settings = &repo_config.milestones;
+
+    if let Some(project_id) = &settings.project_id {
+        if let Err(error) = add_to_project(config, octo, project_id, settings.project_column.as_deref(), issue).await {
+            log::error!("Error adding #{} to project {}: {}", issue.number, project_id, error);
+        }
+    }
+
+    let milestones = match open_milestones(config, octo).await {
+        Ok(milestones) if !milestones.is_empty() => milestones,
+        Ok(_) => return,
+        Err(error) => {
+            log::error!("Error listing milestones: {}", error);
+            return;
+        }
+    };
+    let Some((number, score, reason)) = choose(config, issue, &milestones).await else {
+        return;
+    };
+    let title = milestones.iter().find(|m| m.number == number).map(|m| m.title.clone()).unwrap_or_default();
+
+    if settings.auto_set && confidence::is_confident(repo_config, "milestone", score) {
+        let route = format!("/repos/{}/{}/issues/{}", config.owner, config.repo, issue.number);
+        if dry_run::intercept(config, "set_milestone", Some(issue.number), json!({ "milestone": title })) {
+            return;
+        }
+        if let Err(error) = octo.patch::<Value, _, _>(route, Some(&json!({ "milestone": number }))).await {
+            permissions::check(config, octo, "set milestones", permissions::ISSUES_WRITE, &error).await;
+            return;
+        }
+        audit::record(config, "milestone", "milestone_set", Some(issue.number), json!({ "milestone": title, "confidence": score }));
+        log::info!("Set milestone of #{} to {}", issue.number, title);
+        return;
+    }
+
+    let resp = format!("📅 Suggested milestone: **{}** ({:.0}% confident). {}", title, score * 100.0, reason);
+    if let Err(error) = outbox.post(config, octo, issue.number, &resp).await {
+        log::error!("Error posting milestone suggestion: {}", error);
+    }
+}
+
+/// Records today's open and closed counts for every open milestone.
+pub async fn record_burndown(config: &Config, octo: &Octocrab) {
+    let milestones = match open_milestones(config, octo).await {
+        Ok(milestones) => milestones,
+        Err(error) => {
+            log::error!("Error listing milestones for burndown: {}", error);
+            return;
+        }
+    };
+
+    let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    for milestone in milestones {
+        let mut history = points(config, milestone.number);
+        history.retain(|p| p.date != date);
+        history.push(Point {
+            date: date.clone(),
+            open: milestone.open_issues,
+            closed: milestone.closed_issues,
+        });
+        if history.len() > MAX_POINTS {
+            history.drain(..history.len() - MAX_POINTS);
+        }
+        match serde_json::to_value(&history) {
+            Ok(value) => store::set(config, &burndown_key(milestone.number), value, None),
+            Err(error) => log::error!("Error serializing burndown of {}: {}", milestone.title, error),
+        }
+    }
+}
+
+/// A Mermaid line chart of open issues per day, preceded by a plain-text description for
+/// readers who can't see the diagram.
+fn burndown_chart(title: &str, history: &[Point]) -> String {
+    let (Some(first), Some(last)) = (history.first(), history.last()) else {
+        return "_No burndown data recorded yet._".to_string();
+    };
+    let max = history.iter().map(|p| p.open + p.closed).max().unwrap_or_default().max(1);
+    let days = history.iter().map(|p| format!("\"{}\"", &p.date[5..])).collect::<Vec<String>>().join(", ");
+    let open = history.iter().map(|p| p.open.to_string()).collect::<Vec<String>>().join(", ");
+    format!(
+        "Burndown: open issues went from {} on {} to {} on {}.\n\n```mermaid\nxychart-beta\n    title \"Burndown: {}\"\n    x-axis [{}]\n    y-axis \"Open issues\" 0 --> {}\n    line [{}]\n```",
+        first.open,
+        first.date,
+        last.open,
+        last.date,
+        title.replace('"', "'"),
+        days,
+        max,
+        open
+    )
+}
+
+/// Renders the `milestone-status` reply: progress of every open milestone, or only those
+/// whose title contains `args`, with a burndown chart each.
+pub async fn status_report(config: &Config, octo: &Octocrab, args: &str) -> anyhow::Result<String> {
+    let filter = args.trim().to_lowercase();
+    let milestones = open_milestones(config, octo)
+        .await?
+        .into_iter()
+        .filter(|m| filter.is_empty() || m.title.to_lowercase().contains(&filter))
+        .collect::<Vec<Milestone>>();
+    if milestones.is_empty() {
+        return Ok("No matching open milestones.".to_string());
+    }
+
+    let sections = milestones
+        .iter()
+        .map(|m| {
+            let total = m.open_issues + m.closed_issues;
+            let percent = if total == 0 { 0 } else { m.closed_issues * 100 / total };
+            format!(
+                "### {}\n\n{} of {} issues closed ({}%).\n\n{}",
+                m.title,
+                m.closed_issues,
+                total,
+                percent,
+                burndown_chart(&m.title, &points(config, m.number))
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n");
+    Ok(format!("## Milestone status for {}/{}\n\n{}", config.owner, config.repo, sections))
+}
@@ -0,0 +1,88 @@
+use crate::config::Config;
+use crate::dry_run;
+use crate::footer;
+use crate::outbox::Outbox;
+use crate::permissions;
+use crate::repo_config::MirrorConfig;
+use crate::store;
+use github_flows::octocrab::{models::issues::Issue, models::IssueState, Octocrab};
+use serde_json::json;
+
+fn mirror_key(number: u64) -> String {
+    format!("mirror:{}", number)
+}
+
+/// Whether analyses of `kind` (e.g. `severity`, `security`) go to the private mirror.
+pub fn is_private(mirror: &MirrorConfig, kind: &str) -> bool {
+    mirror.repo.is_some() && mirror.kinds.iter().any(|k| k == kind)
+}
+
+/// Returns the number of the private issue mirroring `issue`, opening it on first use.
+/// The mirror links back to the public thread, and the pairing is kept in the store.
+async fn mirror_issue(config: &Config, octo: &Octocrab, owner: &str, repo: &str, issue: &Issue) -> anyhow::Result<u64> {
+    if let Some(number) = store::get(config, &mirror_key(issue.number)).and_then(|v| v.as_u64()) {
+        return Ok(number);
+    }
+
+    let body = format!(
+        "Team-only mirror of {}\n\nSensitive analyses of the public issue are posted here instead of the public thread. This issue is closed and reopened along with it.",
+        issue.html_url
+    );
+    let created = octo
+        .issues(owner, repo)
+        .create(format!("[{}/{}#{}] {}", config.owner, config.repo, issue.number, issue.title))
+        .body(body)
+        .send()
+        .await?;
+    store::set(config, &mirror_key(issue.number), json!(created.number), None);
+    log::info!("Mirrored #{} to {}/{}#{}", issue.number, owner, repo, created.number);
+    Ok(created.number)
+}
+
+/// Posts `body` on the public issue, or on its private mirror when `kind` is configured as
+/// team-only.
+pub async fn post(
+    config: &Config,
+    mirror: &MirrorConfig,
+    octo: &Octocrab,
+    outbox: &Outbox,
+    issue: &Issue,
+    kind: &str,
+    body: &str,
+) -> anyhow::Result<()> {
+    if !is_private(mirror, kind) || !post_private(config, mirror, octo, issue, body).await? {
+        return outbox.post(config, octo, issue.number, body).await;
+    }
+    Ok(())
+}
+
+/// Posts `body` on the private mirror of `issue`. Returns false when no mirror is configured.
+pub async fn post_private(config: &Config, mirror: &MirrorConfig, octo: &Octocrab, issue: &Issue, body: &str) -> anyhow::Result<bool> {
+    let Some((owner, repo)) = mirror.repo.as_deref().and_then(|r| r.split_once('/')) else {
+        return Ok(false);
+    };
+
+    if dry_run::intercept(config, "mirror_comment", Some(issue.number), json!({ "repo": format!("{}/{}", owner, repo), "body": body })) {
+        return Ok(true);
+    }
+    let number = mirror_issue(config, octo, owner, repo, issue).await?;
+    octo.issues(owner, repo).create_comment(number, footer::marked(body)).await?;
+    Ok(true)
+}
+
+/// Closes or reopens the private mirror along with the public issue.
+pub async fn sync_state(config: &Config, mirror: &MirrorConfig, octo: &Octocrab, issue_number: u64, state: IssueState) {
+    let Some((owner, repo)) = mirror.repo.as_deref().and_then(|r| r.split_once('/')) else {
+        return;
+    };
+    let Some(number) = store::get(config, &mirror_key(issue_number)).and_then(|v| v.as_u64()) else {
+        return;
+    };
+
+    if dry_run::intercept(config, "mirror_state", Some(issue_number), json!({ "state": format!("{:?}", state) })) {
+        return;
+    }
+    if let Err(error) = octo.issues(owner, repo).update(number).state(state).send().await {
+        permissions::check(config, octo, "sync mirror issue state", permissions::ISSUES_WRITE, &error).await;
+    }
+}
@@ -0,0 +1,82 @@
+use crate::config::Config;
+use crate::mutations;
+use crate::pending::{self, State};
+use crate::permissions;
+use github_flows::octocrab::{models::issues::Issue, params::LockReason, Octocrab};
+
+/// Maps a `lock` argument to GitHub's lock reason and how it reads in replies.
+fn lock_reason(word: &str) -> Option<(LockReason, &'static str)> {
+    match word.to_lowercase().as_str() {
+        "spam" => Some((LockReason::Spam, "spam")),
+        "heated" | "too-heated" => Some((LockReason::TooHeated, "too heated")),
+        "resolved" => Some((LockReason::Resolved, "resolved")),
+        "off-topic" | "offtopic" => Some((LockReason::OffTopic, "off-topic")),
+        _ => None,
+    }
+}
+
+async fn lock(config: &Config, octo: &Octocrab, issue: &Issue, requester: &str, word: &str) -> anyhow::Result<String> {
+    let Some((reason, name)) = lock_reason(word) else {
+        return Ok(format!("@{} usage: `lock spam|heated|resolved|off-topic`.", requester));
+    };
+    if issue.locked {
+        return Ok(format!("#{} is already locked.", issue.number));
+    }
+    if let Err(error) = mutations::lock_issue(config, octo, requester, issue.number, reason).await {
+        permissions::check(config, octo, "lock conversations", permissions::ISSUES_WRITE, &error).await;
+        return Err(error.into());
+    }
+    Ok(format!("🔒 @{} locked this conversation as {}.", requester, name))
+}
+
+/// `@flows_bot lock <reason>`. A lock proposal waiting on the issue counts as confirmed.
+pub async fn run_lock(config: &Config, octo: &Octocrab, issue: &Issue, requester: &str, args: &str) -> anyhow::Result<String> {
+    let word = args.split_whitespace().next().unwrap_or_default();
+    let resp = lock(config, octo, issue, requester, word).await?;
+    if pending::get(config, issue.number).map_or(false, |p| p.state == State::Proposed && p.action == "lock") {
+        let _ = pending::decide(config, issue.number, State::Confirmed, requester);
+    }
+    Ok(resp)
+}
+
+/// `@flows_bot unlock`.
+pub async fn run_unlock(config: &Config, octo: &Octocrab, issue: &Issue, requester: &str) -> anyhow::Result<String> {
+    if !issue.locked {
+        return Ok(format!("#{} is not locked.", issue.number));
+    }
+    if let Err(error) = mutations::unlock_issue(config, octo, requester, issue.number).await {
+        permissions::check(config, octo, "unlock conversations", permissions::ISSUES_WRITE, &error).await;
+        return Err(error.into());
+    }
+    Ok(format!("🔓 @{} unlocked this conversation.", requester))
+}
+
+/// Proposes locking the issue on behalf of a detector and returns the instructions to show
+/// maintainers, or `None` when a proposal is already waiting or the issue is locked.
+pub fn propose_lock(config: &Config, issue: &Issue, word: &str, proposed_by: &str) -> Option<String> {
+    let (_, name) = lock_reason(word)?;
+    if issue.locked || !pending::propose(config, issue.number, "lock", word, proposed_by) {
+        return None;
+    }
+    Some(format!(
+        "The bot proposes locking this conversation as {}. A maintainer can reply with the bot's `confirm` command to lock it or `dismiss` to keep it open.",
+        name
+    ))
+}
+
+/// `@flows_bot confirm` and `@flows_bot dismiss`: decides the proposal waiting on the issue
+/// and, when confirmed, carries it out.
+pub async fn decide(config: &Config, octo: &Octocrab, issue: &Issue, requester: &str, confirm: bool) -> anyhow::Result<String> {
+    let state = if confirm { State::Confirmed } else { State::Dismissed };
+    let action = match pending::decide(config, issue.number, state, requester) {
+        Ok(action) => action,
+        Err(reason) => return Ok(format!("@{} nothing to {}: {}.", requester, if confirm { "confirm" } else { "dismiss" }, reason)),
+    };
+    if !confirm {
+        return Ok(format!("@{} dismissed the proposal to {} this conversation.", requester, action.action));
+    }
+    match action.action.as_str() {
+        "lock" => lock(config, octo, issue, requester, &action.reason).await,
+        other => Ok(format!("Unknown pending action `{}`; nothing was done.", other)),
+    }
+}
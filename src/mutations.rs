@@ -0,0 +1,103 @@
+use crate::audit;
+use crate::config::Config;
+use crate::dry_run;
+use github_flows::octocrab::{self, models::CommentId, params::LockReason, Octocrab};
+use serde_json::{json, Value};
+
+/// Adds labels to an issue.
+pub async fn add_labels(config: &Config, octo: &Octocrab, number: u64, labels: &[String]) -> Result<(), octocrab::Error> {
+    if dry_run::intercept(config, "label", Some(number), json!({ "labels": labels })) {
+        return Ok(());
+    }
+    octo.issues(&config.owner, &config.repo).add_labels(number, labels).await.map(|_| ())
+}
+
+/// Removes a label from an issue.
+pub async fn remove_label(config: &Config, octo: &Octocrab, number: u64, label: &str) -> Result<(), octocrab::Error> {
+    if dry_run::intercept(config, "unlabel", Some(number), json!({ "label": label })) {
+        return Ok(());
+    }
+    octo.issues(&config.owner, &config.repo).remove_label(number, label).await.map(|_| ())
+}
+
+/// Deletes a comment.
+pub async fn delete_comment(config: &Config, octo: &Octocrab, id: CommentId) -> Result<(), octocrab::Error> {
+    if dry_run::intercept(config, "delete_comment", None, json!({ "comment": id.to_string() })) {
+        return Ok(());
+    }
+    octo.issues(&config.owner, &config.repo).delete_comment(id).await
+}
+
+/// Assigns users to an issue, recording who asked in the audit log.
+pub async fn add_assignees(config: &Config, octo: &Octocrab, actor: &str, number: u64, logins: &[&str]) -> Result<(), octocrab::Error> {
+    if dry_run::intercept(config, "assign", Some(number), json!({ "assignees": logins })) {
+        return Ok(());
+    }
+    octo.issues(&config.owner, &config.repo).add_assignees(number, logins).await?;
+    audit::record(config, actor, "assign", Some(number), json!({ "assignees": logins }));
+    Ok(())
+}
+
+/// Unassigns users from an issue, recording who asked in the audit log.
+pub async fn remove_assignees(config: &Config, octo: &Octocrab, actor: &str, number: u64, logins: &[&str]) -> Result<(), octocrab::Error> {
+    if dry_run::intercept(config, "unassign", Some(number), json!({ "assignees": logins })) {
+        return Ok(());
+    }
+    octo.issues(&config.owner, &config.repo).remove_assignees(number, logins).await?;
+    audit::record(config, actor, "unassign", Some(number), json!({ "assignees": logins }));
+    Ok(())
+}
+
+/// Closes an issue with a `state_reason` of `completed` or `not_planned`.
+pub async fn close_issue(config: &Config, octo: &Octocrab, actor: &str, number: u64, reason: &str) -> Result<(), octocrab::Error> {
+    if dry_run::intercept(config, "close", Some(number), json!({ "state_reason": reason })) {
+        return Ok(());
+    }
+    let route = format!("/repos/{}/{}/issues/{}", config.owner, config.repo, number);
+    octo.patch::<Value, _, _>(route, Some(&json!({ "state": "closed", "state_reason": reason }))).await?;
+    audit::record(config, actor, "close", Some(number), json!({ "state_reason": reason }));
+    Ok(())
+}
+
+/// Renames an issue, keeping the previous title in the audit log.
+pub async fn rename_issue(config: &Config, octo: &Octocrab, actor: &str, number: u64, previous: &str, title: &str) -> Result<(), octocrab::Error> {
+    if dry_run::intercept(config, "retitle", Some(number), json!({ "title": title, "previous": previous })) {
+        return Ok(());
+    }
+    let route = format!("/repos/{}/{}/issues/{}", config.owner, config.repo, number);
+    octo.patch::<Value, _, _>(route, Some(&json!({ "title": title }))).await?;
+    audit::record(config, actor, "retitle", Some(number), json!({ "title": title, "previous": previous }));
+    Ok(())
+}
+
+/// Replaces an issue's body, recording who asked in the audit log.
+pub async fn edit_body(config: &Config, octo: &Octocrab, actor: &str, number: u64, body: &str) -> Result<(), octocrab::Error> {
+    if dry_run::intercept(config, "edit_body", Some(number), json!({ "body": body })) {
+        return Ok(());
+    }
+    let route = format!("/repos/{}/{}/issues/{}", config.owner, config.repo, number);
+    octo.patch::<Value, _, _>(route, Some(&json!({ "body": body }))).await?;
+    audit::record(config, actor, "edit_body", Some(number), json!({ "chars": body.chars().count() }));
+    Ok(())
+}
+
+/// Locks an issue's conversation, recording who asked and why in the audit log.
+pub async fn lock_issue(config: &Config, octo: &Octocrab, actor: &str, number: u64, reason: LockReason) -> Result<(), octocrab::Error> {
+    let reason_name = format!("{:?}", reason);
+    if dry_run::intercept(config, "lock", Some(number), json!({ "lock_reason": reason_name })) {
+        return Ok(());
+    }
+    octo.issues(&config.owner, &config.repo).lock(number, reason).await?;
+    audit::record(config, actor, "lock", Some(number), json!({ "lock_reason": reason_name }));
+    Ok(())
+}
+
+/// Unlocks an issue's conversation, recording who asked in the audit log.
+pub async fn unlock_issue(config: &Config, octo: &Octocrab, actor: &str, number: u64) -> Result<(), octocrab::Error> {
+    if dry_run::intercept(config, "unlock", Some(number), json!({})) {
+        return Ok(());
+    }
+    octo.issues(&config.owner, &config.repo).unlock(number).await?;
+    audit::record(config, actor, "unlock", Some(number), json!({}));
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::config::{self, Config};
+use crate::dry_run;
+use serde_json::{json, Value};
+use std::env;
+
+/// Discord rejects messages longer than this.
+const DISCORD_MAX_CHARS: usize = 2000;
+
+/// A chat destination that bot output can be forwarded to.
+pub enum Sink {
+    Slack { webhook_url: String },
+    Discord { webhook_url: String },
+}
+
+impl Sink {
+    fn payload(&self, text: &str) -> Value {
+        match self {
+            Sink::Slack { .. } => json!({ "text": text }),
+            Sink::Discord { .. } => json!({ "content": text.chars().take(DISCORD_MAX_CHARS).collect::<String>() }),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Sink::Slack { .. } => "Slack",
+            Sink::Discord { .. } => "Discord",
+        }
+    }
+
+    fn webhook_url(&self) -> &str {
+        match self {
+            Sink::Slack { webhook_url } | Sink::Discord { webhook_url } => webhook_url,
+        }
+    }
+
+    async fn send(&self, text: &str) -> anyhow::Result<()> {
+        let resp = reqwest::Client::new().post(self.webhook_url()).json(&self.payload(text)).send().await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("{}: {}", resp.status(), resp.text().await.unwrap_or_default());
+        }
+        Ok(())
+    }
+}
+
+/// The sinks listed in `notify_sinks` (e.g. `slack,discord`), each configured by its
+/// `<name>_webhook_url` variable. Sinks without a URL are skipped.
+pub fn sinks() -> Vec<Sink> {
+    config::list_var("notify_sinks")
+        .iter()
+        .filter_map(|name| {
+            let webhook_url = env::var(format!("{}_webhook_url", name.to_lowercase())).ok().filter(|u| !u.is_empty());
+            match (name.to_lowercase().as_str(), webhook_url) {
+                ("slack", Some(webhook_url)) => Some(Sink::Slack { webhook_url }),
+                ("discord", Some(webhook_url)) => Some(Sink::Discord { webhook_url }),
+                (other, _) => {
+                    log::warn!("Ignoring notify sink '{}' without a known type or webhook URL", other);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Forwards a summary or triage result to every configured chat sink.
+pub async fn forward(config: &Config, headline: &str, url: &str, text: &str) {
+    let message = format!("*[{}/{}] {}*\n{}\n\n{}", config.owner, config.repo, headline, url, text);
+    let sinks = sinks();
+    if sinks.is_empty() || dry_run::intercept(config, "notify", None, json!({ "body": message })) {
+        return;
+    }
+    for sink in sinks {
+        if let Err(error) = sink.send(&message).await {
+            log::error!("Error forwarding to {}: {}", sink.name(), error);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::chunk;
+use crate::config::Config;
+use crate::dry_run;
+use crate::filters;
+use crate::footer;
+use github_flows::octocrab::Octocrab;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::json;
+use std::cell::RefCell;
+
+lazy_static! {
+    static ref MENTION: Regex = Regex::new(r"(^|[^\w`/])@([A-Za-z0-9][\w-]*(?:/[\w-]+)?)").unwrap();
+}
+
+/// Rewrites `@user` and `@org/team` as code spans when `no_mentions` is set, so bot
+/// comments never notify or subscribe anyone.
+pub fn scrub(config: &Config, text: &str) -> String {
+    if !config.no_mentions {
+        return text.to_string();
+    }
+    MENTION.replace_all(text, "$1`$2`").to_string()
+}
+
+/// Scrubs and posts a single reply, split into parts when it is too long for one comment,
+/// unless dry-run mode records it instead.
+pub async fn reply(config: &Config, octo: &Octocrab, issue_number: u64, body: &str) -> anyhow::Result<()> {
+    let parts = chunk::prepare(config, octo, issue_number, &scrub(config, body)).await;
+    post_parts(config, octo, issue_number, &parts).await.map(|_| ())
+}
+
+/// Posts already scrubbed comment bodies in order, unless dry-run mode records them instead.
+/// During quiet hours they are held and posted once the hours are over. Returns the ids of
+/// the comments posted now.
+pub async fn post_parts(config: &Config, octo: &Octocrab, issue_number: u64, parts: &[String]) -> anyhow::Result<Vec<u64>> {
+    if filters::is_quiet(config) && !parts.is_empty() {
+        filters::hold(config, issue_number, parts);
+        return Ok(Vec::new());
+    }
+    let mut posted = Vec::new();
+    for part in parts {
+        let body = footer::marked(part);
+        if dry_run::intercept(config, "comment", Some(issue_number), json!({ "body": body })) {
+            continue;
+        }
+        let comment = octo.issues(&config.owner, &config.repo).create_comment(issue_number, body).await?;
+        posted.push(comment.id.into_inner());
+    }
+    Ok(posted)
+}
+
+/// Bot comments produced while handling one event. With `consolidate_comments` they are
+/// collected and posted by `flush` as a single comment per issue; otherwise each is
+/// posted right away.
+pub struct Outbox {
+    consolidate: bool,
+    pending: RefCell<Vec<(u64, String)>>,
+}
+
+impl Outbox {
+    pub fn new(config: &Config) -> Self {
+        Outbox {
+            consolidate: config.consolidate_comments,
+            pending: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub async fn post(&self, config: &Config, octo: &Octocrab, issue_number: u64, body: &str) -> anyhow::Result<()> {
+        if self.consolidate {
+            self.pending.borrow_mut().push((issue_number, scrub(config, body)));
+            return Ok(());
+        }
+        reply(config, octo, issue_number, body).await
+    }
+
+    /// Posts the collected comments, one per issue, separated by rules.
+    pub async fn flush(&self, config: &Config, octo: &Octocrab) {
+        let pending = self.pending.take();
+        let mut numbers = pending.iter().map(|(n, _)| *n).collect::<Vec<u64>>();
+        numbers.sort();
+        numbers.dedup();
+        for number in numbers {
+            let body = pending
+                .iter()
+                .filter(|(n, _)| *n == number)
+                .map(|(_, b)| b.as_str())
+                .collect::<Vec<&str>>()
+                .join("\n\n---\n\n");
+            let parts = chunk::prepare(config, octo, number, &body).await;
+            if let Err(error) = post_parts(config, octo, number, &parts).await {
+                log::error!("Error posting consolidated comment on #{}: {}", number, error);
+            }
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::config::Config;
+use crate::repo_config::RepoConfig;
+use crate::{dry_run, gist, mirror, notify, permissions, private};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// GitHub rejects check run summaries longer than this.
+const CHECK_RUN_MAX_CHARS: usize = 65535;
+
+/// Where a command's result is delivered, set per command in the repo config's `outputs`.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    /// A comment on the issue, the default.
+    Comment,
+    /// A neutral check run on the pull request head, or on the default branch for issues.
+    CheckRun,
+    /// A secret gist linked from the issue.
+    Gist,
+    /// The chat sinks in `notify_sinks`.
+    #[serde(alias = "slack", alias = "discord")]
+    Chat,
+    /// The team-only mirror issue.
+    Mirror,
+    /// The maintainer-only tracking issue in `private.tracking_repo`.
+    Tracking,
+    /// The private discussion of `private.team`.
+    Team,
+}
+
+/// The channel configured for `command`, e.g. `summary`, `triage` or a `@flows_bot`
+/// subcommand name.
+pub fn channel(repo_config: &RepoConfig, command: &str) -> Channel {
+    repo_config.outputs.get(command).copied().unwrap_or(Channel::Comment)
+}
+
+async fn check_run(config: &Config, octo: &Octocrab, issue: &Issue, title: &str, body: &str) -> anyhow::Result<String> {
+    let head_sha = if issue.pull_request.is_some() {
+        let route = format!("/repos/{}/{}/pulls/{}", config.owner, config.repo, issue.number);
+        let pull = octo.get::<Value, _, ()>(route, None).await?;
+        pull["head"]["sha"].as_str().map(|s| s.to_string())
+    } else {
+        let default_branch = octo
+            .repos(&config.owner, &config.repo)
+            .get()
+            .await?
+            .default_branch
+            .unwrap_or("main".to_string());
+        let route = format!("/repos/{}/{}/commits/{}", config.owner, config.repo, default_branch);
+        let commit = octo.get::<Value, _, ()>(route, None).await?;
+        commit["sha"].as_str().map(|s| s.to_string())
+    };
+    let head_sha = head_sha.ok_or(anyhow::anyhow!("could not resolve a commit for the check run"))?;
+
+    let route = format!("/repos/{}/{}/check-runs", config.owner, config.repo);
+    let run: Result<Value, _> = octo
+        .post(
+            route,
+            Some(&json!({
+                "name": format!("flows.network: {}", title),
+                "head_sha": head_sha,
+                "status": "completed",
+                "conclusion": "neutral",
+                "output": {
+                    "title": title,
+                    "summary": body.chars().take(CHECK_RUN_MAX_CHARS).collect::<String>(),
+                },
+            })),
+        )
+        .await;
+    let run = run?;
+    run["html_url"]
+        .as_str()
+        .map(|u| u.to_string())
+        .ok_or_else(|| anyhow::anyhow!("Check run response without html_url"))
+}
+
+/// Delivers a command's result through its configured channel. Returns what should still be
+/// posted on the issue: the result itself for `comment`, a link for `gist` and `check_run`,
+/// and nothing for the private channels. A channel that can't be used falls back to a comment.
+pub async fn route(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue, command: &str, body: &str) -> Option<String> {
+    let title = format!("{} for #{}", command, issue.number);
+    match channel(repo_config, command) {
+        Channel::Comment => Some(body.to_string()),
+        Channel::CheckRun => {
+            if dry_run::intercept(config, "check_run", Some(issue.number), json!({ "title": title, "body": body })) {
+                return None;
+            }
+            match check_run(config, octo, issue, &title, body).await {
+                Ok(url) => Some(format!("The `{}` result was posted as a check run: {}", command, url)),
+                Err(error) => {
+                    permissions::check(config, octo, "create check runs", permissions::CHECKS_WRITE, &error).await;
+                    Some(body.to_string())
+                }
+            }
+        }
+        Channel::Gist => {
+            let filename = format!("{}-{}-issue-{}-{}.md", config.owner, config.repo, issue.number, command);
+            match gist::create(config, octo, &format!("{}: {}", title, issue.html_url), &[(filename, body.to_string())]).await {
+                Ok(url) => Some(format!("📎 The `{}` result is in a gist: {}", command, url)),
+                Err(error) => {
+                    log::warn!("Could not create gist for `{}`, posting a comment: {}", command, error);
+                    Some(body.to_string())
+                }
+            }
+        }
+        Channel::Chat => {
+            if notify::sinks().is_empty() {
+                log::warn!("`{}` is routed to chat but no notify_sinks are configured, posting a comment", command);
+                return Some(body.to_string());
+            }
+            notify::forward(config, &format!("{}: {}", title, issue.title), issue.html_url.as_str(), body).await;
+            None
+        }
+        Channel::Tracking | Channel::Team => {
+            if private::deliver(config, repo_config, octo, issue.number, command, body).await {
+                None
+            } else {
+                Some(body.to_string())
+            }
+        }
+        Channel::Mirror => match mirror::post_private(config, &repo_config.mirror, octo, issue, body).await {
+            Ok(true) => None,
+            Ok(false) => {
+                log::warn!("`{}` is routed to the mirror but no mirror repo is configured, posting a comment", command);
+                Some(body.to_string())
+            }
+            Err(error) => {
+                permissions::check(config, octo, "post to the mirror", permissions::ISSUES_WRITE, &error).await;
+                Some(body.to_string())
+            }
+        },
+    }
+}
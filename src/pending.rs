@@ -0,0 +1,74 @@
+use crate::config::Config;
+use crate::store;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::env;
+
+/// Where a proposed action stands. Proposals expire with their store key, so an unanswered
+/// one simply disappears.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum State {
+    Proposed,
+    Confirmed,
+    Dismissed,
+}
+
+/// An action the bot proposed on an issue and a maintainer has to confirm, e.g. locking a
+/// heated thread.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PendingAction {
+    /// What to do, e.g. `lock`.
+    pub action: String,
+    /// Argument of the action, e.g. the lock reason.
+    pub reason: String,
+    /// The detector that proposed it, e.g. `heat`.
+    pub proposed_by: String,
+    pub proposed_at: i64,
+    pub state: State,
+    pub decided_by: Option<String>,
+}
+
+fn key(number: u64) -> String {
+    format!("pending:{}", number)
+}
+
+fn ttl_secs() -> i64 {
+    env::var("pending_action_ttl_secs").ok().and_then(|n| n.parse::<i64>().ok()).unwrap_or(3 * 24 * 3600)
+}
+
+pub fn get(config: &Config, number: u64) -> Option<PendingAction> {
+    store::get(config, &key(number)).and_then(|v| serde_json::from_value::<PendingAction>(v).ok())
+}
+
+/// Records a proposal, unless one is already waiting on the issue. Returns whether it was
+/// recorded.
+pub fn propose(config: &Config, number: u64, action: &str, reason: &str, proposed_by: &str) -> bool {
+    if get(config, number).map_or(false, |p| p.state == State::Proposed) {
+        return false;
+    }
+    let pending = PendingAction {
+        action: action.to_string(),
+        reason: reason.to_string(),
+        proposed_by: proposed_by.to_string(),
+        proposed_at: chrono::Utc::now().timestamp(),
+        state: State::Proposed,
+        decided_by: None,
+    };
+    store::set(config, &key(number), json!(pending), Some(ttl_secs()));
+    true
+}
+
+/// Moves the issue's open proposal to `Confirmed` or `Dismissed` and returns it. Fails
+/// when nothing is waiting for a decision.
+pub fn decide(config: &Config, number: u64, state: State, by: &str) -> Result<PendingAction, String> {
+    let mut pending = match get(config, number) {
+        Some(p) if p.state == State::Proposed => p,
+        Some(p) => return Err(format!("the last proposal on this issue was already {:?}", p.state).to_lowercase()),
+        None => return Err("there is no pending proposal on this issue".to_string()),
+    };
+    pending.state = state;
+    pending.decided_by = Some(by.to_string());
+    store::set(config, &key(number), json!(pending), Some(ttl_secs()));
+    Ok(pending)
+}
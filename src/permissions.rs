@@ -0,0 +1,171 @@
+use crate::config::Config;
+use crate::dry_run;
+use crate::footer;
+use crate::store;
+use github_flows::octocrab::{self, Octocrab};
+use serde_json::json;
+use std::any::Any;
+use std::fmt::Display;
+
+const GUIDANCE_KEY: &str = "permissions:guidance_issue";
+const MISSING_KEY: &str = "permissions:missing";
+
+/// Permissions the GitHub App installation needs for each bot feature.
+pub const ISSUES_READ: &str = "Issues: Read";
+pub const ISSUES_WRITE: &str = "Issues: Read and write";
+pub const CONTENTS_READ: &str = "Contents: Read";
+pub const CONTENTS_WRITE: &str = "Contents: Read and write";
+pub const HOOKS_WRITE: &str = "Webhooks: Read and write";
+pub const CHECKS_WRITE: &str = "Checks: Read and write";
+pub const PROJECTS_WRITE: &str = "Projects: Read and write";
+pub const ACTIONS_READ: &str = "Actions: Read";
+
+/// HTTP status of a GitHub API error, when `error` is an octocrab error (or wraps one).
+pub fn status_code<E: Display + 'static>(error: &E) -> Option<u16> {
+    let any = error as &dyn Any;
+    let octo_error = any
+        .downcast_ref::<octocrab::Error>()
+        .or_else(|| any.downcast_ref::<anyhow::Error>().and_then(|e| e.downcast_ref::<octocrab::Error>()))?;
+    match octo_error {
+        octocrab::Error::GitHub { source, .. } => Some(source.status_code.as_u16()),
+        _ => None,
+    }
+}
+
+/// Whether a GitHub API error was caused by a missing installation permission rather than
+/// a transient failure or a missing resource. Only a 403 that is not a rate limit counts.
+pub fn is_permission_error<E: Display + 'static>(error: &E) -> bool {
+    status_code(error) == Some(403) && !error.to_string().to_lowercase().contains("rate limit")
+}
+
+/// Forgets that `permission` was missing once a call needing it succeeds again.
+pub fn clear_missing(config: &Config, permission: &str) {
+    let Some(mut missing) = store::get(config, MISSING_KEY).and_then(|v| serde_json::from_value::<Vec<String>>(v).ok()) else {
+        return;
+    };
+    if !missing.iter().any(|p| p == permission) {
+        return;
+    }
+    missing.retain(|p| p != permission);
+    if missing.is_empty() {
+        store::del(config, MISSING_KEY);
+    } else {
+        store::set(config, MISSING_KEY, json!(missing), None);
+    }
+    log::info!("Permission '{}' is granted again on {}/{}", permission, config.owner, config.repo);
+}
+
+/// Logs a failed GitHub call and, if it was caused by a missing permission, records the
+/// permission and surfaces setup guidance once instead of failing silently on every event.
+pub async fn check<E: Display + 'static>(config: &Config, octo: &Octocrab, operation: &str, permission: &str, error: &E) {
+    log::error!("Error {}: {}", operation, error);
+    if is_permission_error(error) {
+        record_missing(config, octo, operation, permission).await;
+    }
+}
+
+/// Adds `permission` to the known missing permissions; `None` if it was already known.
+fn with_missing(mut missing: Vec<String>, permission: &str) -> Option<Vec<String>> {
+    if missing.iter().any(|p| p == permission) {
+        return None;
+    }
+    missing.push(permission.to_string());
+    Some(missing)
+}
+
+/// Records that `permission` is missing and surfaces setup guidance the first time, for
+/// callers that already know the permission is lacking without a failed call to inspect.
+pub async fn record_missing(config: &Config, octo: &Octocrab, operation: &str, permission: &str) {
+    let known = store::get(config, MISSING_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+        .unwrap_or_default();
+    let Some(missing) = with_missing(known, permission) else {
+        log::warn!("Still missing '{}' on {}/{}; guidance already reported", permission, config.owner, config.repo);
+        return;
+    };
+    store::set(config, MISSING_KEY, json!(missing), None);
+
+    let guidance = format!(
+        "The issue bot could not {} in {}/{} because the flows.network GitHub App installation lacks permissions.\n\n\
+         Missing permissions detected so far:\n\n{}\n\n\
+         Grant them under **Settings → GitHub Apps → flows.network → Configure**, then accept the updated permissions request.",
+        operation,
+        config.owner,
+        config.repo,
+        missing.iter().map(|p| format!("* {}", p)).collect::<Vec<String>>().join("\n")
+    );
+
+    if dry_run::intercept(config, "report_permissions", None, json!({ "body": guidance })) {
+        return;
+    }
+    let issues = octo.issues(&config.owner, &config.repo);
+    let posted = match store::get(config, GUIDANCE_KEY).and_then(|v| v.as_u64()) {
+        Some(number) => issues.create_comment(number, footer::marked(&guidance)).await.map(|_| number),
+        None => issues
+            .create("Issue bot setup: missing GitHub App permissions")
+            .body(&guidance)
+            .send()
+            .await
+            .map(|issue| issue.number),
+    };
+    match posted {
+        Ok(number) => {
+            store::set(config, GUIDANCE_KEY, json!(number), None);
+            log::info!("Reported missing permission '{}' in issue #{}", permission, number);
+        }
+        Err(_) => log::error!("Setup guidance (could not be posted):\n{}", guidance),
+    }
+}
+
+/// Whether `login` has write access or better to the repository.
+pub async fn is_maintainer(config: &Config, octo: &Octocrab, login: &str) -> bool {
+    let route = format!("/repos/{}/{}/collaborators/{}/permission", config.owner, config.repo, login);
+    match octo.get::<serde_json::Value, _, ()>(route, None).await {
+        Ok(resp) => matches!(resp["permission"].as_str(), Some("admin" | "maintain" | "write")),
+        Err(error) => {
+            log::debug!("Could not get permission of {}: {}", login, error);
+            false
+        }
+    }
+}
+
+/// Whether `login` has any access to the repository, which GitHub requires of assignees.
+pub async fn is_collaborator(config: &Config, octo: &Octocrab, login: &str) -> bool {
+    let route = format!("/repos/{}/{}/collaborators/{}/permission", config.owner, config.repo, login);
+    match octo.get::<serde_json::Value, _, ()>(route, None).await {
+        Ok(resp) => matches!(resp["permission"].as_str(), Some(p) if p != "none"),
+        Err(error) => {
+            log::debug!("Could not get permission of {}: {}", login, error);
+            false
+        }
+    }
+}
+
+/// Whether `login` is a repository admin.
+pub async fn is_admin(config: &Config, octo: &Octocrab, login: &str) -> bool {
+    let route = format!("/repos/{}/{}/collaborators/{}/permission", config.owner, config.repo, login);
+    match octo.get::<serde_json::Value, _, ()>(route, None).await {
+        Ok(resp) => resp["permission"].as_str() == Some("admin"),
+        Err(error) => {
+            log::debug!("Could not get permission of {}: {}", login, error);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_missing_records_a_new_permission() {
+        let missing = with_missing(vec![ISSUES_WRITE.to_string()], CHECKS_WRITE);
+        assert_eq!(missing, Some(vec![ISSUES_WRITE.to_string(), CHECKS_WRITE.to_string()]));
+        assert_eq!(with_missing(Vec::new(), HOOKS_WRITE), Some(vec![HOOKS_WRITE.to_string()]));
+    }
+
+    #[test]
+    fn with_missing_skips_a_known_permission() {
+        assert_eq!(with_missing(vec![CHECKS_WRITE.to_string()], CHECKS_WRITE), None);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::llm::{self, LlmConfig};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+
+lazy_static! {
+    static ref FIRST_PERSON: Regex = Regex::new(r"\b(I|I'm|I've|I'd|I'll|me|my|mine)\b").unwrap();
+    static ref CONTRACTION: Regex = Regex::new(r"(?i)\b\w+'(s|re|ve|ll|d|t|m)\b").unwrap();
+    static ref EMOJI: Regex = Regex::new(r"[\x{1F300}-\x{1FAFF}\x{2600}-\x{27BF}]").unwrap();
+}
+
+/// Word budget for the terse tone.
+const TERSE_MAX_WORDS: usize = 80;
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tone {
+    Formal,
+    Friendly,
+    Terse,
+}
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Voice {
+    FirstPerson,
+    Neutral,
+}
+
+/// How the bot sounds in a repository's threads. Unset fields leave the prompts as they are.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Persona {
+    pub tone: Option<Tone>,
+    pub voice: Option<Voice>,
+}
+
+impl Persona {
+    fn instruction(&self) -> String {
+        let tone = match self.tone {
+            Some(Tone::Formal) => "Use a formal, professional register without contractions, exclamations or emoji.",
+            Some(Tone::Friendly) => "Use a warm, friendly and encouraging tone.",
+            Some(Tone::Terse) => "Be terse: short sentences, no pleasantries, under 80 words.",
+            None => "",
+        };
+        let voice = match self.voice {
+            Some(Voice::FirstPerson) => "Speak in the first person as the project's bot.",
+            Some(Voice::Neutral) => "Write in a neutral, impersonal voice; never refer to yourself.",
+            None => "",
+        };
+        format!("{} {}", tone, voice).trim().to_string()
+    }
+
+    /// Appends the persona's style instructions to a system prompt for user-facing prose.
+    pub fn apply(&self, sys_prompt: &str) -> String {
+        let instruction = self.instruction();
+        if instruction.is_empty() {
+            return sys_prompt.to_string();
+        }
+        format!("{} {}", sys_prompt, instruction)
+    }
+
+    /// Returns the ways `text` breaks the persona.
+    pub fn violations(&self, text: &str) -> Vec<&'static str> {
+        let mut found = Vec::new();
+        if self.voice == Some(Voice::Neutral) && FIRST_PERSON.is_match(text) {
+            found.push("uses the first person");
+        }
+        if self.tone == Some(Tone::Formal) && (CONTRACTION.is_match(text) || EMOJI.is_match(text) || text.contains('!')) {
+            found.push("uses contractions, exclamations or emoji");
+        }
+        if self.tone == Some(Tone::Terse) && text.split_whitespace().count() > TERSE_MAX_WORDS {
+            found.push("is longer than the terse word budget");
+        }
+        found
+    }
+}
+
+/// Output filter: when a reply breaks the persona, asks the model once to rewrite it and
+/// keeps whichever version is usable.
+pub async fn enforce(cfg: &LlmConfig, conversation_id: &str, reply: String, max_tokens: u16) -> String {
+    let violations = cfg.persona.violations(&reply);
+    if violations.is_empty() {
+        return reply;
+    }
+
+    log::debug!("Reply {} {}; rewriting", conversation_id, violations.join(", "));
+    let sys_prompt = cfg.persona.apply("You copy-edit GitHub comments without changing their meaning or Markdown structure.");
+    let usr_prompt = format!(
+        "Rewrite this comment so that it no longer {}. Reply with the rewritten comment only.\n\n{}",
+        violations.join(" and no longer "),
+        reply
+    );
+    match llm::chat(cfg, &format!("{}#persona", conversation_id), &sys_prompt, &usr_prompt, max_tokens).await {
+        Ok(rewritten) => rewritten,
+        Err(error) => {
+            log::warn!("Could not rewrite reply for persona: {}", error);
+            reply
+        }
+    }
+}
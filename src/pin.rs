@@ -0,0 +1,116 @@
+use crate::config::Config;
+use crate::journal;
+use crate::mutations;
+use crate::permissions;
+use crate::repo_config::RepoConfig;
+use crate::summary;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+
+const START: &str = "<!-- flows-summary:start -->";
+const END: &str = "<!-- flows-summary:end -->";
+
+/// Where the pinned block sits in `body`, including its trailing blank lines.
+fn block_range(body: &str) -> Option<(usize, usize)> {
+    let start = body.find(START)?;
+    let end = start + body[start..].find(END)? + END.len();
+    let trailing = body[end..].len() - body[end..].trim_start_matches(['\r', '\n']).len();
+    Some((start, end + trailing))
+}
+
+/// The issue body without the pinned summary, so it doesn't feed back into new summaries.
+pub fn strip(body: &str) -> String {
+    match block_range(body) {
+        Some((start, end)) => format!("{}{}", &body[..start], &body[end..]),
+        None => body.to_string(),
+    }
+}
+
+/// Whether the issue body carries a pinned summary.
+pub fn is_pinned(issue: &Issue) -> bool {
+    issue.body.as_deref().map_or(false, |b| b.contains(START))
+}
+
+fn render(summary: &str) -> String {
+    format!(
+        "{}\n### 🤖 Summary\n\n{}\n\n<sub>Kept up to date by the bot on every summary request ({}). Edits inside this section are overwritten.</sub>\n\n---\n{}\n\n",
+        START,
+        summary.trim(),
+        chrono::Utc::now().format("%Y-%m-%d"),
+        END
+    )
+}
+
+/// Writes the summary block at the top of the issue body, replacing an earlier one. The body
+/// is re-read first so edits made since the event was delivered are kept.
+async fn write(config: &Config, octo: &Octocrab, actor: &str, number: u64, summary: &str) -> anyhow::Result<()> {
+    let current = octo.issues(&config.owner, &config.repo).get(number).await?;
+    let body = current.body.unwrap_or_default();
+    let updated = format!("{}{}", render(summary), strip(&body));
+    mutations::edit_body(config, octo, actor, number, &updated).await?;
+    Ok(())
+}
+
+/// Refreshes an existing pinned block with a summary generated for another trigger.
+pub async fn refresh(config: &Config, octo: &Octocrab, issue: &Issue, summary: &str) {
+    if !is_pinned(issue) {
+        return;
+    }
+    if let Err(error) = write(config, octo, "pin-summary", issue.number, summary).await {
+        permissions::check(config, octo, "refresh pinned summaries", permissions::ISSUES_WRITE, &error).await;
+    }
+}
+
+/// `@flows_bot pin-summary [remove]`: summarizes the thread into a section at the top of the
+/// issue body, or removes that section.
+pub async fn run(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue, requester: &str, args: &str) -> anyhow::Result<String> {
+    if args.split_whitespace().next() == Some("remove") {
+        let current = octo.issues(&config.owner, &config.repo).get(issue.number).await?;
+        let body = current.body.unwrap_or_default();
+        if block_range(&body).is_none() {
+            return Ok(format!("#{} has no pinned summary.", issue.number));
+        }
+        if let Err(error) = mutations::edit_body(config, octo, requester, issue.number, &strip(&body)).await {
+            permissions::check(config, octo, "edit issue bodies", permissions::ISSUES_WRITE, &error).await;
+            return Err(error.into());
+        }
+        return Ok("📌 Removed the pinned summary from the issue body.".to_string());
+    }
+
+    let context = match journal::context(config, octo, issue).await {
+        Some(context) => context,
+        None => summary::issue_context(octo, &config.owner, &config.repo, issue).await?,
+    };
+    let detail = repo_config.summary_detail.unwrap_or(config.summary_detail);
+    let summary = summary::summarize(&config.llm, issue, &context, detail)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    if let Err(error) = write(config, octo, requester, issue.number, &summary).await {
+        permissions::check(config, octo, "edit issue bodies", permissions::ISSUES_WRITE, &error).await;
+        return Err(error);
+    }
+    Ok("📌 Pinned a summary at the top of the issue body. It is refreshed whenever a new summary is requested.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_range_needs_both_markers() {
+        assert_eq!(block_range("No summary here."), None);
+        assert_eq!(block_range(&format!("{}\nunterminated", START)), None);
+    }
+
+    #[test]
+    fn strip_removes_the_pinned_block_and_its_blank_lines() {
+        let body = "Steps to reproduce:\n1. Run it";
+        assert_eq!(strip(&format!("{}{}", render("It crashes."), body)), body);
+        assert_eq!(strip(&format!("Intro\n{}{}", render("It crashes."), body)), format!("Intro\n{}", body));
+        assert_eq!(strip(body), body);
+    }
+
+    #[test]
+    fn pinned_block_does_not_trigger_the_bot() {
+        assert!(!render("It crashes.").contains("@flows_"));
+    }
+}
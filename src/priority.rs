@@ -0,0 +1,63 @@
+use crate::config::Config;
+use crate::footer;
+use crate::images;
+use crate::llm::LlmConfig;
+use crate::mutations;
+use crate::notify;
+use crate::outbox::Outbox;
+use crate::output;
+use crate::permissions;
+use crate::repo_config::RepoConfig;
+use crate::summary;
+use crate::workload;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+
+pub fn is_priority_reporter(config: &Config, login: &str) -> bool {
+    config
+        .priority_reporters
+        .iter()
+        .any(|r| r.eq_ignore_ascii_case(login))
+}
+
+/// Labels an issue and posts an immediate triage summary, rather than waiting for someone
+/// to invoke the trigger phrase. `reason` is shown in the comment heading.
+pub async fn expedite(config: &Config, repo_config: &RepoConfig, llm: &LlmConfig, octo: &Octocrab, outbox: &Outbox, issue: &Issue, label: &str, reason: &str) {
+    if let Err(error) = mutations::add_labels(config, octo, issue.number, &[label.to_string()]).await {
+        permissions::check(config, octo, "label expedited issues", permissions::ISSUES_WRITE, &error).await;
+    }
+
+    let mut context = match summary::issue_context(octo, &config.owner, &config.repo, issue).await {
+        Ok(context) => context,
+        Err(error) => {
+            log::error!("Error getting comments from issue: {}", error);
+            return;
+        }
+    };
+    images::annotate(llm, &mut context).await;
+
+    let analysis = match summary::summarize(llm, issue, &context, config.summary_detail).await {
+        Ok(analysis) => analysis,
+        Err(error) => {
+            log::error!("Error generating expedited triage #{}: {}", issue.number, error);
+            return;
+        }
+    };
+
+    let resp = format!(
+        "**Expedited triage** ({})\n\n{}{}{}",
+        reason,
+        analysis,
+        workload::section(config, repo_config, octo, issue).await,
+        footer::render(config, None)
+    );
+    let Some(resp) = output::route(config, repo_config, octo, issue, "triage", &resp).await else {
+        return;
+    };
+    if let Err(error) = outbox.post(config, octo, issue.number, &resp).await {
+        log::error!("Error posting expedited triage: {}", error);
+    } else {
+        log::info!("Posted expedited triage for issue #{}", issue.number);
+        let headline = format!("Expedited triage of #{} ({}): {}", issue.number, reason, issue.title);
+        notify::forward(config, &headline, issue.html_url.as_str(), &analysis).await;
+    }
+}
@@ -0,0 +1,125 @@
+use crate::config::Config;
+use crate::dry_run;
+use crate::footer;
+use crate::output::{self, Channel};
+use crate::permissions;
+use crate::repo_config::{PrivateConfig, RepoConfig};
+use crate::store;
+use github_flows::octocrab::Octocrab;
+use serde_json::{json, Value};
+
+const TRACKING_KEY: &str = "private:tracking";
+const DISCUSSION_KEY: &str = "private:discussion";
+
+fn heading(config: &Config, issue_number: u64, command: &str) -> String {
+    format!(
+        "**{}** on https://github.com/{}/{}/issues/{}",
+        command, config.owner, config.repo, issue_number
+    )
+}
+
+/// The maintainer-only tracking issue: the configured one, or one opened on first use and
+/// remembered in the store.
+async fn tracking_issue(config: &Config, private: &PrivateConfig, octo: &Octocrab, owner: &str, repo: &str) -> anyhow::Result<u64> {
+    if let Some(number) = private.tracking_issue {
+        return Ok(number);
+    }
+    if let Some(number) = store::get(config, TRACKING_KEY).and_then(|v| v.as_u64()) {
+        return Ok(number);
+    }
+
+    let body = format!(
+        "Maintainer-only reports about {}/{} that the bot keeps out of the public threads: sentiment flags, suspected spam, security heuristics and any command routed to `tracking`.",
+        config.owner, config.repo
+    );
+    let created = octo
+        .issues(owner, repo)
+        .create(format!("Bot reports for {}/{}", config.owner, config.repo))
+        .body(body)
+        .send()
+        .await?;
+    store::set(config, TRACKING_KEY, json!(created.number), None);
+    log::info!("Opened tracking issue {}/{}#{} for {}/{}", owner, repo, created.number, config.owner, config.repo);
+    Ok(created.number)
+}
+
+/// Posts to the tracking issue in `private.tracking_repo`. Returns false when none is configured.
+pub async fn post_tracking(config: &Config, private: &PrivateConfig, octo: &Octocrab, issue_number: u64, command: &str, body: &str) -> anyhow::Result<bool> {
+    let Some((owner, repo)) = private.tracking_repo.as_deref().and_then(|r| r.split_once('/')) else {
+        return Ok(false);
+    };
+    let body = format!("{}\n\n{}", heading(config, issue_number, command), body);
+    if dry_run::intercept(config, "tracking_comment", Some(issue_number), json!({ "repo": format!("{}/{}", owner, repo), "body": body })) {
+        return Ok(true);
+    }
+    let number = tracking_issue(config, private, octo, owner, repo).await?;
+    octo.issues(owner, repo).create_comment(number, footer::marked(&body)).await?;
+    Ok(true)
+}
+
+/// The private team discussion reports go to: the configured one, or one started on first
+/// use and remembered in the store.
+async fn team_discussion(config: &Config, private: &PrivateConfig, octo: &Octocrab, org: &str, team: &str) -> anyhow::Result<u64> {
+    if let Some(number) = private.discussion {
+        return Ok(number);
+    }
+    if let Some(number) = store::get(config, DISCUSSION_KEY).and_then(|v| v.as_u64()) {
+        return Ok(number);
+    }
+
+    let route = format!("/orgs/{}/teams/{}/discussions", org, team);
+    let created: Value = octo
+        .post(
+            route,
+            Some(&json!({
+                "title": format!("Bot reports for {}/{}", config.owner, config.repo),
+                "body": "Maintainer-only reports the bot keeps out of the public threads.",
+                "private": true,
+            })),
+        )
+        .await?;
+    let number = created["number"]
+        .as_u64()
+        .ok_or_else(|| anyhow::anyhow!("Team discussion response without number"))?;
+    store::set(config, DISCUSSION_KEY, json!(number), None);
+    Ok(number)
+}
+
+/// Posts to the private discussion of `private.team` (`org/team-slug`). Returns false when
+/// no team is configured.
+pub async fn post_team(config: &Config, private: &PrivateConfig, octo: &Octocrab, issue_number: u64, command: &str, body: &str) -> anyhow::Result<bool> {
+    let Some((org, team)) = private.team.as_deref().map(|t| t.trim_start_matches('@')).and_then(|t| t.split_once('/')) else {
+        return Ok(false);
+    };
+    let body = format!("{}\n\n{}", heading(config, issue_number, command), body);
+    if dry_run::intercept(config, "team_discussion_comment", Some(issue_number), json!({ "team": format!("{}/{}", org, team), "body": body })) {
+        return Ok(true);
+    }
+    let number = team_discussion(config, private, octo, org, team).await?;
+    let route = format!("/orgs/{}/teams/{}/discussions/{}/comments", org, team, number);
+    let _: Value = octo.post(route, Some(&json!({ "body": footer::marked(&body) }))).await?;
+    Ok(true)
+}
+
+/// Delivers `body` privately when `command` is routed to `tracking` or `team`. Returns false
+/// when it belongs in the public thread: another channel is configured, or the private
+/// destination is missing or failed.
+pub async fn deliver(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue_number: u64, command: &str, body: &str) -> bool {
+    let private = &repo_config.private;
+    let posted = match output::channel(repo_config, command) {
+        Channel::Tracking => post_tracking(config, private, octo, issue_number, command, body).await,
+        Channel::Team => post_team(config, private, octo, issue_number, command, body).await,
+        _ => return false,
+    };
+    match posted {
+        Ok(true) => true,
+        Ok(false) => {
+            log::warn!("`{}` is routed to a private channel that isn't configured, posting publicly", command);
+            false
+        }
+        Err(error) => {
+            permissions::check(config, octo, "post private reports", permissions::ISSUES_WRITE, &error).await;
+            false
+        }
+    }
+}
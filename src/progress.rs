@@ -0,0 +1,74 @@
+use crate::chunk;
+use crate::config::Config;
+use crate::dry_run;
+use crate::filters;
+use crate::footer;
+use crate::outbox;
+use crate::permissions;
+use github_flows::octocrab::{models::CommentId, Octocrab};
+use serde_json::json;
+
+/// A "working on it" comment posted before a long LLM run and replaced by its result.
+pub struct Placeholder {
+    issue_number: u64,
+    id: Option<CommentId>,
+}
+
+impl Placeholder {
+    /// Posts `text` right away. If that fails, or during quiet hours, the final result is
+    /// posted as a new comment.
+    pub async fn post(config: &Config, octo: &Octocrab, issue_number: u64, text: &str) -> Self {
+        if config.dry_run || filters::is_quiet(config) {
+            return Placeholder { issue_number, id: None };
+        }
+        let id = match octo.issues(&config.owner, &config.repo).create_comment(issue_number, footer::marked(text)).await {
+            Ok(comment) => Some(comment.id),
+            Err(error) => {
+                log::warn!("Could not post placeholder on #{}: {}", issue_number, error);
+                None
+            }
+        };
+        Placeholder { issue_number, id }
+    }
+
+    /// The placeholder comment, which becomes the result once `finish` edits it.
+    pub fn id(&self) -> Option<CommentId> {
+        self.id
+    }
+
+    /// Replaces the placeholder with the final result. If the placeholder can't be edited
+    /// the result is posted as a new comment and, with `delete_superseded`, the placeholder
+    /// is removed. Results too long for one comment continue in follow-up comments.
+    pub async fn finish(self, config: &Config, octo: &Octocrab, body: &str) -> anyhow::Result<()> {
+        let issue_number = self.issue_number;
+        let parts = chunk::prepare(config, octo, issue_number, &outbox::scrub(config, body)).await;
+        let Some((first, rest)) = parts.split_first() else {
+            return Ok(());
+        };
+        let body = footer::marked(first);
+        if dry_run::intercept(config, "comment", Some(issue_number), json!({ "body": body })) {
+            return outbox::post_parts(config, octo, issue_number, rest).await.map(|_| ());
+        }
+        if let Some(id) = self.id {
+            match octo.issues(&config.owner, &config.repo).update_comment(id, &body).await {
+                Ok(_) => return outbox::post_parts(config, octo, issue_number, rest).await.map(|_| ()),
+                Err(error) => log::warn!("Could not update placeholder {}, posting a new comment: {}", id, error),
+            }
+        }
+        outbox::post_parts(config, octo, issue_number, &parts).await?;
+        if config.delete_superseded {
+            self.discard(config, octo).await;
+        }
+        Ok(())
+    }
+
+    /// Removes the placeholder when the run failed and the failure is reported elsewhere.
+    pub async fn discard(self, config: &Config, octo: &Octocrab) {
+        let Some(id) = self.id else {
+            return;
+        };
+        if let Err(error) = octo.issues(&config.owner, &config.repo).delete_comment(id).await {
+            permissions::check(config, octo, "delete placeholder comments", permissions::ISSUES_WRITE, &error).await;
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::author::Author;
+use crate::config::Config;
+use crate::llm;
+use crate::mutations;
+use crate::outbox::Outbox;
+use crate::persona;
+use crate::permissions;
+use crate::repo_config::{RepoConfig, RequiredInfo};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use regex::RegexBuilder;
+
+/// Marks the missing-info request so it can be removed once the information arrives.
+const REQUEST_MARKER: &str = "<!-- flows-needs-info -->";
+
+/// Returns the required items that none of their patterns match in `text`.
+pub fn missing_info<'a>(repo_config: &'a RepoConfig, text: &str) -> Vec<&'a RequiredInfo> {
+    repo_config
+        .required_info
+        .iter()
+        .filter(|info| {
+            !info.patterns.iter().any(|p| match RegexBuilder::new(p).case_insensitive(true).build() {
+                Ok(re) => re.is_match(text),
+                Err(error) => {
+                    log::error!("Invalid pattern for required info '{}': {}", info.name, error);
+                    true
+                }
+            })
+        })
+        .collect()
+}
+
+fn missing_list(missing: &[&RequiredInfo]) -> String {
+    missing
+        .iter()
+        .map(|m| {
+            if m.hint.is_empty() {
+                format!("* {}", m.name)
+            } else {
+                format!("* {} ({})", m.name, m.hint)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Checks a newly opened issue for the required information and, if anything is missing,
+/// asks the reporter for it and applies the needs-info label.
+pub async fn on_opened(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue, outbox: &Outbox) {
+    let text = format!("{}\n{}", issue.title, issue.body.clone().unwrap_or_default());
+    let missing = missing_info(repo_config, &text);
+    if missing.is_empty() {
+        return;
+    }
+
+    log::info!("Issue #{} is missing {} required item(s)", issue.number, missing.len());
+    let list = missing_list(&missing);
+
+    let sys_prompt = config
+        .llm
+        .persona
+        .apply("You are a friendly maintainer bot for an open source project. Write short, welcoming GitHub comments in Markdown.");
+    let usr_prompt = format!(
+        "User '{}' opened an issue titled '{}'. The report is missing the following information:\n{}\nWrite a brief, friendly comment thanking them and asking for exactly these missing pieces as a bullet list. Do not ask for anything else.",
+        Author::from_login(&issue.user.login),
+        issue.title,
+        list
+    );
+    let conversation_id = format!("quality_{}", issue.html_url);
+    let request = match llm::chat(&config.llm.profile("quality"), &conversation_id, &sys_prompt, &usr_prompt, 256).await {
+        Ok(text) => persona::enforce(&config.llm, &conversation_id, text, 256).await,
+        Err(error) => {
+            log::error!("Error generating missing-info request for #{}: {}", issue.number, error);
+            format!(
+                "Thanks for the report, {}! To help us investigate, could you please add:\n\n{}",
+                Author::from_login(&issue.user.login).mention(),
+                list
+            )
+        }
+    };
+
+    let request = format!("{}\n{}", REQUEST_MARKER, request);
+    if let Err(error) = outbox.post(config, octo, issue.number, &request).await {
+        log::error!("Error posting missing-info request: {}", error);
+    }
+    if let Err(error) = mutations::add_labels(config, octo, issue.number, &[repo_config.needs_info_label.clone()]).await {
+        permissions::check(config, octo, "label incomplete issues", permissions::ISSUES_WRITE, &error).await;
+    }
+}
+
+/// Re-checks a needs-info issue after its author replies, removing the label (and, with
+/// `delete_superseded`, the bot's request) once the issue body plus the author's comments
+/// cover every required item.
+pub async fn on_author_reply(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue) {
+    if !issue.labels.iter().any(|l| l.name == repo_config.needs_info_label) {
+        return;
+    }
+
+    let issues = octo.issues(&config.owner, &config.repo);
+    let mut text = format!("{}\n{}", issue.title, issue.body.clone().unwrap_or_default());
+    let mut requests = Vec::new();
+    match issues.list_comments(issue.number).per_page(100).send().await {
+        Ok(page) => {
+            for comment in page.items {
+                let body = comment.body.unwrap_or_default();
+                if comment.user.login == issue.user.login {
+                    text.push('\n');
+                    text.push_str(&body);
+                } else if body.starts_with(REQUEST_MARKER) && !body.contains("\n---\n") {
+                    requests.push(comment.id);
+                }
+            }
+        }
+        Err(error) => {
+            log::error!("Error getting comments from issue: {}", error);
+            return;
+        }
+    }
+
+    if !missing_info(repo_config, &text).is_empty() {
+        log::debug!("Issue #{} still missing required info", issue.number);
+        return;
+    }
+
+    if let Err(error) = mutations::remove_label(config, octo, issue.number, &repo_config.needs_info_label).await {
+        log::error!("Error removing {} label from #{}: {}", repo_config.needs_info_label, issue.number, error);
+    } else {
+        log::info!("Issue #{} now has all required info", issue.number);
+    }
+
+    if config.delete_superseded {
+        for id in requests {
+            if let Err(error) = mutations::delete_comment(config, octo, id).await {
+                log::error!("Error deleting superseded missing-info request {}: {}", id, error);
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::config::{self, Config};
+use crate::outbox;
+use crate::permissions;
+use crate::store;
+use github_flows::octocrab::Octocrab;
+use serde_json::json;
+use std::env;
+
+fn env_num(name: &str, default: i64) -> i64 {
+    env::var(name).ok().and_then(|n| n.parse::<i64>().ok()).unwrap_or(default)
+}
+
+/// Returns the timestamps still inside the sliding window for `name`.
+fn window(config: &Config, name: &str, now: i64, window_secs: i64) -> Vec<i64> {
+    store::get(config, name)
+        .and_then(|v| serde_json::from_value::<Vec<i64>>(v).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|t| now - t < window_secs)
+        .collect()
+}
+
+/// Checks the per-user and per-issue LLM quotas and records this invocation if allowed.
+/// Maintainers and users listed in `quota_exempt` are never limited. Returns the polite
+/// refusal to reply with when the request is over quota.
+pub async fn check(config: &Config, octo: &Octocrab, issue_number: u64, login: &str) -> Option<String> {
+    if config::list_var("quota_exempt").iter().any(|u| u.eq_ignore_ascii_case(login)) {
+        return None;
+    }
+
+    let window_secs = env_num("quota_window_secs", 3600);
+    let user_limit = env_num("quota_user_limit", 5) as usize;
+    let issue_limit = env_num("quota_issue_limit", 10) as usize;
+    let now = chrono::Utc::now().timestamp();
+
+    let user_key = format!("quota:user:{}", login.to_lowercase());
+    let issue_key = format!("quota:issue:{}", issue_number);
+    let mut user_calls = window(config, &user_key, now, window_secs);
+    let mut issue_calls = window(config, &issue_key, now, window_secs);
+
+    let limited = if user_calls.len() >= user_limit {
+        Some(("you have", user_limit, user_calls[0]))
+    } else if issue_calls.len() >= issue_limit {
+        Some(("this issue has", issue_limit, issue_calls[0]))
+    } else {
+        None
+    };
+
+    if let Some((who, limit, oldest)) = limited {
+        if permissions::is_maintainer(config, octo, login).await {
+            return None;
+        }
+        let retry_mins = ((oldest + window_secs - now) / 60).max(1);
+        log::info!("Quota exceeded for {} on #{}", login, issue_number);
+        return Some(format!(
+            "@{} {} reached the limit of {} bot requests per {} minutes. Please try again in about {} minutes. Thanks for your patience!",
+            login,
+            who,
+            limit,
+            window_secs / 60,
+            retry_mins
+        ));
+    }
+
+    user_calls.push(now);
+    issue_calls.push(now);
+    store::set(config, &user_key, json!(user_calls), Some(window_secs));
+    store::set(config, &issue_key, json!(issue_calls), Some(window_secs));
+    None
+}
+
+/// Like [`check`], but posts the refusal itself and returns `false` when the request is
+/// over quota.
+pub async fn enforce(config: &Config, octo: &Octocrab, issue_number: u64, login: &str) -> bool {
+    let Some(resp) = check(config, octo, issue_number, login).await else {
+        return true;
+    };
+    if let Err(error) = outbox::reply(config, octo, issue_number, &resp).await {
+        log::error!("Error posting quota notice: {}", error);
+    }
+    false
+}
@@ -0,0 +1,63 @@
+use crate::graphql;
+use github_flows::octocrab::Octocrab;
+
+fn emoji(content: &str) -> &'static str {
+    match content {
+        "THUMBS_UP" => "👍",
+        "THUMBS_DOWN" => "👎",
+        "LAUGH" => "😄",
+        "HOORAY" => "🎉",
+        "CONFUSED" => "😕",
+        "HEART" => "❤️",
+        "ROCKET" => "🚀",
+        "EYES" => "👀",
+        _ => "❔",
+    }
+}
+
+fn total(reactions: &[(String, u64)]) -> u64 {
+    reactions.iter().map(|(_, n)| n).sum()
+}
+
+/// A "Community sentiment" section built from the thread's reaction counts: the reactions
+/// on the issue itself and the most-reacted comment. Empty when nobody has reacted or the
+/// thread can't be fetched.
+pub async fn section(octo: &Octocrab, owner: &str, repo: &str, number: u64) -> String {
+    let thread = match graphql::fetch_thread(octo, owner, repo, number).await {
+        Ok(thread) => thread,
+        Err(error) => {
+            log::warn!("Could not fetch reactions of #{}: {}", number, error);
+            return String::new();
+        }
+    };
+
+    let mut lines = Vec::new();
+    if !thread.reactions.is_empty() {
+        let counts = thread
+            .reactions
+            .iter()
+            .map(|(content, n)| format!("{} {}", emoji(content), n))
+            .collect::<Vec<String>>()
+            .join(" · ");
+        lines.push(format!("* Issue: {}", counts));
+    }
+
+    let most_reacted = thread
+        .comments
+        .iter()
+        .filter(|c| !c.reactions.is_empty())
+        .max_by_key(|c| total(&c.reactions));
+    if let Some(comment) = most_reacted {
+        lines.push(format!(
+            "* Most-reacted comment: [{}]({}) with {} reaction(s)",
+            comment.author.mention(),
+            comment.url,
+            total(&comment.reactions)
+        ));
+    }
+
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!("\n\n**Community sentiment**\n{}", lines.join("\n"))
+}
@@ -0,0 +1,197 @@
+use crate::config::Config;
+use crate::llm;
+use crate::store;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::env;
+
+lazy_static! {
+    static ref URL_REF: Regex = Regex::new(r"https://github\.com/([\w.-]+)/([\w.-]+)/(?:issues|pull)/(\d+)").unwrap();
+    static ref SHORT_REF: Regex = Regex::new(r"(?:^|[\s(\[])(?:([\w.-]+)/([\w.-]+))?#(\d+)\b").unwrap();
+}
+
+/// Body characters of a referenced issue read when summarizing it.
+const MAX_BODY_CHARS: usize = 3000;
+const SUMMARY_TTL_SECS: i64 = 24 * 3600;
+
+#[derive(Clone, PartialEq)]
+pub struct Reference {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+pub struct RelatedIssue {
+    pub reference: Reference,
+    pub issue: Issue,
+    /// One-sentence summary of the referenced thread; empty when it could not be generated.
+    pub summary: String,
+}
+
+/// Extracts issue/PR references (`#123`, `owner/repo#123` and full GitHub URLs) from `text`.
+/// Bare `#123` references resolve against the current repository.
+pub fn extract(text: &str, owner: &str, repo: &str) -> Vec<Reference> {
+    let mut refs: Vec<Reference> = Vec::new();
+    let found = URL_REF
+        .captures_iter(text)
+        .map(|c| (c.get(1).map(|m| m.as_str()), c.get(2).map(|m| m.as_str()), c[3].to_string()))
+        .chain(
+            SHORT_REF
+                .captures_iter(text)
+                .map(|c| (c.get(1).map(|m| m.as_str()), c.get(2).map(|m| m.as_str()), c[3].to_string())),
+        );
+    for (o, r, n) in found {
+        let Ok(number) = n.parse::<u64>() else {
+            continue;
+        };
+        let reference = Reference {
+            owner: o.unwrap_or(owner).to_string(),
+            repo: r.unwrap_or(repo).to_string(),
+            number,
+        };
+        if !refs.contains(&reference) {
+            refs.push(reference);
+        }
+    }
+    refs
+}
+
+/// Whether a referenced issue may be read into this repository's public output: references
+/// within the repository always can, others only from public repositories that are not the
+/// configured mirror or tracking repository.
+async fn is_readable(config: &Config, octo: &Octocrab, reference: &Reference, checked: &mut Vec<(String, bool)>) -> bool {
+    let full_name = format!("{}/{}", reference.owner, reference.repo).to_lowercase();
+    if full_name == format!("{}/{}", config.owner, config.repo).to_lowercase() {
+        return true;
+    }
+    if config.private_repos.iter().any(|r| r.to_lowercase() == full_name) {
+        return false;
+    }
+    if let Some((_, public)) = checked.iter().find(|(name, _)| *name == full_name) {
+        return *public;
+    }
+    let route = format!("/repos/{}/{}", reference.owner, reference.repo);
+    let public = match octo.get::<Value, _, ()>(route, None).await {
+        Ok(repository) => repository["private"].as_bool() == Some(false),
+        Err(error) => {
+            log::debug!("Could not check visibility of {}: {}", full_name, error);
+            false
+        }
+    };
+    checked.push((full_name, public));
+    public
+}
+
+/// Summarizes a referenced thread in one sentence, cached until the issue changes.
+async fn summarize(config: &Config, reference: &Reference, issue: &Issue) -> String {
+    let key = format!(
+        "related:{}/{}#{}:{}",
+        reference.owner.to_lowercase(),
+        reference.repo.to_lowercase(),
+        reference.number,
+        issue.updated_at.timestamp()
+    );
+    if let Some(summary) = store::get(config, &key).and_then(|v| v.as_str().map(|s| s.to_string())) {
+        return summary;
+    }
+    let body = issue.body.clone().unwrap_or_default().chars().take(MAX_BODY_CHARS).collect::<String>();
+    let sys_prompt = "You summarize GitHub issues in one sentence: the problem and, if stated, its status or resolution. Reply with the sentence only.";
+    let usr_prompt = format!("Issue titled '{}':\n{}", issue.title, body);
+    let conversation_id = format!("related_{}", issue.html_url);
+    match llm::chat(&config.llm.profile("related"), &conversation_id, sys_prompt, &usr_prompt, 96).await {
+        Ok(summary) => {
+            let summary = summary.trim().replace('\n', " ");
+            store::set(config, &key, json!(summary), Some(SUMMARY_TTL_SECS));
+            summary
+        }
+        Err(error) => {
+            log::warn!("Could not summarize {}/{}#{}: {}", reference.owner, reference.repo, reference.number, error);
+            String::new()
+        }
+    }
+}
+
+/// Fetches the issues referenced from `issue`, following references found in those issues
+/// up to `related_depth` levels and `related_max` issues in total. References to private
+/// repositories are skipped so nothing private reaches the LLM prompt or the public output.
+pub async fn expand(config: &Config, octo: &Octocrab, issue: &Issue) -> Vec<RelatedIssue> {
+    let max_depth = env::var("related_depth").ok().and_then(|n| n.parse::<u32>().ok()).unwrap_or(1);
+    let max_issues = env::var("related_max").ok().and_then(|n| n.parse::<usize>().ok()).unwrap_or(5);
+
+    let origin = Reference {
+        owner: config.owner.clone(),
+        repo: config.repo.clone(),
+        number: issue.number,
+    };
+    let mut visited = vec![origin];
+    let mut queue: VecDeque<(Reference, u32)> = extract(&issue.body.clone().unwrap_or_default(), &config.owner, &config.repo)
+        .into_iter()
+        .map(|r| (r, 1))
+        .collect();
+    let mut related = Vec::new();
+    let mut checked = Vec::new();
+
+    while let Some((reference, depth)) = queue.pop_front() {
+        if related.len() >= max_issues {
+            break;
+        }
+        if visited.contains(&reference) {
+            continue;
+        }
+        visited.push(reference.clone());
+        if !is_readable(config, octo, &reference, &mut checked).await {
+            log::debug!("Skipping reference to non-public {}/{}#{}", reference.owner, reference.repo, reference.number);
+            continue;
+        }
+
+        let fetched = match octo.issues(&reference.owner, &reference.repo).get(reference.number).await {
+            Ok(fetched) => fetched,
+            Err(error) => {
+                log::debug!("Skipping reference {}/{}#{}: {}", reference.owner, reference.repo, reference.number, error);
+                continue;
+            }
+        };
+        if depth < max_depth {
+            let nested = extract(&fetched.body.clone().unwrap_or_default(), &reference.owner, &reference.repo);
+            queue.extend(nested.into_iter().map(|r| (r, depth + 1)));
+        }
+        let summary = summarize(config, &reference, &fetched).await;
+        related.push(RelatedIssue { reference, issue: fetched, summary });
+    }
+
+    related
+}
+
+/// Renders related issues as additional LLM context.
+pub fn context_block(related: &[RelatedIssue]) -> String {
+    related
+        .iter()
+        .map(|r| {
+            let summary = if r.summary.is_empty() { String::new() } else { format!(" In short: {}", r.summary) };
+            format!(
+                "Referenced {}/{}#{} is titled '{}'.{}\n",
+                r.reference.owner, r.reference.repo, r.reference.number, r.issue.title, summary
+            )
+        })
+        .collect()
+}
+
+/// Renders the "Related" section appended to bot output.
+pub fn section(related: &[RelatedIssue]) -> String {
+    if related.is_empty() {
+        return String::new();
+    }
+    let lines = related
+        .iter()
+        .map(|r| {
+            let kind = if r.issue.pull_request.is_some() { "PR" } else { "issue" };
+            let summary = if r.summary.is_empty() { String::new() } else { format!(": {}", r.summary) };
+            format!("* [{}]({}) ({}, {:?}){}", r.issue.title, r.issue.html_url, kind, r.issue.state, summary)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!("\n\n**Related**\n{}", lines)
+}
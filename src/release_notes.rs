@@ -0,0 +1,255 @@
+use crate::author::Author;
+use crate::config::Config;
+use crate::dry_run;
+use crate::footer;
+use crate::permissions;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+
+const DEFAULT_CATEGORIES: &str = "breaking-change=Breaking changes;feature=Features;enhancement=Features;bug=Bug fixes;documentation=Documentation;dependencies=Dependencies";
+const OTHER_CATEGORY: &str = "Other changes";
+/// Reply to a malformed range. It must not contain the phrase, or it would trigger itself.
+const USAGE: &str = "Usage: follow the release notes phrase with a `<from>..<to>` range, where each side is a tag or a date (YYYY-MM-DD).";
+
+/// Parses the range argument following the command phrase, e.g. `v0.13.0..v0.14.0`.
+/// The right-hand side may be omitted, in which case the range ends now.
+pub fn parse_range(body: &str, phrase: &str) -> Option<(String, Option<String>)> {
+    let rest = &body[body.find(phrase)? + phrase.len()..];
+    let arg = rest.split_whitespace().next()?;
+    let (from, to) = arg.split_once("..")?;
+    if from.is_empty() {
+        return None;
+    }
+    let to = if to.is_empty() { None } else { Some(to.to_string()) };
+    Some((from.to_string(), to))
+}
+
+/// Percent-encodes a user-supplied tag or commit-ish for use as one path segment.
+fn encode_ref(endpoint: &str) -> String {
+    endpoint
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Resolves a range endpoint to an ISO 8601 timestamp. Plain dates (`2024-09-01`) are
+/// used as-is; anything else is treated as a tag or commit-ish and resolved to its commit date.
+async fn resolve_endpoint(octo: &Octocrab, owner: &str, repo: &str, endpoint: &str) -> anyhow::Result<String> {
+    let is_date = endpoint.len() >= 10
+        && endpoint.as_bytes()[4] == b'-'
+        && endpoint.as_bytes()[7] == b'-'
+        && endpoint[..4].chars().all(|c| c.is_ascii_digit());
+    if is_date {
+        return Ok(endpoint.to_string());
+    }
+
+    let route = format!("/repos/{}/{}/commits/{}", owner, repo, encode_ref(endpoint));
+    let commit: Value = octo.get(route, None::<&()>).await?;
+    commit["commit"]["committer"]["date"]
+        .as_str()
+        .map(|d| d.to_string())
+        .ok_or_else(|| anyhow::anyhow!("Could not resolve '{}' to a commit date", endpoint))
+}
+
+async fn search_all(octo: &Octocrab, query: &str) -> anyhow::Result<Vec<Issue>> {
+    let mut items = Vec::new();
+    for page in 1u32..=10 {
+        let result = octo
+            .search()
+            .issues_and_pull_requests(query)
+            .per_page(100)
+            .page(page)
+            .send()
+            .await?;
+        let count = result.items.len();
+        items.extend(result.items);
+        if count < 100 {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+fn category_map() -> Vec<(String, String)> {
+    let raw = env::var("release_notes_categories").unwrap_or(DEFAULT_CATEGORIES.to_string());
+    raw.split(';')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(label, heading)| (label.trim().to_lowercase(), heading.trim().to_string()))
+        .collect()
+}
+
+fn categorize(item: &Issue, categories: &[(String, String)]) -> String {
+    for (label, heading) in categories {
+        if item.labels.iter().any(|l| l.name.to_lowercase() == *label) {
+            return heading.clone();
+        }
+    }
+    OTHER_CATEGORY.to_string()
+}
+
+/// Builds a draft changelog from the merged PRs and closed issues between `from` and `to`.
+pub async fn draft(octo: &Octocrab, owner: &str, repo: &str, from: &str, to: Option<&str>) -> anyhow::Result<String> {
+    let since = resolve_endpoint(octo, owner, repo, from).await?;
+    let until = match to {
+        Some(to) => resolve_endpoint(octo, owner, repo, to).await?,
+        None => "*".to_string(),
+    };
+
+    log::debug!("Collecting release notes for {}/{} between {} and {}", owner, repo, since, until);
+    let prs = search_all(octo, &format!("repo:{}/{} is:pr is:merged merged:{}..{}", owner, repo, since, until)).await?;
+    let issues = search_all(octo, &format!("repo:{}/{} is:issue is:closed reason:completed closed:{}..{}", owner, repo, since, until)).await?;
+
+    let categories = category_map();
+    let mut sections: BTreeMap<usize, (String, Vec<String>)> = BTreeMap::new();
+    let order = |heading: &str| {
+        categories
+            .iter()
+            .position(|(_, h)| h == heading)
+            .unwrap_or(categories.len())
+    };
+
+    for pr in &prs {
+        let heading = categorize(pr, &categories);
+        let entry = format!("* {} by {} in #{}", pr.title, Author::from_login(&pr.user.login).mention(), pr.number);
+        sections.entry(order(&heading)).or_insert((heading, Vec::new())).1.push(entry);
+    }
+    for issue in &issues {
+        let heading = categorize(issue, &categories);
+        let entry = format!("* {} (closes #{})", issue.title, issue.number);
+        sections.entry(order(&heading)).or_insert((heading, Vec::new())).1.push(entry);
+    }
+
+    let mut out = format!(
+        "## What's changed ({}..{})\n",
+        from,
+        to.unwrap_or("HEAD")
+    );
+    if sections.is_empty() {
+        out.push_str("\nNo merged pull requests or closed issues found in this range.\n");
+    }
+    for (_, (heading, entries)) in sections {
+        out.push_str(&format!("\n### {}\n\n{}\n", heading, entries.join("\n")));
+    }
+
+    Ok(out)
+}
+
+/// Finds the tag of the release published immediately before `tag`, used when a release
+/// event asks for notes without an explicit range.
+pub async fn previous_tag(octo: &Octocrab, owner: &str, repo: &str, tag: &str) -> Option<String> {
+    let route = format!("/repos/{}/{}/releases?per_page=100", owner, repo);
+    let releases: Value = octo.get(route, None::<&()>).await.ok()?;
+    let releases = releases.as_array()?;
+    let pos = releases.iter().position(|r| r["tag_name"].as_str() == Some(tag))?;
+    releases[pos + 1..]
+        .iter()
+        .find(|r| !r["draft"].as_bool().unwrap_or(false))
+        .and_then(|r| r["tag_name"].as_str())
+        .map(|t| t.to_string())
+}
+
+/// Handles a published release whose body contains the release notes phrase by replacing
+/// the phrase line with a drafted changelog.
+pub async fn on_release(config: &Config, octo: &Octocrab, release: Value) {
+    if release["action"].as_str() != Some("published") {
+        log::debug!("Ignoring non-published release event");
+        return;
+    }
+    let release_body = release["release"]["body"].as_str().unwrap_or_default().to_string();
+    if !release_body.contains(&config.release_notes_phrase) {
+        log::info!("Ignoring release without release notes phrase");
+        return;
+    }
+
+    let (owner, repo) = (&config.owner, &config.repo);
+    let tag = release["release"]["tag_name"].as_str().unwrap_or_default().to_string();
+    let range = match parse_range(&release_body, &config.release_notes_phrase) {
+        Some(range) => Some(range),
+        None => previous_tag(octo, owner, repo, &tag)
+            .await
+            .map(|prev| (prev, Some(tag.clone()))),
+    };
+    let Some((from, to)) = range else {
+        log::warn!("No previous release found for {}", tag);
+        return;
+    };
+
+    let notes = match draft(octo, owner, repo, &from, to.as_deref()).await {
+        Ok(notes) => notes,
+        Err(error) => {
+            log::error!("Error drafting release notes for {}: {}", tag, error);
+            return;
+        }
+    };
+
+    let phrase_line = release_body
+        .lines()
+        .find(|l| l.contains(&config.release_notes_phrase))
+        .unwrap_or(&config.release_notes_phrase)
+        .to_string();
+    let new_body = release_body.replacen(&phrase_line, &notes, 1);
+    if dry_run::intercept(config, "edit_release", None, serde_json::json!({ "release": tag, "body": new_body })) {
+        return;
+    }
+    let route = format!("/repos/{}/{}/releases/{}", owner, repo, release["release"]["id"]);
+    if let Err(error) = octo.patch::<Value, _, _>(route, Some(&serde_json::json!({ "body": new_body }))).await {
+        permissions::check(config, octo, "update release notes", permissions::CONTENTS_WRITE, &error).await;
+    } else {
+        log::info!("Successfully drafted release notes for {}", tag);
+    }
+}
+
+/// Handles the release notes phrase in an issue comment, returning the draft to reply with.
+pub async fn on_command(config: &Config, octo: &Octocrab, body: &str, requester: &str) -> anyhow::Result<String> {
+    let Some((from, to)) = parse_range(body, &config.release_notes_phrase) else {
+        return Ok(USAGE.to_string());
+    };
+    let notes = draft(octo, &config.owner, &config.repo, &from, to.as_deref()).await?;
+    Ok(format!("{}{}", notes, footer::render(config, Some(&format!("@{}", requester)))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str = "@flows_release_notes";
+
+    #[test]
+    fn parse_range_reads_both_ends() {
+        assert_eq!(
+            parse_range("@flows_release_notes v0.13.0..v0.14.0 please", PHRASE),
+            Some(("v0.13.0".to_string(), Some("v0.14.0".to_string())))
+        );
+    }
+
+    #[test]
+    fn parse_range_leaves_the_end_open() {
+        assert_eq!(parse_range("@flows_release_notes 2024-09-01..", PHRASE), Some(("2024-09-01".to_string(), None)));
+    }
+
+    #[test]
+    fn parse_range_rejects_malformed_ranges() {
+        assert_eq!(parse_range("@flows_release_notes ..v0.14.0", PHRASE), None);
+        assert_eq!(parse_range("@flows_release_notes v0.14.0", PHRASE), None);
+        assert_eq!(parse_range("@flows_release_notes", PHRASE), None);
+        assert_eq!(parse_range("v0.13.0..v0.14.0", PHRASE), None);
+    }
+
+    #[test]
+    fn encode_ref_keeps_tags_in_one_segment() {
+        assert_eq!(encode_ref("v0.14.0-rc.1"), "v0.14.0-rc.1");
+        assert_eq!(encode_ref("../../user?x=1"), "..%2F..%2Fuser%3Fx%3D1");
+        assert_eq!(encode_ref("release/0.14"), "release%2F0.14");
+    }
+
+    #[test]
+    fn usage_does_not_trigger_itself() {
+        assert!(!USAGE.contains(PHRASE));
+        assert_eq!(parse_range(USAGE, PHRASE), None);
+    }
+}
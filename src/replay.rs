@@ -0,0 +1,121 @@
+use crate::commands;
+use crate::config::Config;
+use crate::dry_run;
+use crate::permissions;
+use crate::store;
+use github_flows::octocrab::Octocrab;
+use serde_json::{json, Value};
+use std::env;
+
+/// Events the bot subscribes to; deliveries of anything else are never replayed.
+const EVENTS: [&str; 3] = ["issues", "issue_comment", "release"];
+/// Replay marks outlive the longest window so a delivery is requested at most once.
+const REPLAYED_TTL_SECS: i64 = 2 * 24 * 3600;
+
+/// The repository webhooks that deliver to this flow: `replay_hook_id` when set, otherwise
+/// every hook pointing at `replay_hook_host`.
+async fn hooks(config: &Config, octo: &Octocrab) -> anyhow::Result<Vec<u64>> {
+    if let Some(id) = env::var("replay_hook_id").ok().and_then(|id| id.parse::<u64>().ok()) {
+        return Ok(vec![id]);
+    }
+    let host = env::var("replay_hook_host").unwrap_or("flows.network".to_string());
+    let route = format!("/repos/{}/{}/hooks?per_page=100", config.owner, config.repo);
+    let hooks = octo.get::<Value, _, ()>(route, None).await?;
+    Ok(hooks
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|h| h["config"]["url"].as_str().map_or(false, |u| u.contains(&host)))
+        .filter_map(|h| h["id"].as_u64())
+        .collect())
+}
+
+/// Lists a hook's deliveries newer than `since`, newest first. Only the latest page of
+/// 100 is read, which covers a few hours of events for a busy repository.
+async fn deliveries(config: &Config, octo: &Octocrab, hook: u64, since: i64) -> anyhow::Result<Vec<Value>> {
+    let route = format!("/repos/{}/{}/hooks/{}/deliveries?per_page=100", config.owner, config.repo, hook);
+    let page = octo.get::<Value, _, ()>(route, None).await?;
+    Ok(page
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|d| {
+            d["delivered_at"]
+                .as_str()
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .map_or(false, |t| t.timestamp() >= since)
+        })
+        .cloned()
+        .collect())
+}
+
+/// Asks GitHub to redeliver the subscribed events of the last `hours` hours (default 6)
+/// that did not succeed, or all of them with `all=true`. Redelivered events go through the
+/// normal handler, where the delivery dedup skips anything already processed.
+pub async fn run(config: &Config, octo: &Octocrab, args: &str) -> anyhow::Result<String> {
+    let args = commands::kv_args(args);
+    let dedup_hours = env::var("dedup_ttl_secs").ok().and_then(|n| n.parse::<i64>().ok()).unwrap_or(86400) / 3600;
+    let hours = args.get("hours").and_then(|h| h.parse::<i64>().ok()).unwrap_or(6);
+    let include_ok = args.get("all").map_or(false, |a| a == "true");
+    if include_ok && hours > dedup_hours {
+        return Ok(format!(
+            "`all=true` is limited to the last {} hours, the window in which already-processed events are recognized.",
+            dedup_hours
+        ));
+    }
+    let since = chrono::Utc::now().timestamp() - hours * 3600;
+
+    let hook_ids = match hooks(config, octo).await {
+        Ok(ids) if !ids.is_empty() => ids,
+        Ok(_) => return Ok("No webhook delivering to this flow was found; set `replay_hook_id`.".to_string()),
+        Err(error) => {
+            permissions::check(config, octo, "list webhooks", permissions::HOOKS_WRITE, &error).await;
+            return Err(error);
+        }
+    };
+
+    let (mut requested, mut skipped, mut failed) = (0, 0, 0);
+    for hook in hook_ids {
+        for delivery in deliveries(config, octo, hook, since).await? {
+            let event = delivery["event"].as_str().unwrap_or_default();
+            let succeeded = delivery["status_code"].as_u64().map_or(false, |c| (200..300).contains(&c));
+            let Some(id) = delivery["id"].as_u64() else {
+                continue;
+            };
+            let marker = format!("replay:{}", delivery["guid"].as_str().unwrap_or_default());
+            if !EVENTS.contains(&event) || delivery["redelivery"].as_bool() == Some(true) || store::get(config, &marker).is_some() {
+                continue;
+            }
+            if succeeded && !include_ok {
+                skipped += 1;
+                continue;
+            }
+
+            if dry_run::intercept(config, "redeliver", None, json!({ "event": event, "delivery": id })) {
+                requested += 1;
+                continue;
+            }
+            let route = format!("/repos/{}/{}/hooks/{}/deliveries/{}/attempts", config.owner, config.repo, hook, id);
+            let redelivered: Result<Value, _> = octo.post(route, Some(&json!({}))).await;
+            match redelivered {
+                Ok(_) => {
+                    store::set(config, &marker, json!(true), Some(REPLAYED_TTL_SECS));
+                    requested += 1;
+                }
+                Err(error) => {
+                    log::error!("Error redelivering {} {}: {}", event, id, error);
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    let mut report = format!("🔁 Requested redelivery of {} event(s) from the last {} hour(s).", requested, hours);
+    if skipped > 0 {
+        report.push_str(&format!(" Skipped {} delivered successfully; add `all=true` to include them.", skipped));
+    }
+    if failed > 0 {
+        report.push_str(&format!(" {} redelivery request(s) failed; see the logs.", failed));
+    }
+    Ok(report)
+}
@@ -0,0 +1,457 @@
+use crate::llm::{AzureDeployment, LabelProfile, Sampling};
+use crate::output::Channel;
+use crate::persona::Persona;
+use crate::summary::Detail;
+use github_flows::octocrab::Octocrab;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+
+/// Per-repository settings committed to the target repo, by default at `.github/issue-bot.json`.
+/// Every field is optional so a partial file only overrides what it mentions.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct RepoConfig {
+    pub required_info: Vec<RequiredInfo>,
+    pub needs_info_label: String,
+    pub sponsor_tiers: Vec<SponsorTier>,
+    /// Routes this repo's LLM requests to an Azure OpenAI deployment.
+    pub azure: Option<AzureDeployment>,
+    /// Default summary detail level, overriding the `summary_detail` env var.
+    pub summary_detail: Option<Detail>,
+    pub severity: SeverityConfig,
+    pub digest: DigestConfig,
+    pub spam: SpamConfig,
+    pub security: SecurityConfig,
+    pub mirror: MirrorConfig,
+    pub confidence: ConfidenceConfig,
+    pub greeting: GreetingConfig,
+    pub heat: HeatConfig,
+    /// Tone and voice of the bot's prose in this repo.
+    pub persona: Persona,
+    /// Screen-reader friendly replies for everyone, not just users who opted in.
+    pub accessible: bool,
+    pub milestones: MilestoneConfig,
+    pub good_first_issue: GoodFirstIssueConfig,
+    /// Sampling overrides by command, on top of `llm_profiles`.
+    pub llm_profiles: HashMap<String, Sampling>,
+    /// Prompt guidance and sampling overrides for issues carrying a label, merged over the
+    /// command profile for every matching label.
+    pub label_profiles: HashMap<String, LabelProfile>,
+    /// Post a hand-off brief when an issue with history is assigned to someone new.
+    pub handoff: bool,
+    /// Scan new issues and comments daily for leaked credentials.
+    pub leak_audit: bool,
+    /// Overrides the `footer_template` env var; an empty string or `none` omits the footer.
+    pub footer: Option<String>,
+    /// Keep a rolling journal of each issue's discussion and summarize from it.
+    pub journal: bool,
+    /// Output channel by command (`summary`, `triage`, `suggest_fix`, `compare` or a `@flows_bot`
+    /// subcommand); unlisted commands comment on the issue.
+    pub outputs: HashMap<String, Channel>,
+    pub assignees: AssigneeConfig,
+    pub environment: EnvironmentConfig,
+    /// Destinations of the `tracking` and `team` output channels.
+    pub private: PrivateConfig,
+    /// Which issues and comments the bot acts on, and when it may post.
+    pub filters: FilterConfig,
+    pub titles: TitleConfig,
+}
+
+/// A piece of information a bug report must contain, detected by any of `patterns`
+/// (case-insensitive regexes) matching the report.
+#[derive(Deserialize, Clone)]
+pub struct RequiredInfo {
+    pub name: String,
+    #[serde(default)]
+    pub hint: String,
+    pub patterns: Vec<String>,
+}
+
+/// Response behavior for issues filed by GitHub Sponsors of the repo owner.
+#[derive(Deserialize, Clone)]
+pub struct SponsorTier {
+    pub name: String,
+    #[serde(default)]
+    pub min_monthly_dollars: u64,
+    /// Model used for this tier's analyses instead of `llm_model_name`.
+    pub model: Option<String>,
+    pub label: Option<String>,
+    /// Post a triage summary as soon as the issue is opened.
+    #[serde(default)]
+    pub expedite: bool,
+}
+
+/// LLM severity classification of new issues.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct SeverityConfig {
+    pub enabled: bool,
+    /// The only labels the classifier may apply.
+    pub allowed_labels: Vec<String>,
+    /// Mention (user or `@org/team`) pinged when an issue is classified P0.
+    pub oncall: Option<String>,
+}
+
+impl Default for SeverityConfig {
+    fn default() -> Self {
+        SeverityConfig {
+            enabled: false,
+            allowed_labels: ["P0", "P1", "P2", "P3"].iter().map(|p| format!("priority/{}", p)).collect(),
+            oncall: None,
+        }
+    }
+}
+
+/// Issue digest grouping and the weekly owner sign-off.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct DigestConfig {
+    /// Publish a digest issue every week.
+    pub weekly: bool,
+    pub label: String,
+    pub components: Vec<Component>,
+    /// Mention added when a component misses its sign-off, e.g. `@org/maintainers`.
+    pub escalate_to: Option<String>,
+}
+
+/// A component identified by its label and the owners who sign off on it.
+#[derive(Deserialize, Clone)]
+pub struct Component {
+    pub label: String,
+    #[serde(default)]
+    pub owners: Vec<String>,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        DigestConfig {
+            weekly: false,
+            label: "digest".to_string(),
+            components: Vec::new(),
+            escalate_to: None,
+        }
+    }
+}
+
+/// Spam and abuse screening of every new issue and comment.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct SpamConfig {
+    pub enabled: bool,
+    pub label: String,
+    /// Confirm borderline heuristic hits with the LLM.
+    pub use_llm: bool,
+    /// Ask the LLM about every item, not just borderline ones.
+    pub llm_all: bool,
+    /// Mention notified when something is flagged, e.g. `@org/moderators`.
+    pub notify: Option<String>,
+}
+
+impl Default for SpamConfig {
+    fn default() -> Self {
+        SpamConfig {
+            enabled: false,
+            label: "spam".to_string(),
+            use_llm: true,
+            llm_all: false,
+            notify: None,
+        }
+    }
+}
+
+/// Fast path for reports that may disclose a vulnerability.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct SecurityConfig {
+    pub enabled: bool,
+    pub label: String,
+    /// Confirm heuristic hits with the LLM.
+    pub use_llm: bool,
+    /// Disclosure process linked from the notice; defaults to the repo's security policy.
+    pub policy_url: Option<String>,
+    /// Public notice, supporting `{author}` and `{policy}` placeholders.
+    pub template: Option<String>,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        SecurityConfig {
+            enabled: true,
+            label: "security".to_string(),
+            use_llm: true,
+            policy_url: None,
+            template: None,
+        }
+    }
+}
+
+/// A private repository where sensitive analyses are posted instead of the public thread.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct MirrorConfig {
+    /// `owner/repo` of the team-only repository; mirroring is off when unset.
+    pub repo: Option<String>,
+    /// Analyses routed to the mirror, e.g. `severity`, `security`.
+    pub kinds: Vec<String>,
+}
+
+impl Default for MirrorConfig {
+    fn default() -> Self {
+        MirrorConfig {
+            repo: None,
+            kinds: vec!["severity".to_string(), "security".to_string()],
+        }
+    }
+}
+
+/// Confidence required before classifiers act without a human.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ConfidenceConfig {
+    pub default_threshold: f64,
+    /// Per-feature thresholds, keyed by `severity`, `spam`, `labels` or `duplicates`.
+    pub thresholds: HashMap<String, f64>,
+    /// Label applied to items queued for human triage.
+    pub queue_label: String,
+    /// Ask the reporter a clarifying question when missing information is the cause.
+    pub clarify: bool,
+}
+
+impl Default for ConfidenceConfig {
+    fn default() -> Self {
+        ConfidenceConfig {
+            default_threshold: 0.7,
+            thresholds: HashMap::new(),
+            queue_label: "needs-triage".to_string(),
+            clarify: true,
+        }
+    }
+}
+
+/// Welcome comment for authors opening their first issue in the repo.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct GreetingConfig {
+    pub enabled: bool,
+    /// Supports `{repo}`, `{author}`, `{contributing}` and `{labels}` placeholders.
+    pub template: Option<String>,
+    /// Defaults to the repo's `CONTRIBUTING.md`.
+    pub contributing_url: Option<String>,
+    pub area_label_prefix: String,
+}
+
+impl Default for GreetingConfig {
+    fn default() -> Self {
+        GreetingConfig {
+            enabled: true,
+            template: None,
+            contributing_url: None,
+            area_label_prefix: "area/".to_string(),
+        }
+    }
+}
+
+/// Detection of threads whose tone is escalating.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct HeatConfig {
+    pub enabled: bool,
+    /// Temperature from 0 (calm) to 1 (hostile) at which a thread is flagged.
+    pub threshold: f64,
+    pub label: String,
+    /// Mention notified when a thread is flagged, e.g. `@org/maintainers`.
+    pub notify: Option<String>,
+    /// Propose locking a flagged thread; a maintainer confirms with `@flows_bot confirm`.
+    pub suggest_lock: bool,
+}
+
+impl Default for HeatConfig {
+    fn default() -> Self {
+        HeatConfig {
+            enabled: false,
+            threshold: 0.7,
+            label: "needs-maintainer-attention".to_string(),
+            notify: None,
+            suggest_lock: false,
+        }
+    }
+}
+
+/// Milestone suggestions and project board filing for new issues.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct MilestoneConfig {
+    pub enabled: bool,
+    /// Set the suggested milestone when it clears the `milestone` confidence threshold.
+    pub auto_set: bool,
+    /// Node ID of a Projects v2 board new issues are added to.
+    pub project_id: Option<String>,
+    /// Status column for new items, e.g. `Triage`.
+    pub project_column: Option<String>,
+    /// Record daily open/closed counts per milestone for burndown charts.
+    pub burndown: bool,
+}
+
+impl Default for RepoConfig {
+    fn default() -> Self {
+        RepoConfig {
+            required_info: Vec::new(),
+            needs_info_label: "needs-more-info".to_string(),
+            sponsor_tiers: Vec::new(),
+            azure: None,
+            summary_detail: None,
+            severity: SeverityConfig::default(),
+            digest: DigestConfig::default(),
+            spam: SpamConfig::default(),
+            security: SecurityConfig::default(),
+            mirror: MirrorConfig::default(),
+            confidence: ConfidenceConfig::default(),
+            greeting: GreetingConfig::default(),
+            heat: HeatConfig::default(),
+            persona: Persona::default(),
+            accessible: false,
+            milestones: MilestoneConfig::default(),
+            good_first_issue: GoodFirstIssueConfig::default(),
+            llm_profiles: HashMap::new(),
+            label_profiles: HashMap::new(),
+            handoff: false,
+            leak_audit: false,
+            footer: None,
+            journal: false,
+            outputs: HashMap::new(),
+            assignees: AssigneeConfig::default(),
+            environment: EnvironmentConfig::default(),
+            private: PrivateConfig::default(),
+            filters: FilterConfig::default(),
+            titles: TitleConfig::default(),
+        }
+    }
+}
+
+/// Loads the repo config from the default branch, falling back to defaults when the file
+/// is absent or malformed.
+pub async fn load(octo: &Octocrab, owner: &str, repo: &str) -> RepoConfig {
+    let path = env::var("repo_config_path").unwrap_or(".github/issue-bot.json".to_string());
+
+    let content = match octo.repos(owner, repo).get_content().path(&path).send().await {
+        Ok(items) => items.items.into_iter().next().and_then(|i| i.decoded_content()),
+        Err(error) => {
+            log::debug!("No repo config at {}: {}", path, error);
+            None
+        }
+    };
+
+    match content.map(|c| serde_json::from_str::<RepoConfig>(&c)) {
+        Some(Ok(config)) => config,
+        Some(Err(error)) => {
+            log::error!("Invalid repo config {}: {}", path, error);
+            RepoConfig::default()
+        }
+        None => RepoConfig::default(),
+    }
+}
+
+/// Newcomer-friendliness pass over open unassigned issues.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct GoodFirstIssueConfig {
+    /// Run the pass every week, in addition to the `good-first-issues` command.
+    pub weekly: bool,
+    pub label: String,
+    /// Issues evaluated per run, bounding LLM usage.
+    pub max_per_run: usize,
+}
+
+impl Default for GoodFirstIssueConfig {
+    fn default() -> Self {
+        GoodFirstIssueConfig {
+            weekly: false,
+            label: "good first issue".to_string(),
+            max_per_run: 10,
+        }
+    }
+}
+
+/// Workload-aware assignee suggestions in triage comments.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct AssigneeConfig {
+    pub suggest: bool,
+    /// Considered when no digest component owning the issue's labels has owners.
+    pub candidates: Vec<String>,
+    /// Window for a candidate's recent activity.
+    pub activity_days: i64,
+    /// How long a candidate's workload is cached.
+    pub cache_secs: i64,
+}
+
+impl Default for AssigneeConfig {
+    fn default() -> Self {
+        AssigneeConfig {
+            suggest: false,
+            candidates: Vec::new(),
+            activity_days: 14,
+            cache_secs: 3600,
+        }
+    }
+}
+
+/// WasmEdge version, OS and architecture detection with `os/`, `arch/` and `version/` labels.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct EnvironmentConfig {
+    pub enabled: bool,
+    /// Ask the LLM about whatever the regex heuristics miss.
+    pub use_llm: bool,
+}
+
+impl Default for EnvironmentConfig {
+    fn default() -> Self {
+        EnvironmentConfig {
+            enabled: false,
+            use_llm: true,
+        }
+    }
+}
+
+/// Maintainer-only destinations for results that shouldn't appear in public threads.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct PrivateConfig {
+    /// `owner/repo` of a private repository holding the tracking issue.
+    pub tracking_repo: Option<String>,
+    /// Tracking issue number; one is opened on first use when unset.
+    pub tracking_issue: Option<u64>,
+    /// `org/team-slug` whose private team discussion receives reports.
+    pub team: Option<String>,
+    /// Team discussion number; one is started on first use when unset.
+    pub discussion: Option<u64>,
+}
+
+/// Ignore rules for incoming events and quiet hours for outgoing replies.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct FilterConfig {
+    /// Comments matching any of these regexes are ignored.
+    pub ignore_comments: Vec<String>,
+    /// Logins whose issues and comments are ignored, case-insensitively.
+    pub ignore_users: Vec<String>,
+    /// Ignore issues and comments from bot accounts.
+    pub ignore_bots: bool,
+    /// Only act on issues carrying one of these labels; empty means every issue.
+    pub labels: Vec<String>,
+    /// Daily windows such as `22:00-07:00` during which replies are queued and posted later.
+    pub quiet_hours: Vec<String>,
+    /// Offset of the quiet hours from UTC, e.g. `+08:00`; UTC when unset.
+    pub utc_offset: Option<String>,
+}
+
+/// Clearer title suggestions for new issues with vague titles.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct TitleConfig {
+    pub enabled: bool,
+    /// Rename the issue instead of suggesting; the comment keeps the original title.
+    pub rename: bool,
+}
@@ -0,0 +1,72 @@
+use crate::config::{self, Config};
+use crate::store;
+use crate::{backfill, batch, digest, feedback, filters, good_first, leak_audit, metrics, milestone, repo_config};
+use github_flows::{get_octo, GithubLogin};
+use serde_json::json;
+
+const HOUR_SECS: i64 = 3600;
+const DAY_SECS: i64 = 24 * HOUR_SECS;
+const WEEK_SECS: i64 = 7 * DAY_SECS;
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// Returns true and records the run if `job` last ran at least `interval_secs` ago.
+fn is_due(config: &Config, job: &str, interval_secs: i64) -> bool {
+    let name = format!("schedule:{}:last", job);
+    let last = store::get(config, &name).and_then(|v| v.as_i64()).unwrap_or(0);
+    if now() - last < interval_secs {
+        return false;
+    }
+    store::set(config, &name, json!(now()), None);
+    true
+}
+
+/// Runs every periodic job that is due, for each registered repository. The cron tick is
+/// more frequent than any job, so each job tracks its own last run in the store.
+pub async fn tick() {
+    let octo = get_octo(&GithubLogin::Default);
+    for (owner, repo) in config::registry() {
+        let mut config = Config::from_env(&owner, &repo);
+        let repo_config = repo_config::load(&octo, &owner, &repo).await;
+        config.quiet_hours = filters::quiet_windows(&repo_config.filters);
+        let run = metrics::start(&octo).await;
+
+        if repo_config.digest.weekly && is_due(&config, "weekly_digest", WEEK_SECS) {
+            if let Err(error) = digest::publish_weekly(&config, &repo_config, &octo).await {
+                log::error!("Error publishing weekly digest for {}/{}: {}", owner, repo, error);
+            }
+        }
+
+        if repo_config.good_first_issue.weekly && is_due(&config, "good_first_issue", WEEK_SECS) {
+            match good_first::run(&config, &repo_config, &octo).await {
+                Ok(report) => log::info!("Good-first-issue pass for {}/{}: {}", owner, repo, report),
+                Err(error) => log::error!("Error running good-first-issue pass for {}/{}: {}", owner, repo, error),
+            }
+        }
+
+        if repo_config.milestones.burndown && is_due(&config, "burndown", DAY_SECS) {
+            milestone::record_burndown(&config, &octo).await;
+        }
+
+        if repo_config.leak_audit && is_due(&config, "leak_audit", DAY_SECS) {
+            leak_audit::run(&config, &repo_config, &octo).await;
+        }
+
+        if is_due(&config, "backfill", HOUR_SECS) {
+            backfill::resume(&config, &repo_config, &octo).await;
+        }
+
+        if is_due(&config, "feedback", DAY_SECS) {
+            feedback::poll(&config, &octo).await;
+        }
+
+        filters::release(&config, &octo).await;
+
+        if !filters::is_quiet(&config) && is_due(&config, "comment_batch", DAY_SECS) {
+            batch::flush(&config, &octo).await;
+        }
+        metrics::finish(&config, &octo, run).await;
+    }
+}
@@ -0,0 +1,141 @@
+use crate::author::Author;
+use crate::config::Config;
+use github_flows::octocrab::{
+    models::{issues::Issue, CommentId},
+    Octocrab,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref PERMALINK: Regex = Regex::new(r"https://github\.com/([\w.-]+)/([\w.-]+)/(?:issues|pull)/(\d+)#(issue-\d+|issuecomment-(\d+))").unwrap();
+}
+
+/// A part of the thread the requester asked the bot to focus on.
+pub struct Excerpt {
+    pub author: Option<String>,
+    pub url: Option<String>,
+    pub text: String,
+}
+
+/// Collapses whitespace and case so a quote still matches after GitHub's quote-reply
+/// reflows it.
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase()
+}
+
+/// Consecutive `> ` lines in the trigger comment, one entry per quoted block.
+fn quotes(body: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current = Vec::new();
+    for line in body.lines().chain(std::iter::once("")) {
+        match line.trim_start().strip_prefix('>') {
+            Some(quoted) => current.push(quoted.trim().to_string()),
+            None if !current.is_empty() => {
+                let block = current.join("\n").trim().to_string();
+                if !block.is_empty() {
+                    blocks.push(block);
+                }
+                current.clear();
+            }
+            None => {}
+        }
+    }
+    blocks
+}
+
+/// Resolves the quotes and permalinks in a trigger comment back to the comments they came
+/// from. Quotes that match no comment are kept unattributed; permalinks to other threads are
+/// ignored. An empty result means the whole thread is in scope.
+pub async fn resolve(config: &Config, octo: &Octocrab, issue: &Issue, body: &str, trigger: Option<CommentId>) -> Vec<Excerpt> {
+    let quoted = quotes(body);
+    let links = PERMALINK
+        .captures_iter(body)
+        .filter(|c| c[1].eq_ignore_ascii_case(&config.owner) && c[2].eq_ignore_ascii_case(&config.repo) && c[3] == issue.number.to_string())
+        .map(|c| c.get(5).and_then(|id| id.as_str().parse::<u64>().ok()))
+        .collect::<Vec<Option<u64>>>();
+    if quoted.is_empty() && links.is_empty() {
+        return Vec::new();
+    }
+
+    let issues = octo.issues(&config.owner, &config.repo);
+    let mut excerpts = Vec::new();
+
+    for link in links {
+        match link {
+            None => excerpts.push(Excerpt {
+                author: Some(Author::from_login(&issue.user.login).to_string()),
+                url: Some(issue.html_url.to_string()),
+                text: issue.body.clone().unwrap_or_default(),
+            }),
+            Some(id) => match issues.get_comment(CommentId(id)).await {
+                Ok(comment) => excerpts.push(Excerpt {
+                    author: Some(Author::from_login(&comment.user.login).to_string()),
+                    url: Some(comment.html_url.to_string()),
+                    text: comment.body.unwrap_or_default(),
+                }),
+                Err(error) => log::warn!("Could not fetch linked comment {}: {}", id, error),
+            },
+        }
+    }
+
+    if !quoted.is_empty() {
+        let comments = match issues.list_comments(issue.number).per_page(100).send().await {
+            Ok(page) => page.items,
+            Err(error) => {
+                log::warn!("Could not list comments of #{} to attribute quotes: {}", issue.number, error);
+                Vec::new()
+            }
+        };
+        for quote in quoted {
+            let needle = normalize(&quote);
+            let source = comments
+                .iter()
+                .filter(|c| Some(c.id) != trigger)
+                .find(|c| normalize(c.body.as_deref().unwrap_or_default()).contains(&needle));
+            let excerpt = match source {
+                Some(comment) => Excerpt {
+                    author: Some(Author::from_login(&comment.user.login).to_string()),
+                    url: Some(comment.html_url.to_string()),
+                    text: quote,
+                },
+                None if normalize(issue.body.as_deref().unwrap_or_default()).contains(&needle) => Excerpt {
+                    author: Some(Author::from_login(&issue.user.login).to_string()),
+                    url: Some(issue.html_url.to_string()),
+                    text: quote,
+                },
+                None => Excerpt { author: None, url: None, text: quote },
+            };
+            excerpts.push(excerpt);
+        }
+    }
+
+    log::debug!("Summary of #{} scoped to {} excerpt(s)", issue.number, excerpts.len());
+    excerpts
+}
+
+/// The excerpts as an LLM prompt block, or an empty string when the whole thread is in scope.
+pub fn context_block(excerpts: &[Excerpt]) -> String {
+    excerpts
+        .iter()
+        .map(|e| match &e.author {
+            Some(author) => format!("{} wrote: {}\n", author, e.text),
+            None => format!("Quoted: {}\n", e.text),
+        })
+        .collect()
+}
+
+/// A footnote listing where the scoped excerpts came from.
+pub fn section(excerpts: &[Excerpt]) -> String {
+    if excerpts.is_empty() {
+        return String::new();
+    }
+    let sources = excerpts
+        .iter()
+        .map(|e| match (&e.author, &e.url) {
+            (Some(author), Some(url)) => format!("[{}]({})", author, url),
+            _ => "an unattributed quote".to_string(),
+        })
+        .collect::<Vec<String>>();
+    format!("\n\n_Scoped to {} quoted excerpt(s): {}._", excerpts.len(), sources.join(", "))
+}
@@ -0,0 +1,115 @@
+use crate::config::Config;
+use crate::{embeddings, llm};
+use github_flows::octocrab::Octocrab;
+use std::collections::HashMap;
+use std::env;
+
+/// Candidates taken from each source before merging.
+const CANDIDATES: usize = 10;
+/// Reciprocal rank fusion constant; dampens the advantage of the very top ranks.
+const RRF_K: f32 = 60.0;
+/// Reply to an empty query. It must not contain the phrase, or it would trigger itself.
+const USAGE: &str = "Usage: follow the search phrase with a `<query>`, e.g. `wasi-nn plugin crash`.";
+
+/// Returns the query following the search phrase on the same line.
+pub fn query<'a>(body: &'a str, phrase: &str) -> &'a str {
+    body.find(phrase)
+        .map(|i| &body[i + phrase.len()..])
+        .and_then(|rest| rest.lines().next())
+        .unwrap_or_default()
+        .trim()
+}
+
+/// Merges GitHub's keyword search with the semantic index using reciprocal rank fusion
+/// and replies with the top results, each with a one-line summary.
+pub async fn run(config: &Config, octo: &Octocrab, query: &str) -> anyhow::Result<String> {
+    if query.is_empty() {
+        return Ok(USAGE.to_string());
+    }
+    let limit = env::var("search_results").ok().and_then(|n| n.parse::<usize>().ok()).unwrap_or(5);
+
+    let mut scores: HashMap<u64, f32> = HashMap::new();
+    let keyword = octo
+        .search()
+        .issues_and_pull_requests(&format!("repo:{}/{} is:issue {}", config.owner, config.repo, query))
+        .per_page(CANDIDATES as u8)
+        .send()
+        .await?
+        .items;
+    for (rank, issue) in keyword.iter().enumerate() {
+        *scores.entry(issue.number).or_default() += 1.0 / (RRF_K + rank as f32 + 1.0);
+    }
+
+    match embeddings::search(config, query, CANDIDATES).await {
+        Ok(semantic) => {
+            for (rank, (number, _, _)) in semantic.iter().enumerate() {
+                *scores.entry(*number).or_default() += 1.0 / (RRF_K + rank as f32 + 1.0);
+            }
+        }
+        Err(error) => log::debug!("Semantic search unavailable: {}", error),
+    }
+
+    let mut ranked = scores.into_iter().collect::<Vec<(u64, f32)>>();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.truncate(limit);
+    if ranked.is_empty() {
+        return Ok(format!("No issues found for `{}`.", query));
+    }
+
+    let issues = octo.issues(&config.owner, &config.repo);
+    let mut found = Vec::new();
+    for (number, _) in &ranked {
+        match issues.get(*number).await {
+            Ok(issue) => found.push(issue),
+            Err(error) => log::warn!("Could not fetch search result #{}: {}", number, error),
+        }
+    }
+
+    let listing = found
+        .iter()
+        .map(|i| {
+            let body = i.body.clone().unwrap_or_default().chars().take(500).collect::<String>();
+            format!("#{} '{}': {}", i.number, i.title, body)
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n");
+    let sys_prompt = "You write one-line summaries of GitHub issues. Reply only with JSON mapping each issue number to a summary of at most 20 words, e.g. {\"123\": \"...\"}.";
+    let summaries = match llm::chat(&config.llm.profile("search"), &format!("search_{}", query), sys_prompt, &listing, 512).await {
+        Ok(reply) => llm::extract_json(&reply).unwrap_or_default(),
+        Err(error) => {
+            log::warn!("Could not summarize search results: {}", error);
+            Default::default()
+        }
+    };
+
+    let lines = found
+        .iter()
+        .map(|i| {
+            let state = format!("{:?}", i.state).to_lowercase();
+            match summaries[i.number.to_string()].as_str() {
+                Some(summary) => format!("* #{} **{}** ({}): {}", i.number, i.title, state, summary),
+                None => format!("* #{} **{}** ({})", i.number, i.title, state),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    Ok(format!("🔎 Issues matching `{}`:\n\n{}", query, lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_reads_the_rest_of_the_line() {
+        assert_eq!(query("Try @flows_search  wasi-nn crash \nthanks", "@flows_search"), "wasi-nn crash");
+        assert_eq!(query("@flows_search", "@flows_search"), "");
+        assert_eq!(query("no phrase here", "@flows_search"), "");
+    }
+
+    #[test]
+    fn usage_does_not_trigger_itself() {
+        assert!(!USAGE.contains("@flows_search"));
+        assert_eq!(query(USAGE, "@flows_search"), "");
+    }
+}
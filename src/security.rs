@@ -0,0 +1,128 @@
+use crate::audit;
+use crate::config::Config;
+use crate::confidence;
+use crate::llm;
+use crate::mirror;
+use crate::mutations;
+use crate::notify;
+use crate::outbox::Outbox;
+use crate::permissions;
+use crate::private;
+use crate::repo_config::{RepoConfig, SecurityConfig};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::json;
+
+lazy_static! {
+    static ref CVE: Regex = Regex::new(r"(?i)\bCVE-\d{4}-\d{4,}\b").unwrap();
+    static ref SIGNAL: Regex = Regex::new(
+        r"(?i)\b(rce|remote code execution|arbitrary code execution|(?:buffer|heap|stack|integer) overflow|use[- ]after[- ]free|double free|out[- ]of[- ]bounds (?:read|write)|memory corruption|sandbox escape|privilege escalation|poc|proof[- ]of[- ]concept|exploit\w*|vulnerabilit(?:y|ies))\b"
+    )
+    .unwrap();
+}
+
+const DEFAULT_TEMPLATE: &str = "Thanks for the report, @{author}. This issue may describe a security vulnerability, so the bot will not analyze it in public. Please report vulnerabilities privately following {policy} and avoid posting further exploit details here; the maintainers have been notified.";
+
+/// Distinct security signals in `text`. A CVE id counts double.
+fn signals(text: &str) -> Vec<String> {
+    let mut found = SIGNAL
+        .find_iter(text)
+        .map(|m| m.as_str().to_lowercase())
+        .collect::<Vec<String>>();
+    found.sort();
+    found.dedup();
+    if let Some(cve) = CVE.find(text) {
+        found.insert(0, cve.as_str().to_uppercase());
+        found.insert(0, "cve".to_string());
+    }
+    found
+}
+
+/// Asks the LLM whether the report describes a vulnerability in WasmEdge itself, rather
+/// than e.g. a crash in the reporter's own Wasm program.
+async fn confirm(config: &Config, issue: &Issue, text: &str) -> Option<(String, f64)> {
+    let sys_prompt = format!(
+        "You screen GitHub issues filed against the WasmEdge runtime for security vulnerabilities in WasmEdge itself (memory safety bugs in the runtime, sandbox escapes, code execution, known CVEs, exploit proofs of concept). Bugs in the reporter's own guest program are not vulnerabilities. Reply only with JSON: {{\"security\": <bool>, \"reason\": \"<one sentence>\", \"confidence\": <0-1>}}. {}",
+        confidence::INSTRUCTION
+    );
+    let reply = llm::chat(&config.llm.profile("security"), &format!("security_{}", issue.html_url), &sys_prompt, text, 96)
+        .await
+        .ok()?;
+    let value = llm::extract_json(&reply)?;
+    if value["security"].as_bool() != Some(true) {
+        return None;
+    }
+    Some((value["reason"].as_str().unwrap_or_default().to_string(), confidence::parse(&value)))
+}
+
+/// Decides whether an issue is security-sensitive. Without the LLM, two distinct signals
+/// (or a CVE id) are enough.
+async fn detect(config: &Config, security: &SecurityConfig, issue: &Issue) -> Option<String> {
+    let text = format!("{}\n{}", issue.title, issue.body.clone().unwrap_or_default());
+    let found = signals(&text);
+    if found.is_empty() {
+        return None;
+    }
+    if !security.use_llm {
+        return (found.len() >= 2).then(|| format!("mentions {}", found.join(", ")));
+    }
+    // The cost of a missed report is high, so a lower bar than other classifiers applies.
+    match confirm(config, issue, &text).await {
+        Some((reason, confidence)) if confidence >= 0.5 => Some(reason),
+        _ => None,
+    }
+}
+
+/// Whether the issue went through the security fast path.
+pub fn is_flagged(security: &SecurityConfig, issue: &Issue) -> bool {
+    issue.labels.iter().any(|l| l.name == security.label)
+}
+
+/// Public reply pointing to the private disclosure process.
+pub fn disclosure_notice(config: &Config, security: &SecurityConfig, author: &str) -> String {
+    let policy = security
+        .policy_url
+        .clone()
+        .unwrap_or(format!("https://github.com/{}/{}/security/policy", config.owner, config.repo));
+    security
+        .template
+        .as_deref()
+        .unwrap_or(DEFAULT_TEMPLATE)
+        .replace("{author}", author)
+        .replace("{policy}", &policy)
+}
+
+/// Screens a new issue; security-sensitive reports are labeled, answered with the
+/// disclosure notice and reported to maintainers privately. Returns true when the issue
+/// took the fast path and must not be summarized publicly.
+pub async fn screen_issue(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, outbox: &Outbox, issue: &Issue) -> bool {
+    let security = &repo_config.security;
+    let Some(reason) = detect(config, security, issue).await else {
+        return false;
+    };
+    log::info!("Issue #{} looks security-sensitive: {}", issue.number, reason);
+
+    if let Err(error) = mutations::add_labels(config, octo, issue.number, &[security.label.clone()]).await {
+        permissions::check(config, octo, "label security reports", permissions::ISSUES_WRITE, &error).await;
+    }
+    if let Err(error) = outbox.post(config, octo, issue.number, &disclosure_notice(config, security, &issue.user.login)).await {
+        log::error!("Error posting disclosure notice on #{}: {}", issue.number, error);
+    }
+
+    // The report only ever goes to a private channel or the mirror; without either the
+    // maintainers hear about it through the notification sinks alone.
+    let note = format!(
+        "🔒 **Possible security report** by @{}\n\n{}\n\nThe public issue was labeled `{}` and the reporter pointed to the disclosure process.",
+        issue.user.login, reason, security.label
+    );
+    if !private::deliver(config, repo_config, octo, issue.number, "security", &note).await && mirror::is_private(&repo_config.mirror, "security") {
+        if let Err(error) = mirror::post(config, &repo_config.mirror, octo, outbox, issue, "security", &note).await {
+            log::error!("Error posting security report #{} to the mirror: {}", issue.number, error);
+        }
+    }
+    let headline = format!("Possible security report #{}: {}", issue.number, issue.title);
+    notify::forward(config, &headline, issue.html_url.as_str(), &reason).await;
+    audit::record(config, "bot", "security_fast_path", Some(issue.number), json!({ "reason": reason }));
+    true
+}
@@ -0,0 +1,141 @@
+use crate::author::Author;
+use crate::clarify;
+use crate::config::Config;
+use crate::confidence;
+use crate::footer;
+use crate::llm;
+use crate::mirror;
+use crate::mutations;
+use crate::notify;
+use crate::outbox::Outbox;
+use crate::permissions;
+use crate::repo_config::RepoConfig;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+
+const PRIORITY_PREFIX: &str = "priority/";
+
+pub struct Classification {
+    pub priority: String,
+    pub category: String,
+    pub reason: String,
+    pub confidence: f64,
+    /// Information the classifier said would change its rating.
+    pub missing: String,
+}
+
+/// Lower is more urgent; unknown labels sort last.
+fn rank(priority: &str) -> u8 {
+    priority
+        .trim_start_matches(PRIORITY_PREFIX)
+        .trim_start_matches('P')
+        .parse::<u8>()
+        .unwrap_or(u8::MAX)
+}
+
+/// Asks the LLM to rate the issue's severity, taking any follow-up Q&A into account.
+pub async fn classify(config: &Config, issue: &Issue, followup: Option<&str>) -> Option<Classification> {
+    let sys_prompt = format!(
+        "You triage GitHub issues for the WasmEdge project. Rate severity: P0 = crash, data loss or security vulnerability affecting many users; P1 = major functionality broken without workaround; P2 = bug with a workaround or limited impact; P3 = cosmetic, docs or minor enhancement. Reply only with JSON: {{\"priority\": \"P0|P1|P2|P3\", \"category\": \"crash|data-loss|security|functional|performance|cosmetic|docs|enhancement\", \"reason\": \"<one sentence>\", \"confidence\": <0-1>, \"missing\": \"<information that would change your rating, or empty>\"}}. {}",
+        confidence::INSTRUCTION
+    );
+    let usr_prompt = format!(
+        "Issue titled '{}' by '{}':\n{}\n{}",
+        issue.title,
+        Author::from_login(&issue.user.login),
+        issue.body.clone().unwrap_or_default(),
+        followup.unwrap_or_default()
+    );
+
+    let reply = match llm::chat(&config.llm.profile("severity"), &format!("severity_{}", issue.html_url), &sys_prompt, &usr_prompt, 128).await {
+        Ok(reply) => reply,
+        Err(error) => {
+            log::error!("Error classifying severity of #{}: {}", issue.number, error);
+            return None;
+        }
+    };
+    let value = llm::extract_json(&reply)?;
+    Some(Classification {
+        priority: value["priority"].as_str()?.to_uppercase(),
+        category: value["category"].as_str().unwrap_or("unknown").to_string(),
+        reason: value["reason"].as_str().unwrap_or_default().to_string(),
+        confidence: confidence::parse(&value),
+        missing: value["missing"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+/// Classifies a new issue and applies the matching `priority/Px` label. Only labels in the
+/// allowlist are applied, and an existing priority label is never replaced by a less
+/// urgent one. When the rating is uncertain for lack of information the reporter is asked a
+/// clarifying question; other low-confidence ratings are queued for human triage. P0s ping the
+/// configured on-call handle, on the private mirror if severity analyses are team-only.
+pub async fn triage(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue, outbox: &Outbox) {
+    run(config, repo_config, octo, issue, outbox, None).await;
+}
+
+/// Re-runs triage after the reporter answered a clarifying question. If the rating is still
+/// uncertain the issue goes to the human triage queue rather than asking again.
+pub async fn retriage(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue, outbox: &Outbox, followup: &str) {
+    run(config, repo_config, octo, issue, outbox, Some(followup)).await;
+}
+
+async fn run(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue, outbox: &Outbox, followup: Option<&str>) {
+    let severity = &repo_config.severity;
+    let Some(classification) = classify(config, issue, followup).await else {
+        return;
+    };
+    let label = format!("{}{}", PRIORITY_PREFIX, classification.priority);
+    if !severity.allowed_labels.iter().any(|l| *l == label) {
+        log::warn!("Classifier proposed non-allowlisted label '{}' for #{}", label, issue.number);
+        return;
+    }
+    if !confidence::is_confident(repo_config, "severity", classification.confidence) {
+        if followup.is_none() && repo_config.confidence.clarify && !classification.missing.is_empty() {
+            clarify::ask(config, octo, outbox, issue, "severity", &classification.missing).await;
+            return;
+        }
+        let proposal = format!("{} ({}): {}", label, classification.category, classification.reason);
+        confidence::abstain(config, repo_config, octo, issue.number, "severity", &proposal, classification.confidence).await;
+        return;
+    }
+
+    let existing = issue
+        .labels
+        .iter()
+        .filter(|l| l.name.starts_with(PRIORITY_PREFIX))
+        .map(|l| l.name.clone())
+        .collect::<Vec<String>>();
+    if existing.iter().any(|l| rank(l) <= rank(&label)) {
+        log::info!("Keeping existing priority {:?} on #{} (classifier said {})", existing, issue.number, label);
+        return;
+    }
+
+    if let Err(error) = mutations::add_labels(config, octo, issue.number, &[label.clone()]).await {
+        permissions::check(config, octo, "apply priority labels", permissions::ISSUES_WRITE, &error).await;
+        return;
+    }
+    for old in &existing {
+        if let Err(error) = mutations::remove_label(config, octo, issue.number, old).await {
+            log::error!("Error removing {} from #{}: {}", old, issue.number, error);
+        }
+    }
+    log::info!("Labeled #{} as {} ({})", issue.number, label, classification.category);
+    if !mirror::is_private(&repo_config.mirror, "severity") {
+        let headline = format!("#{} triaged as {}: {}", issue.number, label, issue.title);
+        let text = format!("{} ({})", classification.reason, classification.category);
+        notify::forward(config, &headline, issue.html_url.as_str(), &text).await;
+    }
+
+    if classification.priority == "P0" {
+        let mention = severity.oncall.as_deref().map(|o| format!("{} ", o)).unwrap_or_default();
+        let resp = format!(
+            "{}This issue was classified as **P0** ({}): {}{}",
+            mention,
+            classification.category,
+            classification.reason,
+            footer::render(config, None)
+        );
+        if let Err(error) = mirror::post(config, &repo_config.mirror, octo, outbox, issue, "severity", &resp).await {
+            log::error!("Error posting P0 notification: {}", error);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use crate::audit;
+use crate::config::Config;
+use crate::confidence;
+use crate::dry_run;
+use crate::llm;
+use crate::mutations;
+use crate::outbox;
+use crate::permissions;
+use crate::private;
+use crate::repo_config::{RepoConfig, SpamConfig};
+use github_flows::octocrab::Octocrab;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{json, Value};
+
+lazy_static! {
+    static ref LINK: Regex = Regex::new(r"https?://([^/\s)]+)").unwrap();
+    static ref CRYPTO: Regex = Regex::new(
+        r"(?i)\b(airdrop|giveaway|usdt|wallet connect|seed phrase|metamask|claim your|crypto (?:recovery|investment)|telegram|whatsapp|binance|forex)\b"
+    )
+    .unwrap();
+}
+
+/// Heuristic score at or above which content is treated as spam without asking the LLM.
+const CERTAIN: u32 = 4;
+/// Heuristic score at or above which the LLM is consulted.
+const SUSPICIOUS: u32 = 2;
+
+#[derive(Debug)]
+pub struct Verdict {
+    pub category: String,
+    pub reason: String,
+    pub confidence: f64,
+}
+
+fn heuristic_score(text: &str) -> u32 {
+    let mut domains = LINK
+        .captures_iter(text)
+        .map(|c| c[1].to_lowercase())
+        .filter(|d| !d.ends_with("github.com") && !d.ends_with("githubusercontent.com"))
+        .collect::<Vec<String>>();
+    let links = domains.len() as u32;
+    domains.sort();
+    domains.dedup();
+
+    let mut score = 0;
+    score += (domains.len() as u32).saturating_sub(2);
+    if links > 5 {
+        score += 2;
+    }
+    score += 2 * CRYPTO.find_iter(text).count().min(2) as u32;
+    score
+}
+
+async fn ask_llm(config: &Config, id: &str, text: &str) -> Option<Verdict> {
+    let sys_prompt = format!(
+        "You moderate a GitHub repository for an open source runtime. Decide whether the content is spam (crypto scams, link farms, SEO or ads) or abuse (harassment, slurs), as opposed to a genuine technical contribution. Reply only with JSON: {{\"spam\": <bool>, \"category\": \"crypto|link-farm|abuse|none\", \"reason\": \"<short>\", \"confidence\": <0-1>}}. {}",
+        confidence::INSTRUCTION
+    );
+    let reply = llm::chat(&config.llm.profile("spam"), &format!("spam_{}", id), &sys_prompt, text, 96).await.ok()?;
+    let value = llm::extract_json(&reply)?;
+    if value["spam"].as_bool() != Some(true) {
+        return None;
+    }
+    Some(Verdict {
+        category: value["category"].as_str().unwrap_or("spam").to_string(),
+        reason: value["reason"].as_str().unwrap_or_default().to_string(),
+        confidence: confidence::parse(&value),
+    })
+}
+
+/// Classifies `text`: obvious spam is decided by heuristics alone, borderline content is
+/// confirmed with the LLM when enabled.
+pub async fn classify(config: &Config, spam: &SpamConfig, id: &str, text: &str) -> Option<Verdict> {
+    let score = heuristic_score(text);
+    if score >= CERTAIN {
+        return Some(Verdict {
+            category: "heuristic".to_string(),
+            reason: format!("heuristic score {}", score),
+            confidence: 1.0,
+        });
+    }
+    if spam.use_llm && (score >= SUSPICIOUS || spam.llm_all) {
+        return ask_llm(config, id, text).await;
+    }
+    None
+}
+
+/// Hides a comment through the GraphQL `minimizeComment` mutation.
+async fn minimize(config: &Config, octo: &Octocrab, node_id: &str, category: &str) -> anyhow::Result<()> {
+    let classifier = if category == "abuse" { "ABUSE" } else { "SPAM" };
+    if dry_run::intercept(config, "minimize_comment", None, json!({ "node_id": node_id, "classifier": classifier })) {
+        return Ok(());
+    }
+    let resp: Value = octo
+        .graphql(&json!({
+            "query": "mutation($id: ID!, $classifier: ReportedContentClassifiers!) { minimizeComment(input: {subjectId: $id, classifier: $classifier}) { minimizedComment { isMinimized } } }",
+            "variables": { "id": node_id, "classifier": classifier },
+        }))
+        .await?;
+    if let Some(errors) = resp.get("errors") {
+        anyhow::bail!("GraphQL errors: {}", errors);
+    }
+    Ok(())
+}
+
+/// Tells maintainers about a flagged item, privately when `spam` is routed to a private
+/// channel and otherwise by mentioning `spam.notify` on the thread.
+async fn notify(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue_number: u64, what: &str, verdict: &Verdict) {
+    let note = format!("{} was flagged as {} ({}).", what, verdict.category, verdict.reason);
+    if private::deliver(config, repo_config, octo, issue_number, "spam", &note).await {
+        return;
+    }
+    let Some(mention) = &repo_config.spam.notify else {
+        return;
+    };
+    let resp = format!("{} {} was flagged as {} ({}). Please review.", mention, what, verdict.category, verdict.reason);
+    if let Err(error) = outbox::reply(config, octo, issue_number, &resp).await {
+        log::error!("Error notifying maintainers about spam: {}", error);
+    }
+}
+
+/// Screens a new comment; returns true if it was flagged and hidden. Uncertain verdicts
+/// are queued for human triage and the comment is left alone.
+pub async fn screen_comment(
+    config: &Config,
+    repo_config: &RepoConfig,
+    octo: &Octocrab,
+    issue_number: u64,
+    author: &str,
+    node_id: &str,
+    body: &str,
+) -> bool {
+    let spam = &repo_config.spam;
+    if permissions::is_maintainer(config, octo, author).await {
+        return false;
+    }
+    let Some(verdict) = classify(config, spam, node_id, body).await else {
+        return false;
+    };
+    if !confidence::is_confident(repo_config, "spam", verdict.confidence) {
+        let proposal = format!("comment by @{} is {} ({})", author, verdict.category, verdict.reason);
+        confidence::abstain(config, repo_config, octo, issue_number, "spam", &proposal, verdict.confidence).await;
+        return false;
+    }
+
+    log::info!("Comment by {} on #{} flagged as {:?}", author, issue_number, verdict);
+    if let Err(error) = minimize(config, octo, node_id, &verdict.category).await {
+        permissions::check(config, octo, "hide spam comments", permissions::ISSUES_WRITE, &error).await;
+    }
+    audit::record(config, "spam-filter", "comment_minimized", Some(issue_number), json!({ "author": author, "category": verdict.category, "reason": verdict.reason }));
+    notify(config, repo_config, octo, issue_number, &format!("A comment by @{}", author), &verdict).await;
+    true
+}
+
+/// Screens a new issue; returns true if it was flagged and labeled.
+pub async fn screen_issue(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue_number: u64, author: &str, text: &str) -> bool {
+    let spam = &repo_config.spam;
+    if permissions::is_maintainer(config, octo, author).await {
+        return false;
+    }
+    let Some(verdict) = classify(config, spam, &format!("{}/{}#{}", config.owner, config.repo, issue_number), text).await else {
+        return false;
+    };
+    if !confidence::is_confident(repo_config, "spam", verdict.confidence) {
+        let proposal = format!("issue is {} ({})", verdict.category, verdict.reason);
+        confidence::abstain(config, repo_config, octo, issue_number, "spam", &proposal, verdict.confidence).await;
+        return false;
+    }
+
+    log::info!("Issue #{} by {} flagged as {:?}", issue_number, author, verdict);
+    if let Err(error) = mutations::add_labels(config, octo, issue_number, &[spam.label.clone()]).await {
+        permissions::check(config, octo, "label spam issues", permissions::ISSUES_WRITE, &error).await;
+    }
+    audit::record(config, "spam-filter", "issue_labeled_spam", Some(issue_number), json!({ "author": author, "category": verdict.category, "reason": verdict.reason }));
+    notify(config, repo_config, octo, issue_number, "This issue", &verdict).await;
+    true
+}
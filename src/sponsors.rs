@@ -0,0 +1,90 @@
+use crate::config::Config;
+use crate::repo_config::{RepoConfig, SponsorTier};
+use crate::store;
+use github_flows::octocrab::Octocrab;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::env;
+
+const SPONSORS_QUERY: &str = r#"
+query($owner: String!, $cursor: String) {
+  repositoryOwner(login: $owner) {
+    ... on Sponsorable {
+      sponsorshipsAsMaintainer(first: 100, after: $cursor, includePrivate: true, activeOnly: true) {
+        pageInfo { hasNextPage endCursor }
+        nodes {
+          sponsorEntity {
+            ... on User { login }
+            ... on Organization { login }
+          }
+          tier { monthlyPriceInDollars }
+        }
+      }
+    }
+  }
+}"#;
+
+/// Monthly amounts of every active sponsor of `owner`, keyed by lowercase login. The list is
+/// paged through once per `sponsors_cache_secs` (an hour by default) and cached.
+async fn sponsor_map(config: &Config, octo: &Octocrab, owner: &str) -> Option<HashMap<String, u64>> {
+    let key = format!("sponsors:{}", owner.to_lowercase());
+    if let Some(map) = store::get(config, &key).and_then(|v| serde_json::from_value::<HashMap<String, u64>>(v).ok()) {
+        return Some(map);
+    }
+
+    let mut map = HashMap::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let resp: Value = match octo
+            .graphql(&json!({ "query": SPONSORS_QUERY, "variables": { "owner": owner, "cursor": cursor } }))
+            .await
+        {
+            Ok(resp) => resp,
+            Err(error) => {
+                log::error!("Error querying sponsors of {}: {}", owner, error);
+                return None;
+            }
+        };
+
+        let sponsorships = &resp["data"]["repositoryOwner"]["sponsorshipsAsMaintainer"];
+        for node in sponsorships["nodes"].as_array().into_iter().flatten() {
+            if let Some(login) = node["sponsorEntity"]["login"].as_str() {
+                map.insert(login.to_lowercase(), node["tier"]["monthlyPriceInDollars"].as_u64().unwrap_or(0));
+            }
+        }
+
+        if !sponsorships["pageInfo"]["hasNextPage"].as_bool().unwrap_or(false) {
+            break;
+        }
+        cursor = sponsorships["pageInfo"]["endCursor"].as_str().map(|c| c.to_string());
+    }
+
+    let ttl = env::var("sponsors_cache_secs").ok().and_then(|n| n.parse::<i64>().ok()).unwrap_or(3600);
+    store::set(config, &key, json!(map), Some(ttl));
+    Some(map)
+}
+
+/// Returns the monthly sponsorship amount `login` pays to the repo owner, or `None`
+/// if they are not an active sponsor. Requires the token to see the owner's sponsors.
+pub async fn monthly_amount(config: &Config, octo: &Octocrab, login: &str) -> Option<u64> {
+    sponsor_map(config, octo, &config.owner).await?.get(&login.to_lowercase()).copied()
+}
+
+/// Picks the highest configured tier whose threshold the amount reaches.
+pub fn tier_for_amount(repo_config: &RepoConfig, amount: u64) -> Option<&SponsorTier> {
+    repo_config
+        .sponsor_tiers
+        .iter()
+        .filter(|t| amount >= t.min_monthly_dollars)
+        .max_by_key(|t| t.min_monthly_dollars)
+}
+
+/// Resolves the response tier for `login`, the issue author on new issues and the requester
+/// on summaries, skipping the API call when no tiers are configured.
+pub async fn tier_for_user<'a>(config: &Config, octo: &Octocrab, repo_config: &'a RepoConfig, login: &str) -> Option<&'a SponsorTier> {
+    if repo_config.sponsor_tiers.is_empty() {
+        return None;
+    }
+    let amount = monthly_amount(config, octo, login).await?;
+    tier_for_amount(repo_config, amount)
+}
@@ -0,0 +1,87 @@
+use crate::config::Config;
+use crate::{audit, gist, store};
+use github_flows::octocrab::Octocrab;
+use serde_json::{json, Map, Value};
+
+/// The snapshot as a portable document, tagged with its repository.
+fn document(config: &Config, values: &Map<String, Value>) -> Value {
+    json!({
+        "repo": format!("{}/{}", config.owner, config.repo),
+        "taken_at": chrono::Utc::now().to_rfc3339(),
+        "keys": values,
+    })
+}
+
+/// Exports a snapshot to a secret gist, returning a line for the reply.
+async fn export(config: &Config, octo: &Octocrab, values: &Map<String, Value>) -> String {
+    let filename = format!("{}-{}-bot-state.json", config.owner, config.repo);
+    let content = serde_json::to_string_pretty(&document(config, values)).unwrap_or_default();
+    let description = format!("Issue bot state snapshot for {}/{}", config.owner, config.repo);
+    match gist::create(config, octo, &description, &[(filename, content)]).await {
+        Ok(url) => format!("Exported to {}.", url),
+        Err(error) => {
+            log::warn!("Could not export state snapshot: {}", error);
+            "It could not be exported to a gist, but is kept in the bot's store for `state restore`.".to_string()
+        }
+    }
+}
+
+/// Loads the `keys` of a snapshot exported to the gist `id` (or its URL).
+async fn import(config: &Config, octo: &Octocrab, id: &str) -> anyhow::Result<Map<String, Value>> {
+    let id = id.trim_end_matches('/').rsplit('/').next().unwrap_or(id);
+    let gist = octo.get::<Value, _, ()>(format!("/gists/{}", id), None).await?;
+    let file = gist["files"]
+        .as_object()
+        .and_then(|files| files.values().next().cloned())
+        .ok_or(anyhow::anyhow!("gist {} has no files", id))?;
+    let content = match (file["truncated"].as_bool(), file["raw_url"].as_str()) {
+        (Some(true), Some(raw_url)) => reqwest::get(raw_url).await?.text().await?,
+        _ => file["content"].as_str().unwrap_or_default().to_string(),
+    };
+    let snapshot = serde_json::from_str::<Value>(&content)?;
+    let repo = format!("{}/{}", config.owner, config.repo);
+    if !snapshot["repo"].as_str().map_or(false, |r| r.eq_ignore_ascii_case(&repo)) {
+        anyhow::bail!("the snapshot in gist {} is not for {}", id, repo);
+    }
+    snapshot["keys"].as_object().cloned().ok_or(anyhow::anyhow!("gist {} holds no snapshot", id))
+}
+
+/// `state snapshot|wipe|restore [gist]`: saves, clears or restores everything the bot has
+/// persisted for this repository. Expiring caches and quotas are left to lapse. A wipe
+/// snapshots first, so it can always be undone.
+pub async fn run(config: &Config, octo: &Octocrab, requester: &str, args: &str) -> anyhow::Result<String> {
+    let mut words = args.split_whitespace();
+    match words.next().unwrap_or_default() {
+        "snapshot" => {
+            let values = store::snapshot(config);
+            audit::record(config, requester, "state_snapshot", None, json!({ "keys": values.len() }));
+            Ok(format!("Snapshot of {} key(s) taken. {}", values.len(), export(config, octo, &values).await))
+        }
+        "wipe" => {
+            if config.dry_run {
+                return Ok("Bot state is not wiped in dry-run mode.".to_string());
+            }
+            let values = store::snapshot(config);
+            let exported = export(config, octo, &values).await;
+            let deleted = store::wipe(config, &values);
+            audit::record(config, requester, "state_wipe", None, json!({ "keys": deleted }));
+            Ok(format!("Wiped {} key(s) of bot state. The snapshot taken first can be restored with `state restore`. {}", deleted, exported))
+        }
+        "restore" => {
+            let values = match words.next() {
+                Some(gist) => import(config, octo, gist).await?,
+                None => match store::last_snapshot(config) {
+                    Some(values) => values,
+                    None => return Ok("There is no snapshot to restore. Pass the gist a snapshot was exported to.".to_string()),
+                },
+            };
+            if config.dry_run {
+                return Ok(format!("Would restore {} key(s) of bot state; nothing is written in dry-run mode.", values.len()));
+            }
+            let restored = store::restore(config, &values);
+            audit::record(config, requester, "state_restore", None, json!({ "keys": restored }));
+            Ok(format!("Restored {} key(s) of bot state. Expiring keys were restored without their expiry.", restored))
+        }
+        _ => Ok("Usage: `state snapshot`, `state wipe` or `state restore [gist]`.".to_string()),
+    }
+}
@@ -0,0 +1,92 @@
+use crate::config::Config;
+use serde_json::{json, Map, Value};
+use store_flows::{Expire, ExpireKind};
+
+/// Names of every persistent key written for the repository. The store can't list keys,
+/// so this is what snapshots and wipes walk. Expiring keys (caches, quotas) are left out
+/// so they don't cost an index update on every write.
+const INDEX_KEY: &str = "store:index";
+/// The last snapshot, kept outside the index so a wipe doesn't remove it.
+const SNAPSHOT_KEY: &str = "store:snapshot";
+
+/// Reads a value from the flow's key-value store, scoped to the config's repository.
+pub fn get(config: &Config, name: &str) -> Option<Value> {
+    store_flows::get(&config.state_key(name))
+}
+
+/// Writes a repository-scoped value, optionally expiring after `ttl_secs`.
+pub fn set(config: &Config, name: &str, value: Value, ttl_secs: Option<i64>) {
+    let expire = ttl_secs.map(|secs| Expire {
+        kind: ExpireKind::Ex,
+        value: secs,
+    });
+    store_flows::set(&config.state_key(name), value, expire);
+    if ttl_secs.is_none() {
+        register(config, name);
+    }
+}
+
+pub fn del(config: &Config, name: &str) {
+    store_flows::del(&config.state_key(name));
+    let mut names = index(config);
+    if names.iter().any(|n| n == name) {
+        names.retain(|n| n != name);
+        store_flows::set(&config.state_key(INDEX_KEY), json!(names), None);
+    }
+}
+
+fn index(config: &Config) -> Vec<String> {
+    get(config, INDEX_KEY)
+        .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+        .unwrap_or_default()
+}
+
+fn register(config: &Config, name: &str) {
+    if name == INDEX_KEY || name == SNAPSHOT_KEY {
+        return;
+    }
+    let mut names = index(config);
+    if names.iter().any(|n| n == name) {
+        return;
+    }
+    names.push(name.to_string());
+    store_flows::set(&config.state_key(INDEX_KEY), json!(names), None);
+}
+
+/// Every live persistent value written for the repository, keyed by name. Expired keys are dropped
+/// from the index along the way; remaining TTLs are not preserved.
+pub fn snapshot(config: &Config) -> Map<String, Value> {
+    let mut values = Map::new();
+    for name in index(config) {
+        if let Some(value) = get(config, &name) {
+            values.insert(name, value);
+        }
+    }
+    let live = values.keys().cloned().collect::<Vec<String>>();
+    store_flows::set(&config.state_key(INDEX_KEY), json!(live), None);
+    store_flows::set(&config.state_key(SNAPSHOT_KEY), json!(values), None);
+    values
+}
+
+/// The snapshot taken by the last [`snapshot`], including the one taken before a wipe.
+pub fn last_snapshot(config: &Config) -> Option<Map<String, Value>> {
+    get(config, SNAPSHOT_KEY).and_then(|v| v.as_object().cloned())
+}
+
+/// Deletes every value in `values`, a [`snapshot`] just taken so the wipe can be undone,
+/// along with the index. Returns how many were deleted.
+pub fn wipe(config: &Config, values: &Map<String, Value>) -> usize {
+    for name in values.keys() {
+        store_flows::del(&config.state_key(name));
+    }
+    store_flows::del(&config.state_key(INDEX_KEY));
+    values.len()
+}
+
+/// Writes every value in `values` back, without expiry.
+pub fn restore(config: &Config, values: &Map<String, Value>) -> usize {
+    for (name, value) in values {
+        set(config, name, value.clone(), None);
+    }
+    values.len()
+}
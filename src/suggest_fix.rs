@@ -0,0 +1,49 @@
+use crate::config::Config;
+use crate::summary;
+use crate::{code, llm};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+
+const MAX_FILES: usize = 3;
+const MAX_LINES: usize = 200;
+
+/// Proposes a patch for a simple issue from the files it references. Blob links are used
+/// as-is; bare paths are resolved against the default branch.
+pub async fn run(config: &Config, octo: &Octocrab, issue: &Issue) -> anyhow::Result<String> {
+    let context = summary::issue_context(octo, &config.owner, &config.repo, issue).await?;
+
+    let default_branch = octo
+        .repos(&config.owner, &config.repo)
+        .get()
+        .await?
+        .default_branch
+        .unwrap_or("main".to_string());
+    let mut refs = code::extract_links(&context);
+    refs.extend(code::extract_paths(&context, &config.owner, &config.repo, &default_branch));
+
+    let mut files = String::new();
+    for code_ref in refs.iter().take(MAX_FILES) {
+        if let Some(excerpt) = code::fetch_excerpt(octo, code_ref, MAX_LINES).await {
+            files.push_str(&format!("{}\n\n", excerpt));
+        }
+    }
+    if files.is_empty() {
+        return Ok(format!(
+            "No source files could be fetched for #{}. Reference them as blob links or repository paths such as `lib/foo.cpp:120` and try again.",
+            issue.number
+        ));
+    }
+
+    let sys_prompt = "You are a careful maintainer of the WasmEdge project proposing minimal fixes. Only change what the issue requires, and say so plainly if the provided code is not enough to fix it.";
+    let usr_prompt = format!(
+        "Issue thread:\n{}\n\nReferenced source (line numbers on the left):\n{}\n\nPropose a fix. Start with one or two sentences explaining the change, then give it as a unified diff in a ```diff block against the paths above.",
+        context, files
+    );
+    let proposal = llm::chat(&config.llm.profile("suggest_fix"), &format!("suggest_fix_{}", issue.html_url), sys_prompt, &usr_prompt, 1024)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    Ok(format!(
+        "### 🩹 Suggested fix for #{}\n\n{}\n\n> ⚠️ This patch is machine-generated and unverified. Review and test it before applying.",
+        issue.number, proposal
+    ))
+}
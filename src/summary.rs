@@ -0,0 +1,190 @@
+use crate::author::Author;
+use crate::graphql;
+use crate::llm::{self, LlmConfig};
+use crate::persona;
+use crate::pin;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use serde::Deserialize;
+
+/// How long and how thorough a summary should be.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Detail {
+    #[serde(alias = "tl;dr")]
+    Tldr,
+    Brief,
+    Full,
+}
+
+impl Detail {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "tl;dr" | "tldr" => Some(Detail::Tldr),
+            "brief" => Some(Detail::Brief),
+            "full" => Some(Detail::Full),
+            _ => None,
+        }
+    }
+
+    /// Reads `--detail <level>` from a trigger comment.
+    pub fn from_args(body: &str) -> Option<Self> {
+        let mut words = body.split_whitespace();
+        words.find(|w| *w == "--detail")?;
+        Detail::parse(words.next()?)
+    }
+
+    fn max_tokens(self) -> u16 {
+        match self {
+            Detail::Tldr => 64,
+            Detail::Brief => 192,
+            Detail::Full => 768,
+        }
+    }
+
+    fn instruction(self) -> &'static str {
+        match self {
+            Detail::Tldr => "Reply with a single-sentence TL;DR of the issue and its current status, under 40 tokens.",
+            Detail::Brief => "Aim for a succinct, analytical summary that stays under 128 tokens.",
+            Detail::Full => "Give a thorough analysis with short sections for the problem, the environment, what has been tried, the proposed solutions and open questions. Stay under 600 tokens.",
+        }
+    }
+
+    /// How many chunks of a long thread are summarized before the final pass.
+    fn max_chunks(self) -> usize {
+        match self {
+            Detail::Tldr => 2,
+            Detail::Brief => 4,
+            Detail::Full => 16,
+        }
+    }
+}
+
+/// Collects the issue post and its comments into a single block of text for the LLM.
+/// The thread is fetched with one batched GraphQL query where possible, falling back to
+/// REST if GraphQL is unavailable.
+pub async fn issue_context(octo: &Octocrab, owner: &str, repo: &str, issue: &Issue) -> anyhow::Result<String> {
+    match graphql::fetch_thread(octo, owner, repo, issue.number).await {
+        Ok(thread) => {
+            let mut all_text_from_issue = format!(
+                "User '{}', opened an issue titled '{}', labeled '{}', with the following post: '{}'.\n",
+                thread.author,
+                thread.title,
+                thread.labels.join(", "),
+                thread.body
+            );
+            for comment in thread.comments {
+                all_text_from_issue.push_str(&format!("{} commented: {}\n", comment.author, comment.body));
+            }
+            return Ok(all_text_from_issue);
+        }
+        Err(error) => log::warn!("GraphQL fetch of #{} failed, falling back to REST: {}", issue.number, error),
+    }
+
+    let labels = issue.labels.iter().map(|lab| lab.name.clone()).collect::<Vec<String>>().join(", ");
+    let mut all_text_from_issue = format!(
+        "User '{}', opened an issue titled '{}', labeled '{}', with the following post: '{}'.\n",
+        Author::from_login(&issue.user.login),
+        issue.title,
+        labels,
+        pin::strip(issue.body.as_deref().unwrap_or_default())
+    );
+
+    log::debug!("Fetching comments for issue #{}", issue.number);
+    let comments = octo.issues(owner, repo).list_comments(issue.number).per_page(100).send().await?.items;
+
+    for comment in comments {
+        let comment_body = comment.body.unwrap_or_else(String::new);
+        let commenter = Author::from_login(&comment.user.login);
+        all_text_from_issue.push_str(&format!("{} commented: {}\n", commenter, comment_body));
+    }
+
+    Ok(all_text_from_issue)
+}
+
+/// Splits `context` on line boundaries into chunks of at most `size` bytes.
+fn chunks(context: &str, size: usize) -> Vec<String> {
+    let mut out = vec![String::new()];
+    for line in context.lines() {
+        let current = out.last_mut().unwrap();
+        if !current.is_empty() && current.len() + line.len() > size {
+            out.push(String::new());
+        }
+        let current = out.last_mut().unwrap();
+        current.push_str(line);
+        current.push('\n');
+    }
+    out
+}
+
+/// Condenses a thread too long for one prompt: the opening post and the most recent chunks
+/// (up to the detail level's budget) are each summarized, and the notes replace the raw text.
+async fn condense(cfg: &LlmConfig, issue: &Issue, context: &str, detail: Detail) -> String {
+    // Leave room for the prompt and reply; roughly 3 bytes per token.
+    let chunk_size = (cfg.ctx_size as usize * 3) / 2;
+    if context.len() <= chunk_size {
+        return context.to_string();
+    }
+
+    let mut parts = chunks(context, chunk_size);
+    let max_chunks = detail.max_chunks();
+    if parts.len() > max_chunks {
+        let skipped = parts.len() - max_chunks;
+        log::debug!("Skipping {} middle chunk(s) of issue #{}", skipped, issue.number);
+        let tail = parts.split_off(parts.len() - (max_chunks - 1));
+        parts.truncate(1);
+        parts.extend(tail);
+    }
+
+    let mut notes = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        let usr_prompt = format!(
+            "This is part {} of {} of a GitHub issue thread: {}. Write concise notes on the problem, findings and proposed solutions in this part.",
+            i + 1,
+            parts.len(),
+            part
+        );
+        match llm::chat(cfg, &format!("{}#part{}", issue.html_url, i), "You take concise notes on GitHub issue threads.", &usr_prompt, 256).await {
+            Ok(note) => notes.push_str(&format!("Notes on part {}: {}\n", i + 1, note)),
+            Err(error) => log::error!("Error condensing part {} of issue #{}: {}", i + 1, issue.number, error),
+        }
+    }
+    notes
+}
+
+/// Asks the LLM for an analysis of the issue thread at the requested level of detail.
+pub async fn summarize(cfg: &LlmConfig, issue: &Issue, context: &str, detail: Detail) -> Result<String, String> {
+    summarize_focused(cfg, issue, context, "", detail).await
+}
+
+/// Like [`summarize`], but restricted to `focus`, the excerpts the requester quoted; the
+/// rest of the thread is only background. An empty `focus` covers the whole thread.
+pub async fn summarize_focused(cfg: &LlmConfig, issue: &Issue, context: &str, focus: &str, detail: Detail) -> Result<String, String> {
+    let cfg = &cfg.profile("summary");
+    let context = condense(cfg, issue, context, detail).await;
+
+    log::debug!("Preparing LLM prompts");
+    let sys_prompt = cfg.persona.apply(&format!(
+        "Given the information that user '{}' opened an issue titled '{}', your task is to deeply analyze the content of the issue posts. Distill the crux of the issue, the potential solutions suggested.",
+        Author::from_login(&issue.user.login),
+        issue.title
+    ));
+
+    let usr_prompt = if focus.is_empty() {
+        format!(
+            "Analyze the GitHub issue content: {}. Provide a concise analysis touching upon: The central problem discussed in the issue. The main solutions proposed or agreed upon. {}",
+            context,
+            detail.instruction()
+        )
+    } else {
+        format!(
+            "Background from the GitHub issue: {}.\n\nThe requester asked only about these excerpts:\n{}\nAnalyze only what the excerpts say: the problem or claim they raise and any solutions they propose or that the thread gives in reply to them. Use the background solely to interpret the excerpts and do not summarize the rest of the thread. {}",
+            context,
+            focus,
+            detail.instruction()
+        )
+    };
+
+    log::debug!("Generating {:?} summary with LLM", detail);
+    let reply = llm::chat(cfg, issue.html_url.as_str(), &sys_prompt, &usr_prompt, detail.max_tokens()).await?;
+    Ok(persona::enforce(cfg, issue.html_url.as_str(), reply, detail.max_tokens()).await)
+}
@@ -0,0 +1,146 @@
+use crate::author::Author;
+use crate::config::Config;
+use crate::llm;
+use crate::mutations;
+use crate::outbox::Outbox;
+use crate::permissions;
+use crate::repo_config::RepoConfig;
+use crate::store;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::json;
+
+lazy_static! {
+    static ref WORD: Regex = Regex::new(r"[\w'’]+").unwrap();
+}
+
+/// Words that say nothing about what went wrong.
+const GENERIC: [&str; 36] = [
+    "a", "an", "the", "it", "this", "i", "my", "me", "we", "is", "not", "no", "does", "doesn't", "doesnt", "don't", "can't", "cannot", "work",
+    "works", "working", "help", "please", "bug", "error", "errors", "issue", "problem", "question", "broken", "fail", "fails", "failed", "urgent",
+    "wasmedge", "need",
+];
+const MAX_TITLE_CHARS: usize = 100;
+const MAX_BODY_CHARS: usize = 4000;
+const SUGGESTION_TTL_SECS: i64 = 30 * 24 * 3600;
+
+fn suggestion_key(number: u64) -> String {
+    format!("title:{}", number)
+}
+
+/// Whether a title is too short or too generic to find the issue by, e.g. "help" or
+/// "it doesn't work".
+pub fn is_vague(title: &str) -> bool {
+    let words = WORD
+        .find_iter(title)
+        .map(|w| w.as_str().to_lowercase().replace('’', "'"))
+        .collect::<Vec<String>>();
+    words.len() < 3 || words.iter().all(|w| GENERIC.contains(&w.as_str()))
+}
+
+/// Asks the LLM for a specific, searchable title based on the report.
+async fn suggest(config: &Config, issue: &Issue) -> anyhow::Result<Option<String>> {
+    let body = issue.body.clone().unwrap_or_default().chars().take(MAX_BODY_CHARS).collect::<String>();
+    let sys_prompt = "You write clear, searchable GitHub issue titles. Name the component, the symptom and, when given, the platform or version, in under 80 characters. Reply with the title only, without quotes.";
+    let usr_prompt = format!("Current title: '{}'\nIssue body:\n{}", issue.title, body);
+    let reply = llm::chat(&config.llm.profile("title"), &format!("title_{}", issue.html_url), sys_prompt, &usr_prompt, 48)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let title = reply
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or_default()
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'' || c == '`' || c == '*')
+        .trim()
+        .chars()
+        .take(MAX_TITLE_CHARS)
+        .collect::<String>();
+    if title.is_empty() || title.eq_ignore_ascii_case(issue.title.trim()) || is_vague(&title) {
+        return Ok(None);
+    }
+    Ok(Some(title))
+}
+
+/// Suggests a clearer title for a newly opened issue with a vague one, or with
+/// `titles.rename` renames it and records the original title in the comment.
+pub async fn on_opened(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue, outbox: &Outbox) {
+    if !repo_config.titles.enabled || !is_vague(&issue.title) {
+        return;
+    }
+    let title = match suggest(config, issue).await {
+        Ok(Some(title)) => title,
+        Ok(None) => return,
+        Err(error) => {
+            log::error!("Error suggesting a title for #{}: {}", issue.number, error);
+            return;
+        }
+    };
+    log::info!("Suggesting title '{}' for #{}", title, issue.number);
+
+    if repo_config.titles.rename {
+        match mutations::rename_issue(config, octo, "titles", issue.number, &issue.title, &title).await {
+            Ok(()) => {
+                let resp = format!(
+                    "✏️ I renamed this issue to make it easier to find.\n\n* Original title: {}\n* New title: {}\n\nFeel free to edit it if it doesn't describe the problem.",
+                    issue.title, title
+                );
+                if let Err(error) = outbox.post(config, octo, issue.number, &resp).await {
+                    log::error!("Error posting title change note on #{}: {}", issue.number, error);
+                }
+                return;
+            }
+            Err(error) => permissions::check(config, octo, "rename vague issues", permissions::ISSUES_WRITE, &error).await,
+        }
+    }
+
+    store::set(config, &suggestion_key(issue.number), json!(title), Some(SUGGESTION_TTL_SECS));
+    let resp = format!(
+        "💡 A more specific title helps others find this issue. How about:\n\n> {}\n\n{} you can edit the title yourself, or a maintainer can apply it with `{} retitle`.",
+        title,
+        Author::from_login(&issue.user.login).mention(),
+        config.bot_phrase.trim_start_matches('@')
+    );
+    if let Err(error) = outbox.post(config, octo, issue.number, &resp).await {
+        log::error!("Error posting title suggestion on #{}: {}", issue.number, error);
+    }
+}
+
+/// `@flows_bot retitle [new title]`: applies the given title, or the one suggested when the
+/// issue was opened.
+pub async fn retitle(config: &Config, octo: &Octocrab, issue: &Issue, requester: &str, args: &str) -> anyhow::Result<String> {
+    let title = match args.trim() {
+        "" => match store::get(config, &suggestion_key(issue.number)).and_then(|v| v.as_str().map(|t| t.to_string())) {
+            Some(title) => title,
+            None => return Ok(format!("@{} there is no title suggestion for #{}; use `retitle <new title>`.", requester, issue.number)),
+        },
+        given => given.chars().take(MAX_TITLE_CHARS).collect(),
+    };
+    if let Err(error) = mutations::rename_issue(config, octo, requester, issue.number, &issue.title, &title).await {
+        permissions::check(config, octo, "rename issues", permissions::ISSUES_WRITE, &error).await;
+        return Err(error.into());
+    }
+    store::del(config, &suggestion_key(issue.number));
+    Ok(format!("✏️ @{} renamed this issue from \"{}\" to \"{}\".", requester, issue.title, title))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_and_generic_titles_are_vague() {
+        assert!(is_vague("help"));
+        assert!(is_vague("It doesn't work"));
+        assert!(is_vague("It doesn’t work!!"));
+        assert!(is_vague("Urgent: please help, WasmEdge error"));
+        assert!(is_vague("Not working"));
+    }
+
+    #[test]
+    fn specific_titles_are_not_vague() {
+        assert!(!is_vague("Segfault in the AOT compiler"));
+        assert!(!is_vague("wasi-nn plugin fails to load a GGUF model on arm64"));
+    }
+}
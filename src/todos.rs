@@ -0,0 +1,89 @@
+use crate::config::Config;
+use crate::{dry_run, llm, summary};
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use std::env;
+
+const HEADING: &str = "## Action items";
+const START_MARKER: &str = "<!-- flows-action-items:start -->";
+const END_MARKER: &str = "<!-- flows-action-items:end -->";
+
+struct ActionItem {
+    task: String,
+    owner: Option<String>,
+    done: bool,
+}
+
+/// Asks the LLM for the concrete action items in the thread, each attributed to the
+/// commenter who owns it.
+async fn extract(config: &Config, issue: &Issue, context: &str) -> Result<Vec<ActionItem>, String> {
+    let sys_prompt = "You extract concrete action items from GitHub issue threads. Only include tasks someone committed to or was asked to do. Reply only with JSON: {\"items\": [{\"task\": \"...\", \"owner\": \"<github login or null>\", \"done\": <bool>}]}.";
+    let usr_prompt = format!("Issue thread:\n{}", context);
+    let reply = llm::chat(&config.llm.profile("todos"), &format!("todos_{}", issue.html_url), sys_prompt, &usr_prompt, 512).await?;
+    let value = llm::extract_json(&reply).ok_or(format!("Unparseable action items: {}", reply))?;
+
+    Ok(value["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            Some(ActionItem {
+                task: item["task"].as_str()?.to_string(),
+                owner: item["owner"].as_str().map(|o| o.trim_start_matches('@').to_string()),
+                done: item["done"].as_bool().unwrap_or(false),
+            })
+        })
+        .collect())
+}
+
+fn render(items: &[ActionItem]) -> String {
+    if items.is_empty() {
+        return "_No action items found._".to_string();
+    }
+    items
+        .iter()
+        .map(|i| {
+            let check = if i.done { "x" } else { " " };
+            match &i.owner {
+                Some(owner) => format!("- [{}] {} (@{})", check, i.task, owner),
+                None => format!("- [{}] {}", check, i.task),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Replaces the bot-managed action items section of `body`, or appends one.
+fn with_section(body: &str, list: &str) -> String {
+    let section = format!("{}\n{}\n{}\n{}", START_MARKER, HEADING, list, END_MARKER);
+    match (body.find(START_MARKER), body.find(END_MARKER)) {
+        (Some(start), Some(end)) if end > start => {
+            format!("{}{}{}", &body[..start], section, &body[end + END_MARKER.len()..])
+        }
+        _ => format!("{}\n\n{}", body.trim_end(), section),
+    }
+}
+
+/// Handles the todos phrase. With `--append` (or `todos_append=true`) the list is written
+/// into the issue body; otherwise it is returned as the reply comment.
+pub async fn run(config: &Config, octo: &Octocrab, issue: &Issue, body: &str) -> anyhow::Result<Option<String>> {
+    let context = summary::issue_context(octo, &config.owner, &config.repo, issue).await?;
+    let items = extract(config, issue, &context).await.map_err(|e| anyhow::anyhow!(e))?;
+    let list = render(&items);
+
+    let append = body.contains("--append") || env::var("todos_append").unwrap_or_default() == "true";
+    if !append {
+        return Ok(Some(format!("{}\n\n{}", HEADING, list)));
+    }
+
+    let new_body = with_section(&issue.body.clone().unwrap_or_default(), &list);
+    if dry_run::intercept(config, "edit_issue", Some(issue.number), serde_json::json!({ "body": new_body })) {
+        return Ok(None);
+    }
+    octo.issues(&config.owner, &config.repo)
+        .update(issue.number)
+        .body(&new_body)
+        .send()
+        .await?;
+    log::info!("Updated action items in the body of #{}", issue.number);
+    Ok(None)
+}
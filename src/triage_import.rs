@@ -0,0 +1,150 @@
+use crate::audit;
+use crate::confidence;
+use crate::config::{self, Config};
+use crate::mutations;
+use crate::outbox;
+use crate::permissions;
+use crate::repo_config::RepoConfig;
+use github_flows::octocrab::Octocrab;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// A triage decision made by an external tool. The bot validates it and is the only
+/// writer to GitHub.
+#[derive(Deserialize)]
+pub struct Decision {
+    /// Target issue; defaults to the issue the import comment was posted on.
+    pub issue: Option<u64>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// `P0`..`P3`, applied as a `priority/Px` label.
+    pub severity: Option<String>,
+    pub duplicate_of: Option<u64>,
+    /// Name of the tool that made the decision, for the audit log.
+    pub source: Option<String>,
+    /// The tool's confidence in the decision, from 0 to 1; decisions below the configured
+    /// thresholds are queued for human triage.
+    pub confidence: Option<f64>,
+}
+
+/// Extracts the JSON decision from a fenced code block in an import comment.
+pub fn parse_comment(body: &str) -> Result<Decision, String> {
+    let start = body.find("```").ok_or("Expected a ```json code block with the decision")?;
+    let rest = &body[start + 3..];
+    let rest = rest.trim_start_matches("json");
+    let end = rest.find("```").ok_or("Unterminated code block")?;
+    serde_json::from_str::<Decision>(rest[..end].trim()).map_err(|e| format!("Invalid decision: {}", e))
+}
+
+/// Whether `login` may import decisions: maintainers and accounts listed in `import_trusted`.
+pub async fn is_trusted(config: &Config, octo: &Octocrab, login: &str) -> bool {
+    config::list_var("import_trusted").iter().any(|u| u.eq_ignore_ascii_case(login))
+        || permissions::is_maintainer(config, octo, login).await
+}
+
+/// Checks every part of the decision before anything is written.
+async fn validate(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, number: u64, decision: &Decision) -> Vec<String> {
+    let mut errors = Vec::new();
+    let issues = octo.issues(&config.owner, &config.repo);
+
+    if let Err(error) = issues.get(number).await {
+        errors.push(format!("issue #{} not found: {}", number, error));
+    }
+
+    if !decision.labels.is_empty() {
+        let route = format!("/repos/{}/{}/labels?per_page=100", config.owner, config.repo);
+        let existing = octo
+            .get::<Value, _, ()>(route, None)
+            .await
+            .ok()
+            .and_then(|v| v.as_array().cloned())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|l| l["name"].as_str().map(|s| s.to_string()))
+            .collect::<Vec<String>>();
+        for label in &decision.labels {
+            if !existing.iter().any(|l| l == label) {
+                errors.push(format!("label `{}` does not exist", label));
+            }
+        }
+    }
+
+    if let Some(severity) = &decision.severity {
+        let label = format!("priority/{}", severity.to_uppercase());
+        if !repo_config.severity.allowed_labels.contains(&label) {
+            errors.push(format!("severity `{}` is not allowed", severity));
+        }
+    }
+
+    if let Some(dup) = decision.duplicate_of {
+        if dup == number {
+            errors.push("an issue cannot duplicate itself".to_string());
+        } else if issues.get(dup).await.is_err() {
+            errors.push(format!("duplicate target #{} not found", dup));
+        }
+    }
+
+    errors
+}
+
+/// Validates and applies a decision, recording the outcome in the audit log. Returns a
+/// human-readable report of what was applied or why it was rejected.
+pub async fn apply(
+    config: &Config,
+    repo_config: &RepoConfig,
+    octo: &Octocrab,
+    actor: &str,
+    number: u64,
+    decision: &Decision,
+) -> Result<String, String> {
+    let source = decision.source.clone().unwrap_or(actor.to_string());
+    let errors = validate(config, repo_config, octo, number, decision).await;
+    if !errors.is_empty() {
+        audit::record(config, actor, "import_rejected", Some(number), json!({ "source": source, "errors": errors }));
+        return Err(errors.join("; "));
+    }
+
+    if let Some(score) = decision.confidence {
+        let features = [
+            ("labels", !decision.labels.is_empty()),
+            ("severity", decision.severity.is_some()),
+            ("duplicates", decision.duplicate_of.is_some()),
+        ];
+        if let Some((feature, _)) = features
+            .iter()
+            .find(|(feature, present)| *present && !confidence::is_confident(repo_config, feature, score))
+        {
+            let proposal = format!("{} decision from {}", feature, source);
+            confidence::abstain(config, repo_config, octo, number, feature, &proposal, score).await;
+            return Ok(format!("Confidence {:.2} is below the `{}` threshold; #{} was queued for human triage.", score, feature, number));
+        }
+    }
+
+    let mut labels = decision.labels.clone();
+    if let Some(severity) = &decision.severity {
+        labels.push(format!("priority/{}", severity.to_uppercase()));
+    }
+    if decision.duplicate_of.is_some() {
+        labels.push("duplicate".to_string());
+    }
+    let mut applied = Vec::new();
+
+    if !labels.is_empty() {
+        mutations::add_labels(config, octo, number, &labels).await.map_err(|e| e.to_string())?;
+        applied.push(format!("labels: {}", labels.join(", ")));
+    }
+    if let Some(dup) = decision.duplicate_of {
+        let note = format!("Marked as a duplicate of #{} (triage decision from {}).", dup, source);
+        outbox::reply(config, octo, number, &note).await.map_err(|e| e.to_string())?;
+        applied.push(format!("duplicate of #{}", dup));
+    }
+
+    audit::record(
+        config,
+        actor,
+        "import_applied",
+        Some(number),
+        json!({ "source": source, "labels": labels, "duplicate_of": decision.duplicate_of }),
+    );
+    Ok(format!("Applied triage decision from {} to #{}: {}", source, number, applied.join("; ")))
+}
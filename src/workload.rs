@@ -0,0 +1,123 @@
+use crate::config::Config;
+use crate::repo_config::RepoConfig;
+use crate::store;
+use github_flows::octocrab::{models::issues::Issue, Octocrab};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// How busy and how present a candidate assignee is in the repository.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Workload {
+    pub login: String,
+    /// Open issues and pull requests assigned to them.
+    pub open_assigned: u64,
+    /// Issues and pull requests they commented on within `activity_days`.
+    pub recent_activity: u64,
+}
+
+async fn count(octo: &Octocrab, query: &str) -> anyhow::Result<u64> {
+    let page = octo.search().issues_and_pull_requests(query).per_page(1).send().await?;
+    Ok(page.total_count.unwrap_or(0))
+}
+
+/// Looks up a user's workload, cached for `cache_secs` since the search API allows only a
+/// few dozen requests a minute.
+pub async fn query(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, login: &str) -> anyhow::Result<Workload> {
+    let name = format!("workload:{}", login.to_lowercase());
+    if let Some(cached) = store::get(config, &name).and_then(|v| serde_json::from_value::<Workload>(v).ok()) {
+        return Ok(cached);
+    }
+
+    let repo = format!("repo:{}/{}", config.owner, config.repo);
+    let since = (chrono::Utc::now() - chrono::Duration::days(repo_config.assignees.activity_days)).format("%Y-%m-%d");
+    let workload = Workload {
+        login: login.to_string(),
+        open_assigned: count(octo, &format!("{} is:open assignee:{}", repo, login)).await?,
+        recent_activity: count(octo, &format!("{} commenter:{} updated:>={}", repo, login, since)).await?,
+    };
+    store::set(config, &name, json!(workload), Some(repo_config.assignees.cache_secs));
+    Ok(workload)
+}
+
+/// Owners of the components the issue is labeled with, or the configured candidates when
+/// none match. Teams can't be assigned and are skipped.
+fn candidates(repo_config: &RepoConfig, issue: &Issue) -> Vec<String> {
+    let mut owners = repo_config
+        .digest
+        .components
+        .iter()
+        .filter(|c| issue.labels.iter().any(|l| l.name == c.label))
+        .flat_map(|c| c.owners.iter().cloned())
+        .collect::<Vec<String>>();
+    if owners.is_empty() {
+        owners = repo_config.assignees.candidates.clone();
+    }
+
+    let mut logins = Vec::<String>::new();
+    for owner in owners {
+        let login = owner.trim_start_matches('@').to_string();
+        if login.contains('/') || login.eq_ignore_ascii_case(&issue.user.login) {
+            continue;
+        }
+        if !logins.iter().any(|l| l.eq_ignore_ascii_case(&login)) {
+            logins.push(login);
+        }
+    }
+    logins
+}
+
+/// Ranks candidates by open assignments, fewest first. Candidates with no recent activity
+/// are probably away and go last; ties go to the more active candidate.
+fn rank(mut workloads: Vec<Workload>) -> Vec<Workload> {
+    workloads.sort_by_key(|w| (w.recent_activity == 0, w.open_assigned, std::cmp::Reverse(w.recent_activity)));
+    workloads
+}
+
+/// A "Suggested assignee" section for the triage comment, with the workloads behind the
+/// choice. Empty when suggestions are off, the issue is already assigned, or no candidate's
+/// workload could be read.
+pub async fn section(config: &Config, repo_config: &RepoConfig, octo: &Octocrab, issue: &Issue) -> String {
+    if !repo_config.assignees.suggest || !issue.assignees.is_empty() {
+        return String::new();
+    }
+
+    let mut workloads = Vec::new();
+    for login in candidates(repo_config, issue) {
+        match query(config, repo_config, octo, &login).await {
+            Ok(workload) => workloads.push(workload),
+            Err(error) => log::warn!("Could not read the workload of {}: {}", login, error),
+        }
+    }
+    let ranked = rank(workloads);
+    let Some(best) = ranked.first() else {
+        return String::new();
+    };
+
+    let days = repo_config.assignees.activity_days;
+    let lines = ranked
+        .iter()
+        .map(|w| {
+            format!(
+                "* `{}`: {} open assigned, active on {} issue(s) in the last {} days",
+                w.login, w.open_assigned, w.recent_activity, days
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    let reason = if best.recent_activity == 0 {
+        "no candidate has been active recently, so this is the least-loaded one".to_string()
+    } else {
+        format!(
+            "the least-loaded recently active candidate, with {} open assigned issue(s)",
+            best.open_assigned
+        )
+    };
+    format!(
+        "\n\n**Suggested assignee**: `{}`, {}. A maintainer can run `{} assign {}`.\n{}",
+        best.login,
+        reason,
+        config.bot_phrase.trim_start_matches('@'),
+        best.login,
+        lines
+    )
+}